@@ -1,15 +1,29 @@
+use std::collections::BTreeMap;
 use std::time::Duration;
 
+use anyhow::Context;
 use anyhow::Result;
+use gourd_lib::bailc;
+use gourd_lib::config::constraints::Condition;
+use gourd_lib::constants::CUSTOM_METRICS_ENV;
+use gourd_lib::ctx;
 use gourd_lib::experiment::Experiment;
+use gourd_lib::measurement::CgroupMetrics;
+use gourd_lib::measurement::HostInfo;
 use gourd_lib::measurement::Measurement;
+use gourd_lib::measurement::MetricValue;
 use gourd_lib::measurement::RUsage;
+use gourd_lib::measurement::Termination;
+use log::warn;
 
+use crate::analyse::replicate_sets;
+use crate::analyse::Column;
 use crate::analyse::ColumnGenerator;
 use crate::analyse::Table;
 use crate::cli::def::CsvColumn;
 use crate::cli::def::CsvFormatting;
 use crate::cli::def::GroupBy;
+use crate::cli::def::TerminationFilter;
 use crate::status::ExperimentStatus;
 use crate::status::FsState;
 use crate::status::Status;
@@ -77,6 +91,36 @@ macro_rules! rusage_metrics {
     };
 }
 
+/// Shorthand to create a column generator for a metric that is measured with
+/// the cgroup backend.
+macro_rules! cgroup_metrics {
+    ($name:expr, $field:expr) => {
+        create_column_full(
+            $name,
+            |_, x| {
+                Ok(match &x.1.fs_status.completion {
+                    FsState::Completed(Measurement {
+                        cgroup: Some(c), ..
+                    }) => $field(c).map_or("N/A".to_string(), |v: u64| format!("{v}")),
+                    _ => "N/A".to_string(),
+                })
+            },
+            |_, runs| {
+                let (total, n) = runs.iter().fold((0, 0), |(sum, count), run| {
+                    match &run.1.fs_status.completion {
+                        FsState::Completed(Measurement {
+                            cgroup: Some(c), ..
+                        }) => $field(c).map_or((sum, count), |v| (sum + v, count + 1)),
+                        _ => (sum, count),
+                    }
+                });
+
+                Ok(Some(format!("{:.2}", ((total as f64) / (n as f64)))))
+            },
+        )
+    };
+}
+
 /// Get a [`ColumnGenerator`] for every possible column of [`CsvColumn`].
 pub fn metrics_generators(col: CsvColumn) -> ColumnGenerator<(usize, Status)> {
     match col {
@@ -93,16 +137,23 @@ pub fn metrics_generators(col: CsvColumn) -> ColumnGenerator<(usize, Status)> {
         CsvColumn::Args => create_column("input args", |exp, x: &(usize, Status)| {
             Ok(format!("{:?}", &exp.runs[x.0].input.args))
         }),
+        CsvColumn::Replicate => create_column("replicate", |exp, x: &(usize, Status)| {
+            Ok(format!("{}", exp.runs[x.0].replicate))
+        }),
+        CsvColumn::Seed => create_column("seed", |exp, x: &(usize, Status)| {
+            Ok(exp.runs[x.0]
+                .seed
+                .map_or("N/A".to_string(), |seed| seed.to_string()))
+        }),
         CsvColumn::Group => create_column("group", |exp: &Experiment, x: &(usize, Status)| {
             Ok(exp.runs[x.0].group.clone().unwrap_or("N/A".to_string()))
         }),
         CsvColumn::Label => create_column("label", |_, x| {
-            Ok(x.1
-                .fs_status
-                .afterscript_completion
-                .clone()
-                .unwrap_or(Some("N/A".to_string()))
-                .unwrap_or("no label".to_string()))
+            Ok(if x.1.fs_status.labels.is_empty() {
+                "no label".to_string()
+            } else {
+                x.1.fs_status.labels.join(", ")
+            })
         }),
         CsvColumn::Afterscript => create_column("afterscript", |exp, x| {
             exp.runs[x.0]
@@ -130,6 +181,26 @@ pub fn metrics_generators(col: CsvColumn) -> ColumnGenerator<(usize, Status)> {
             },
             footer: |_, _| Ok(None),
         },
+        CsvColumn::Termination => create_column("termination", |_, x| {
+            Ok(match &x.1.fs_status.completion {
+                FsState::Completed(measurement) => measurement.termination.to_string(),
+                _ => "N/A".to_string(),
+            })
+        }),
+        CsvColumn::Signal => create_column("signal", |_, x| {
+            Ok(match &x.1.fs_status.completion {
+                FsState::Completed(Measurement {
+                    termination: Termination::Signalled { signal, .. },
+                    ..
+                }) => format!("{signal}"),
+                _ => "N/A".to_string(),
+            })
+        }),
+        CsvColumn::Host => create_column("host", |_, x| {
+            Ok(host(&x.1)
+                .and_then(|host| host.hostname.clone())
+                .unwrap_or("N/A".to_string()))
+        }),
         CsvColumn::WallTime => create_column_full(
             "wall time",
             |_, x| {
@@ -224,14 +295,129 @@ pub fn metrics_generators(col: CsvColumn) -> ColumnGenerator<(usize, Status)> {
         CsvColumn::NSignals => rusage_metrics!("signals received", |r: &RUsage| r.nsignals),
         CsvColumn::NVCsw => rusage_metrics!("voluntary context switches", |r: &RUsage| r.nvcsw),
         CsvColumn::NIvCsw => rusage_metrics!("involuntary context switches", |r: &RUsage| r.nivcsw),
+        CsvColumn::CgroupMemoryPeak => {
+            cgroup_metrics!("cgroup memory peak", |c: &CgroupMetrics| c.memory_peak)
+        }
+        CsvColumn::CgroupCpuTime => create_column_full(
+            "cgroup cpu time",
+            |_, x| {
+                Ok(match &x.1.fs_status.completion {
+                    FsState::Completed(Measurement {
+                        cgroup:
+                            Some(CgroupMetrics {
+                                cpu_usage: Some(t), ..
+                            }),
+                        ..
+                    }) => format!("{:.5}s", t.as_secs_f32()),
+                    _ => "N/A".to_string(),
+                })
+            },
+            |_, runs| {
+                let (dt, n) = runs.iter().fold((0, 0), |(sum, count), run| {
+                    match &run.1.fs_status.completion {
+                        FsState::Completed(Measurement {
+                            cgroup:
+                                Some(CgroupMetrics {
+                                    cpu_usage: Some(t), ..
+                                }),
+                            ..
+                        }) => (sum + t.as_nanos(), count + 1),
+                        _ => (sum, count),
+                    }
+                });
+
+                Ok(Some(format!(
+                    "{:.5}s",
+                    Duration::from_nanos((dt.checked_div(n).unwrap_or_default()) as u64)
+                        .as_secs_f32()
+                )))
+            },
+        ),
+        CsvColumn::CgroupIoRead => {
+            cgroup_metrics!("cgroup bytes read", |c: &CgroupMetrics| c.io_read_bytes)
+        }
+        CsvColumn::CgroupIoWrite => {
+            cgroup_metrics!("cgroup bytes written", |c: &CgroupMetrics| c.io_write_bytes)
+        }
     }
 }
 
-/// Generate a [`Table`] of metrics for this experiment.
-/// TODO: better documentation
+/// Generate a [`Column`] for a metric that the programs reported themselves,
+/// or that was extracted from their output.
+pub fn custom_metric_column(key: &str, status_tuples: &[(usize, Status)]) -> Column {
+    let values: Vec<Option<&MetricValue>> = status_tuples
+        .iter()
+        .map(|(_, status)| status.metric(key))
+        .collect();
+
+    let numbers: Vec<f64> = values
+        .iter()
+        .filter_map(|v| v.and_then(MetricValue::as_f64))
+        .collect();
+
+    Column {
+        header: Some(key.to_string()),
+        body: values
+            .iter()
+            .map(|v| v.map_or("N/A".to_string(), |v| v.to_string()))
+            .collect(),
+        footer: (!numbers.is_empty())
+            .then(|| format!("{:.2}", numbers.iter().sum::<f64>() / numbers.len() as f64)),
+    }
+}
+
+/// Generate a [`Column`] for a parameter in the arguments of the programs.
+pub fn parameter_column(
+    experiment: &Experiment,
+    key: &str,
+    status_tuples: &[(usize, Status)],
+) -> Column {
+    Column {
+        header: Some(key.to_string()),
+        body: status_tuples
+            .iter()
+            .map(|(id, _)| {
+                experiment.programs[experiment.runs[*id].program]
+                    .parameters
+                    .get(key)
+                    .cloned()
+                    .unwrap_or("N/A".to_string())
+            })
+            .collect(),
+        footer: None,
+    }
+}
+
+/// Generate a [`Column`] for a metadata value of the inputs.
+pub fn metadata_column(
+    experiment: &Experiment,
+    key: &str,
+    status_tuples: &[(usize, Status)],
+) -> Column {
+    Column {
+        header: Some(key.to_string()),
+        body: status_tuples
+            .iter()
+            .map(|(id, _)| {
+                experiment
+                    .input_metadata(*id)
+                    .and_then(|metadata| metadata.get(key))
+                    .map_or("N/A".to_string(), |value| value.to_string())
+            })
+            .collect(),
+        footer: None,
+    }
+}
+
+/// Generate a [`Table`] of metrics for this experiment, with the built-in
+/// columns in `header` followed by the program `parameters`, the input
+/// `metadata` and the custom `metrics`.
 pub fn metrics_table(
     experiment: &Experiment,
     header: Vec<CsvColumn>,
+    parameters: &[String],
+    metadata: &[String],
+    metrics: &[String],
     status_tuples: Vec<(usize, Status)>,
 ) -> Result<Table> {
     let mut metrics_table = Table {
@@ -249,6 +435,18 @@ pub fn metrics_table(
         metrics_table.append_column(column);
     }
 
+    for key in parameters {
+        metrics_table.append_column(parameter_column(experiment, key, &status_tuples));
+    }
+
+    for key in metadata {
+        metrics_table.append_column(metadata_column(experiment, key, &status_tuples));
+    }
+
+    for key in metrics {
+        metrics_table.append_column(custom_metric_column(key, &status_tuples));
+    }
+
     Ok(metrics_table)
 }
 
@@ -265,7 +463,79 @@ pub fn tables_from_command(
         CsvColumn::WallTime,
     ]);
 
-    let mut groups: Vec<Vec<(usize, Status)>> = vec![statuses.clone().into_iter().collect()];
+    for key in &fmt.metrics {
+        let reported = statuses.values().any(|status| status.metric(key).is_some());
+
+        if !reported {
+            bailc!(
+                "No run reported the metric {key:?}", ;
+                "Programs report metrics by writing `key = value` lines to the file in \
+                {CUSTOM_METRICS_ENV}, or an [extract] rule reads them from their output", ;
+                "Check the spelling of the metric, or wait for the runs to finish",
+            );
+        }
+    }
+
+    for key in &fmt.parameters {
+        if !experiment
+            .programs
+            .iter()
+            .any(|program| program.parameters.contains_key(key))
+        {
+            bailc!(
+                "No program has the parameter {key:?}", ;
+                "Parameters in the arguments of a program create a variant of it for every value", ;
+                "Check the spelling of the parameter, a subparameter is written as `x.sub`",
+            );
+        }
+    }
+
+    let filter = fmt
+        .filter
+        .as_deref()
+        .map(Condition::new)
+        .transpose()
+        .with_context(ctx!(
+          "Could not parse the filter {:?}", fmt.filter;
+          "A filter compares metadata, like \"n >= 1000 && family == 'random'\"",
+        ))?;
+
+    for key in fmt.metadata.iter().map(String::as_str).chain(
+        fmt.group_metadata
+            .iter()
+            .map(String::as_str)
+            .chain(filter.iter().flat_map(Condition::parameters)),
+    ) {
+        if !experiment
+            .inputs
+            .values()
+            .any(|input| input.metadata.values.contains_key(key))
+        {
+            bailc!(
+                "No input has the metadata {key:?}", ;
+                "Inputs get metadata from the columns of a CSV input schema, \
+                or from their `metadata` table", ;
+                "Check the spelling of the metadata",
+            );
+        }
+    }
+
+    let mut groups: Vec<Vec<(usize, Status)>> = vec![statuses
+        .clone()
+        .into_iter()
+        .filter(|(_, status)| {
+            fmt.termination.is_empty()
+                || fmt
+                    .termination
+                    .iter()
+                    .any(|filter| termination_matches(*filter, status))
+        })
+        .filter(|(id, _)| {
+            filter.as_ref().is_none_or(|filter| {
+                filter.evaluate(&metadata_values(experiment, *id)) == Some(true)
+            })
+        })
+        .collect()];
 
     for condition in fmt.group {
         let mut temp = vec![];
@@ -289,13 +559,231 @@ pub fn tables_from_command(
                     })
                     .for_each(|x| temp.push(x.to_vec()));
                 }
+                GroupBy::Termination => {
+                    // runs that ended the same way are rarely adjacent
+                    temp.append(&mut partition(g, |(_, status)| termination(status)));
+                }
+                GroupBy::Host => {
+                    temp.append(&mut partition(g, |(_, status)| {
+                        host(status).and_then(|host| host.hostname.clone())
+                    }));
+                }
+                GroupBy::Seed => {
+                    temp.append(&mut partition(g, |(id, _)| experiment.runs[*id].seed));
+                }
             }
         }
         groups = temp;
     }
 
+    for key in &fmt.group_metadata {
+        groups = groups
+            .into_iter()
+            .flat_map(|g| {
+                partition(g, |(id, _)| {
+                    experiment
+                        .input_metadata(*id)
+                        .and_then(|metadata| metadata.get(key))
+                        .cloned()
+                })
+            })
+            .collect();
+    }
+
+    for runs in &groups {
+        warn_mixed_hardware(runs);
+    }
+
     groups
         .into_iter()
-        .map(|runs| metrics_table(experiment, header.clone(), runs))
+        .map(|runs| {
+            if fmt.replicates {
+                metrics_table(
+                    experiment,
+                    header.clone(),
+                    &fmt.parameters,
+                    &fmt.metadata,
+                    &fmt.metrics,
+                    runs,
+                )
+            } else {
+                averaged_metrics_table(
+                    experiment,
+                    header.clone(),
+                    &fmt.parameters,
+                    &fmt.metadata,
+                    &fmt.metrics,
+                    runs,
+                )
+            }
+        })
         .collect()
 }
+
+/// Generate a [`Table`] of metrics where every set of replicates is collapsed
+/// into a single row holding their averages.
+///
+/// Columns that cannot be averaged show the value of the first replicate.
+pub fn averaged_metrics_table(
+    experiment: &Experiment,
+    header: Vec<CsvColumn>,
+    parameters: &[String],
+    metadata: &[String],
+    metrics: &[String],
+    status_tuples: Vec<(usize, Status)>,
+) -> Result<Table> {
+    let mut table = metrics_table(
+        experiment,
+        header.clone(),
+        parameters,
+        metadata,
+        metrics,
+        status_tuples.clone(),
+    )?;
+
+    table.body = vec![];
+
+    for set in replicate_sets(status_tuples.iter().map(|(id, _)| *id), experiment) {
+        let runs: Vec<(usize, Status)> = status_tuples
+            .iter()
+            .filter(|(id, _)| set.contains(id))
+            .cloned()
+            .collect();
+
+        let mut set_table = metrics_table(
+            experiment,
+            header.clone(),
+            parameters,
+            metadata,
+            metrics,
+            runs,
+        )?;
+        let mut row = set_table.body.swap_remove(0);
+
+        if set.len() > 1 {
+            for (cell, average) in row.iter_mut().zip(set_table.footer.unwrap_or_default()) {
+                if !average.is_empty() {
+                    *cell = average;
+                }
+            }
+
+            row[0] = set
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+
+            // The seeds are what the replicates differ in.
+            if let Some(column) = header.iter().position(|c| *c == CsvColumn::Seed) {
+                row[column + 1] = set
+                    .iter()
+                    .filter_map(|id| experiment.runs[*id].seed)
+                    .map(|seed| seed.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+
+        table.body.push(row);
+    }
+
+    Ok(table)
+}
+
+/// The metadata of the input of a run as text, as conditions compare it.
+fn metadata_values(experiment: &Experiment, run_id: usize) -> BTreeMap<String, String> {
+    experiment
+        .input_metadata(run_id)
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| (key.clone(), value.to_string()))
+        .collect()
+}
+
+/// Split runs by a key, keeping the order in which the keys first appear.
+fn partition<K: PartialEq>(
+    runs: Vec<(usize, Status)>,
+    key: impl Fn(&(usize, Status)) -> K,
+) -> Vec<Vec<(usize, Status)>> {
+    let mut keys = vec![];
+    for run in &runs {
+        if !keys.contains(&key(run)) {
+            keys.push(key(run));
+        }
+    }
+
+    keys.into_iter()
+        .map(|k| runs.iter().filter(|run| key(run) == k).cloned().collect())
+        .collect()
+}
+
+/// The machine that a run was executed on, if it has completed.
+fn host(status: &Status) -> Option<&HostInfo> {
+    match &status.fs_status.completion {
+        FsState::Completed(measurement) => measurement.host.as_ref(),
+        _ => None,
+    }
+}
+
+/// Warn if the runs in a table were executed on different hardware, which
+/// makes their timings incomparable.
+fn warn_mixed_hardware(runs: &[(usize, Status)]) {
+    let hosts: Vec<&HostInfo> = runs.iter().filter_map(|(_, status)| host(status)).collect();
+
+    let Some(first) = hosts.first() else {
+        return;
+    };
+
+    if hosts.iter().all(|host| first.same_hardware(host)) {
+        return;
+    }
+
+    let mut machines = vec![];
+    for host in &hosts {
+        let machine = format!(
+            "{} ({} cores, {})",
+            host.cpu_model.as_deref().unwrap_or("unknown cpu"),
+            host.cores.map_or("?".to_string(), |c| c.to_string()),
+            host.cpu_governor.as_deref().unwrap_or("unknown governor"),
+        );
+
+        if !machines.contains(&machine) {
+            machines.push(machine);
+        }
+    }
+
+    warn!(
+        "The runs in this table ran on different hardware: {}",
+        machines.join(", ")
+    );
+    warn!("Their timings may not be comparable, try grouping them with --group=host");
+}
+
+/// The way a run ended, if it has completed.
+fn termination(status: &Status) -> Option<Termination> {
+    match &status.fs_status.completion {
+        FsState::Completed(measurement) => Some(measurement.termination),
+        _ => None,
+    }
+}
+
+/// Check if a run ended in the way described by the filter.
+fn termination_matches(filter: TerminationFilter, status: &Status) -> bool {
+    matches!(
+        (filter, termination(status)),
+        (TerminationFilter::Exited, Some(Termination::Exited))
+            | (
+                TerminationFilter::Signalled,
+                Some(Termination::Signalled { .. })
+            )
+            | (
+                TerminationFilter::Interrupted,
+                Some(Termination::Interrupted { .. })
+            )
+            | (TerminationFilter::Killed, Some(Termination::Killed { .. }))
+    )
+}
+
+#[cfg(test)]
+#[path = "tests/csvs.rs"]
+mod tests;
@@ -12,6 +12,7 @@ use csv::Writer;
 use gourd_lib::bailc;
 use gourd_lib::experiment::Experiment;
 use gourd_lib::experiment::FieldRef;
+use gourd_lib::measurement::MetricValue;
 
 use crate::status::FsState;
 use crate::status::Status;
@@ -214,30 +215,59 @@ impl Display for Table {
     }
 }
 
+/// Split runs into sets of replicates, the runs of the same program on the
+/// same input. The order of the runs is kept.
+pub fn replicate_sets(
+    run_ids: impl IntoIterator<Item = usize>,
+    experiment: &Experiment,
+) -> Vec<Vec<usize>> {
+    let mut sets: Vec<Vec<usize>> = vec![];
+
+    for id in run_ids {
+        let run = &experiment.runs[id];
+
+        let set = sets.iter_mut().find(|set| {
+            let other = &experiment.runs[set[0]];
+            other.program == run.program
+                && other.input == run.input
+                && other.generated_from_input == run.generated_from_input
+                && other.parent == run.parent
+        });
+
+        match set {
+            Some(set) => set.push(id),
+            None => sets.push(vec![id]),
+        }
+    }
+
+    sets
+}
+
 /// Get completion times of jobs.
+///
+/// The completion time of a set of replicates is their average.
 pub fn get_completions(
     statuses: BTreeMap<usize, Status>,
     experiment: &Experiment,
 ) -> Result<BTreeMap<FieldRef, Vec<u128>>> {
     let mut completions: BTreeMap<FieldRef, Vec<u128>> = BTreeMap::new();
 
-    for (id, status) in statuses {
-        let program_name = experiment.program_from_run_id(id)?.name;
-
-        if status.is_completed() {
-            let time = match get_completion_time(status.fs_status.completion) {
-                Ok(t) => t.as_nanos(),
-                // No RUsage
-                Err(_) => continue,
-            };
-
-            if completions.contains_key(&program_name) {
-                let mut times = completions[&program_name].clone();
-                times.push(time);
-                completions.insert(program_name.clone(), times);
-            } else {
-                completions.insert(program_name.clone(), vec![time]);
-            }
+    for set in replicate_sets(statuses.keys().copied(), experiment) {
+        let program_name = experiment.program_from_run_id(set[0])?.name;
+
+        let times: Vec<u128> = set
+            .iter()
+            .filter(|id| statuses[id].is_completed())
+            // runs without RUsage are skipped
+            .filter_map(|id| get_completion_time(&statuses[id].fs_status.completion).ok())
+            .map(|t| t.as_nanos())
+            .collect();
+
+        if !times.is_empty() {
+            completions
+                .entry(program_name)
+                .or_default()
+                .push(times.iter().sum::<u128>() / times.len() as u128);
         }
     }
 
@@ -247,8 +277,89 @@ pub fn get_completions(
     Ok(completions)
 }
 
+/// Get the values of a custom metric for every program, sorted.
+///
+/// The value of a set of replicates is their average, and runs that did not
+/// report a number for the metric are skipped.
+pub fn get_custom_metric_values(
+    statuses: &BTreeMap<usize, Status>,
+    experiment: &Experiment,
+    key: &str,
+) -> Result<BTreeMap<FieldRef, Vec<f64>>> {
+    let mut values: BTreeMap<FieldRef, Vec<f64>> = BTreeMap::new();
+
+    for set in replicate_sets(statuses.keys().copied(), experiment) {
+        let program_name = experiment.program_from_run_id(set[0])?.name;
+
+        let numbers: Vec<f64> = set
+            .iter()
+            .filter_map(|id| statuses[id].metric(key)?.as_f64())
+            .collect();
+
+        if !numbers.is_empty() {
+            values
+                .entry(program_name)
+                .or_default()
+                .push(numbers.iter().sum::<f64>() / numbers.len() as f64);
+        }
+    }
+
+    for program_values in values.values_mut() {
+        program_values.sort_by(f64::total_cmp);
+    }
+
+    Ok(values)
+}
+
+/// Get the runtime in seconds, or the value of a custom `metric`, of every
+/// program against a metadata value of the inputs, sorted by that value.
+///
+/// A point is the average of the runs on inputs with the same value, runs
+/// without a number for either of them are skipped.
+pub fn get_values_by_metadata(
+    statuses: &BTreeMap<usize, Status>,
+    experiment: &Experiment,
+    key: &str,
+    metric: Option<&str>,
+) -> Result<BTreeMap<FieldRef, Vec<(f64, f64)>>> {
+    let mut points: BTreeMap<FieldRef, Vec<(f64, f64)>> = BTreeMap::new();
+
+    for (id, status) in statuses {
+        let x = experiment
+            .input_metadata(*id)
+            .and_then(|metadata| metadata.get(key))
+            .and_then(MetricValue::as_f64);
+
+        let y = match metric {
+            Some(metric) => status.metric(metric).and_then(MetricValue::as_f64),
+            None => get_completion_time(&status.fs_status.completion)
+                .ok()
+                .map(|time| time.as_secs_f64()),
+        };
+
+        if let (Some(x), Some(y)) = (x, y) {
+            let program_name = experiment.program_from_run_id(*id)?.name;
+            points.entry(program_name).or_default().push((x, y));
+        }
+    }
+
+    for program_points in points.values_mut() {
+        program_points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        *program_points = program_points
+            .chunk_by(|a, b| a.0 == b.0)
+            .map(|same| {
+                let sum: f64 = same.iter().map(|(_, y)| y).sum();
+                (same[0].0, sum / same.len() as f64)
+            })
+            .collect();
+    }
+
+    Ok(points)
+}
+
 /// Get completion time of a run.
-pub fn get_completion_time(state: FsState) -> Result<Duration> {
+pub fn get_completion_time(state: &FsState) -> Result<Duration> {
     match state {
         FsState::Completed(measured) => {
             let measured = measured.rusage;
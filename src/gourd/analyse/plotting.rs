@@ -4,10 +4,16 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use anyhow::anyhow;
+use anyhow::Context;
 use anyhow::Result;
+use gourd_lib::bailc;
+use gourd_lib::constants::CMD_STYLE;
 use gourd_lib::constants::PLOT_SIZE;
+use gourd_lib::ctx;
 use gourd_lib::experiment::Experiment;
 use gourd_lib::experiment::FieldRef;
+use gourd_lib::experiment::Run;
+use gourd_lib::measurement::Sample;
 use log::debug;
 use plotters::backend::BitMapBackend;
 use plotters::backend::DrawingBackend;
@@ -20,12 +26,20 @@ use plotters::style::register_font;
 use plotters::style::Palette;
 
 use crate::analyse::get_completions;
+use crate::analyse::get_custom_metric_values;
+use crate::analyse::get_values_by_metadata;
 use crate::cli::def::PlotType;
+use crate::cli::def::SampleMetric;
+use crate::cli::def::SamplesPer;
+use crate::cli::def::SamplesPlot;
 use crate::status::ExperimentStatus;
 
 /// Plot width, size, and data to plot.
 pub(super) type PlotData = (u128, u128, BTreeMap<FieldRef, Vec<(u128, u128)>>);
 
+/// The curves of a samples plot, every label has a curve for each of its runs.
+pub(super) type SampleSeries = BTreeMap<String, Vec<Vec<(f64, f64)>>>;
+
 /// Get data for plotting and generate plots.
 pub fn analysis_plot(
     path: &Path,
@@ -45,6 +59,106 @@ pub fn analysis_plot(
     Ok(path.into())
 }
 
+/// Plot the values of a custom metric, every program's runs sorted by it.
+pub fn metric_plot(
+    path: &Path,
+    statuses: &ExperimentStatus,
+    experiment: &Experiment,
+    key: &str,
+    plot_type: PlotType,
+) -> Result<PathBuf> {
+    let values = get_custom_metric_values(statuses, experiment, key)?;
+
+    if values.is_empty() {
+        bailc!(
+            "No run reported a number for the metric {key:?}", ;
+            "Only metrics with numeric values can be plotted", ;
+            "Check the spelling of the metric, or wait for the runs to finish",
+        );
+    }
+
+    let series = get_data_for_metric_plot(values);
+
+    match plot_type {
+        PlotType::Png => draw_curves(
+            series,
+            key,
+            ("Runs", key),
+            BitMapBackend::new(&path, PLOT_SIZE),
+        )?,
+        PlotType::Svg => draw_curves(
+            series,
+            key,
+            ("Runs", key),
+            SVGBackend::new(&path, PLOT_SIZE),
+        )?,
+    }
+
+    Ok(path.into())
+}
+
+/// Plot the runtime, or a custom `metric`, of every program against a
+/// metadata value of the inputs.
+pub fn metadata_plot(
+    path: &Path,
+    statuses: &ExperimentStatus,
+    experiment: &Experiment,
+    key: &str,
+    metric: Option<&str>,
+    plot_type: PlotType,
+) -> Result<PathBuf> {
+    let values = get_values_by_metadata(statuses, experiment, key, metric)?;
+
+    if values.is_empty() {
+        bailc!(
+            "No completed run has an input with a number for the metadata {key:?}", ;
+            "Runs can only be plotted against numeric metadata of their inputs", ;
+            "Check the spelling of the metadata, or wait for the runs to finish",
+        );
+    }
+
+    let series: SampleSeries = values
+        .into_iter()
+        .map(|(name, points)| (name, vec![points]))
+        .collect();
+
+    let y_desc = metric.unwrap_or("Seconds");
+    let caption = format!("{} by {key}", metric.unwrap_or("Runtime"));
+
+    match plot_type {
+        PlotType::Png => draw_curves(
+            series,
+            &caption,
+            (key, y_desc),
+            BitMapBackend::new(&path, PLOT_SIZE),
+        )?,
+        PlotType::Svg => draw_curves(
+            series,
+            &caption,
+            (key, y_desc),
+            SVGBackend::new(&path, PLOT_SIZE),
+        )?,
+    }
+
+    Ok(path.into())
+}
+
+/// Get the curves of a metric plot, the n-th point of a program is its n-th
+/// smallest value.
+pub fn get_data_for_metric_plot(values: BTreeMap<FieldRef, Vec<f64>>) -> SampleSeries {
+    values
+        .into_iter()
+        .map(|(name, program_values)| {
+            let curve = (1..)
+                .zip(program_values)
+                .map(|(n, value)| (n as f64, value))
+                .collect();
+
+            (name, vec![curve])
+        })
+        .collect()
+}
+
 /// Get wall clock data for cactus plot.
 pub fn get_data_for_plot(completions: BTreeMap<FieldRef, Vec<u128>>) -> PlotData {
     let max_time = completions.values().flatten().max();
@@ -90,14 +204,7 @@ where
 
     let (max_time, max_count, cactus_data) = plot_data;
 
-    register_font(
-        "sans-serif",
-        FontStyle::Normal,
-        include_bytes!("../../resources/LinLibertine_R.otf"),
-    )
-    .map_err(|_| anyhow!("Could not load the font"))?;
-
-    let style = TextStyle::from(("sans-serif", 20).into_font()).color(&BLACK);
+    let style = plot_text_style()?;
     let root = backend.into_drawing_area();
 
     root.fill(&WHITE)?;
@@ -141,6 +248,194 @@ where
     Ok(())
 }
 
+/// Read the samples recorded for a run, if it was sampled.
+pub fn read_samples(run: &Run) -> Result<Option<Vec<Sample>>> {
+    let path = run.samples_path();
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    csv::Reader::from_path(&path)
+        .and_then(|mut reader| reader.deserialize().collect::<Result<Vec<Sample>, _>>())
+        .map(Some)
+        .with_context(ctx!(
+            "Could not read the samples at {path:?}", ;
+            "The file may have been modified or the run may still be going",
+        ))
+}
+
+/// Plot the samples of resource usage for the runs of an experiment.
+pub fn samples_plot(
+    path: &Path,
+    experiment: &Experiment,
+    options: &SamplesPlot,
+) -> Result<PathBuf> {
+    let mut samples = vec![];
+
+    for (run_id, run) in experiment.runs.iter().enumerate() {
+        if !options.runs.is_empty() && !options.runs.contains(&run_id) {
+            continue;
+        }
+
+        if let Some(run_samples) = read_samples(run)? {
+            samples.push((run_id, experiment.get_program(run)?.name, run_samples));
+        }
+    }
+
+    if samples.is_empty() {
+        bailc!(
+            "No samples were recorded", ;
+            "None of the selected runs recorded their resource usage over time", ;
+            "Set {CMD_STYLE}sample_interval{CMD_STYLE:#} in the [measurement] \
+            section of the configuration and run the experiment again",
+        );
+    }
+
+    let series = get_sample_series(samples, options.metric, options.per);
+
+    match options.format {
+        PlotType::Png => {
+            make_samples_plot(series, options.metric, BitMapBackend::new(&path, PLOT_SIZE))?
+        }
+        PlotType::Svg => {
+            make_samples_plot(series, options.metric, SVGBackend::new(&path, PLOT_SIZE))?
+        }
+    }
+
+    Ok(path.into())
+}
+
+/// Get the curves of the chosen metric from the samples of runs, given as
+/// tuples of the run id, the program name and the samples.
+pub fn get_sample_series(
+    samples: Vec<(usize, String, Vec<Sample>)>,
+    metric: SampleMetric,
+    per: SamplesPer,
+) -> SampleSeries {
+    let mut series = SampleSeries::new();
+
+    for (run_id, program, run_samples) in samples {
+        let label = match per {
+            SamplesPer::Run => format!("run {run_id}"),
+            SamplesPer::Program => program,
+        };
+
+        let curve = run_samples
+            .iter()
+            .map(|sample| {
+                let value = match metric {
+                    SampleMetric::Rss => sample.rss_bytes as f64 / (1024.0 * 1024.0),
+                    SampleMetric::Cpu => sample.cpu,
+                    SampleMetric::Threads => sample.threads as f64,
+                };
+
+                (sample.time_ms as f64 / 1000.0, value)
+            })
+            .collect();
+
+        series.entry(label).or_default().push(curve);
+    }
+
+    series
+}
+
+/// Plot the curves of sampled resource usage over time.
+pub fn make_samples_plot<T>(series: SampleSeries, metric: SampleMetric, backend: T) -> Result<()>
+where
+    T: DrawingBackend,
+    <T as DrawingBackend>::ErrorType: 'static,
+{
+    debug!("Drawing a samples plot");
+
+    let y_desc = match metric {
+        SampleMetric::Rss => "Resident memory (MiB)",
+        SampleMetric::Cpu => "CPU utilisation (cores)",
+        SampleMetric::Threads => "Threads",
+    };
+
+    draw_curves(series, "Resource usage", ("Seconds", y_desc), backend)
+}
+
+/// Draw line curves, with the axes described by `(x_desc, y_desc)`.
+fn draw_curves<T>(
+    series: SampleSeries,
+    caption: &str,
+    (x_desc, y_desc): (&str, &str),
+    backend: T,
+) -> Result<()>
+where
+    T: DrawingBackend,
+    <T as DrawingBackend>::ErrorType: 'static,
+{
+    let points = || series.values().flatten().flatten();
+    let max_x = points().map(|(x, _)| *x).fold(0.0, f64::max);
+    let min_y = points().map(|(_, y)| *y).fold(0.0, f64::min);
+    let max_y = points().map(|(_, y)| *y).fold(0.0, f64::max);
+
+    let style = plot_text_style()?;
+    let root = backend.into_drawing_area();
+
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(60)
+        .caption(caption, 40)
+        .build_cartesian_2d(
+            0.0..max_x.max(1.0),
+            min_y * 1.1..(max_y * 1.1).max(min_y * 1.1 + 1.0),
+        )?;
+
+    chart
+        .configure_mesh()
+        .light_line_style(WHITE)
+        .x_label_style(style.clone())
+        .y_label_style(style.clone())
+        .label_style(style.clone())
+        .x_desc(x_desc)
+        .y_desc(y_desc)
+        .draw()?;
+
+    for (idx, (label, curves)) in (0..).zip(series) {
+        for (curve_idx, curve) in curves.into_iter().enumerate() {
+            let drawn = chart.draw_series(LineSeries::new(
+                curve,
+                Into::<ShapeStyle>::into(Palette99::pick(idx)).stroke_width(2),
+            ))?;
+
+            // Only the first curve of every label gets an entry in the legend.
+            if curve_idx == 0 {
+                drawn.label(label.clone()).legend(move |(x, y)| {
+                    Rectangle::new(
+                        [(x - 5, y - 5), (x + 5, y + 5)],
+                        Palette99::pick(idx).stroke_width(5),
+                    )
+                });
+            }
+        }
+    }
+
+    chart.configure_series_labels().label_font(style).draw()?;
+
+    root.present()?;
+
+    Ok(())
+}
+
+/// Load the font of the plots and get the style for their text.
+fn plot_text_style() -> Result<TextStyle<'static>> {
+    register_font(
+        "sans-serif",
+        FontStyle::Normal,
+        include_bytes!("../../resources/LinLibertine_R.otf"),
+    )
+    .map_err(|_| anyhow!("Could not load the font"))?;
+
+    Ok(TextStyle::from(("sans-serif", 20).into_font()).color(&BLACK))
+}
+
 #[cfg(test)]
 #[path = "tests/plotting.rs"]
 mod tests;
@@ -0,0 +1,569 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::Local;
+use gourd_lib::config::Config;
+use gourd_lib::experiment::Environment;
+use gourd_lib::experiment::RunInput;
+use gourd_lib::measurement::LimitKind;
+use tempdir::TempDir;
+
+use super::*;
+use crate::experiments::run::generate_new_run;
+use crate::experiments::ExperimentExt;
+use crate::status::FileSystemBasedStatus;
+use crate::test_utils::create_sample_experiment;
+use crate::test_utils::REAL_FS;
+
+/// An experiment with `n` runs, the replicates of one program on one input.
+fn experiment_with_runs(n: usize) -> Experiment {
+    let (mut experiment, _) = create_sample_experiment(BTreeMap::new(), BTreeMap::new());
+
+    for replicate in 0..n {
+        let mut run = generate_new_run(
+            replicate,
+            0,
+            RunInput {
+                file: None,
+                args: vec![],
+            },
+            None,
+            None,
+            Default::default(),
+            None,
+            &experiment,
+            &REAL_FS,
+        )
+        .unwrap();
+        run.replicate = replicate;
+        experiment.runs.push(run);
+    }
+
+    experiment
+}
+
+fn completed(exit_code: i32, termination: Termination) -> Status {
+    Status {
+        slurm_status: None,
+        fs_status: FileSystemBasedStatus {
+            completion: FsState::Completed(Measurement {
+                wall_micros: Duration::from_secs(1),
+                exit_code,
+                rusage: Some(crate::analyse::tests::TEST_RUSAGE),
+                termination,
+                cgroup: None,
+                custom: BTreeMap::new(),
+                host: None,
+                extracted: None,
+                labels: None,
+            }),
+            extracted: BTreeMap::new(),
+            labels: vec![],
+        },
+        slurm_file_text: None,
+    }
+}
+
+#[test]
+fn test_termination_columns_and_filter() {
+    let experiment = experiment_with_runs(3);
+
+    let mut statuses = BTreeMap::new();
+    statuses.insert(0, completed(0, Termination::Exited));
+    statuses.insert(
+        1,
+        completed(
+            139,
+            Termination::Signalled {
+                signal: 11,
+                core_dumped: true,
+            },
+        ),
+    );
+    statuses.insert(
+        2,
+        completed(
+            143,
+            Termination::Killed {
+                limit: LimitKind::WallTime,
+            },
+        ),
+    );
+
+    let tables = tables_from_command(
+        &experiment,
+        &statuses,
+        CsvFormatting {
+            group: vec![],
+            format: Some(vec![CsvColumn::Termination, CsvColumn::Signal]),
+            termination: vec![TerminationFilter::Signalled, TerminationFilter::Killed],
+            replicates: true,
+            output: None,
+            metrics: vec![],
+            parameters: vec![],
+            metadata: vec![],
+            group_metadata: vec![],
+            filter: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(1, tables.len());
+    assert_eq!(
+        vec![
+            vec![
+                "1".to_string(),
+                "terminated by signal 11 (core dumped)".to_string(),
+                "11".to_string()
+            ],
+            vec![
+                "2".to_string(),
+                "time limit exceeded".to_string(),
+                "N/A".to_string()
+            ],
+        ],
+        tables[0].body
+    );
+}
+
+#[test]
+fn test_group_by_termination() {
+    let experiment = experiment_with_runs(3);
+
+    let mut statuses = BTreeMap::new();
+    statuses.insert(0, completed(0, Termination::Exited));
+    statuses.insert(
+        1,
+        completed(
+            137,
+            Termination::Killed {
+                limit: LimitKind::Memory,
+            },
+        ),
+    );
+    statuses.insert(2, completed(1, Termination::Exited));
+
+    let tables = tables_from_command(
+        &experiment,
+        &statuses,
+        CsvFormatting {
+            group: vec![GroupBy::Termination],
+            format: Some(vec![CsvColumn::ExitCode]),
+            termination: vec![],
+            replicates: true,
+            output: None,
+            metrics: vec![],
+            parameters: vec![],
+            metadata: vec![],
+            group_metadata: vec![],
+            filter: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(2, tables.len());
+    assert_eq!(2, tables[0].body.len());
+    assert_eq!(1, tables[1].body.len());
+}
+
+#[test]
+fn test_replicates_are_averaged() {
+    let experiment = experiment_with_runs(3);
+
+    let mut statuses = BTreeMap::new();
+    for id in 0..3 {
+        let mut status = completed(0, Termination::Exited);
+        if let FsState::Completed(m) = &mut status.fs_status.completion {
+            m.wall_micros = Duration::from_secs(id as u64 + 1);
+        }
+        statuses.insert(id, status);
+    }
+
+    let fmt = CsvFormatting {
+        group: vec![],
+        format: Some(vec![CsvColumn::Replicate, CsvColumn::WallTime]),
+        termination: vec![],
+        replicates: false,
+        output: None,
+        metrics: vec![],
+        parameters: vec![],
+        metadata: vec![],
+        group_metadata: vec![],
+        filter: None,
+    };
+
+    let tables = tables_from_command(&experiment, &statuses, fmt.clone()).unwrap();
+
+    // the three replicates are averaged
+    assert_eq!(
+        vec![vec![
+            "0,1,2".to_string(),
+            "0".to_string(),
+            "2.00000s".to_string()
+        ]],
+        tables[0].body
+    );
+
+    let tables = tables_from_command(
+        &experiment,
+        &statuses,
+        CsvFormatting {
+            replicates: true,
+            ..fmt
+        },
+    )
+    .unwrap();
+
+    assert_eq!(3, tables[0].body.len());
+}
+
+#[test]
+fn test_custom_metric_columns() {
+    let experiment = experiment_with_runs(2);
+
+    let mut statuses = BTreeMap::new();
+    for (id, nodes) in [(0, 10), (1, 20)] {
+        let mut status = completed(0, Termination::Exited);
+        if let FsState::Completed(m) = &mut status.fs_status.completion {
+            m.custom
+                .insert("nodes".to_string(), MetricValue::Int(nodes));
+            m.custom
+                .insert("solver".to_string(), MetricValue::Text("dfs".to_string()));
+        }
+        statuses.insert(id, status);
+    }
+
+    let fmt = CsvFormatting {
+        group: vec![],
+        format: Some(vec![]),
+        termination: vec![],
+        replicates: true,
+        output: None,
+        metrics: vec!["nodes".to_string(), "solver".to_string()],
+        parameters: vec![],
+        metadata: vec![],
+        group_metadata: vec![],
+        filter: None,
+    };
+
+    let table = &tables_from_command(&experiment, &statuses, fmt.clone()).unwrap()[0];
+
+    assert_eq!(
+        Some(vec![
+            "run id".to_string(),
+            "nodes".to_string(),
+            "solver".to_string()
+        ]),
+        table.header
+    );
+    assert_eq!(vec!["0", "10", "dfs"], table.body[0]);
+    assert_eq!(vec!["1", "20", "dfs"], table.body[1]);
+    // only numbers are averaged
+    assert_eq!(vec!["average", "15.00", ""], table.footer.clone().unwrap());
+
+    // a metric that no run reported is most likely a typo
+    assert!(tables_from_command(
+        &experiment,
+        &statuses,
+        CsvFormatting {
+            metrics: vec!["node".to_string()],
+            parameters: vec![],
+            metadata: vec![],
+            group_metadata: vec![],
+            filter: None,
+            ..fmt
+        },
+    )
+    .is_err());
+}
+
+#[test]
+fn test_group_by_host() {
+    let experiment = experiment_with_runs(3);
+
+    let mut statuses = BTreeMap::new();
+    for (id, hostname) in [(0, "node1"), (1, "node2"), (2, "node1")] {
+        let mut status = completed(0, Termination::Exited);
+        if let FsState::Completed(m) = &mut status.fs_status.completion {
+            m.host = Some(HostInfo {
+                hostname: Some(hostname.to_string()),
+                ..Default::default()
+            });
+        }
+        statuses.insert(id, status);
+    }
+
+    let tables = tables_from_command(
+        &experiment,
+        &statuses,
+        CsvFormatting {
+            group: vec![GroupBy::Host],
+            format: Some(vec![CsvColumn::Host]),
+            termination: vec![],
+            replicates: true,
+            output: None,
+            metrics: vec![],
+            parameters: vec![],
+            metadata: vec![],
+            group_metadata: vec![],
+            filter: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(2, tables.len());
+    assert_eq!(vec!["0", "node1"], tables[0].body[0]);
+    assert_eq!(vec!["2", "node1"], tables[0].body[1]);
+    assert_eq!(vec!["1", "node2"], tables[1].body[0]);
+}
+
+#[test]
+fn test_seeds_grouped_and_averaged() {
+    let mut experiment = experiment_with_runs(4);
+    for (run, seed) in experiment.runs.iter_mut().zip([7, 8, 7, 8]) {
+        run.seed = Some(seed);
+    }
+
+    let statuses: BTreeMap<usize, Status> = (0..4)
+        .map(|id| (id, completed(0, Termination::Exited)))
+        .collect();
+
+    let fmt = |group, replicates| CsvFormatting {
+        group,
+        format: Some(vec![CsvColumn::Seed]),
+        termination: vec![],
+        replicates,
+        output: None,
+        metrics: vec![],
+        parameters: vec![],
+        metadata: vec![],
+        group_metadata: vec![],
+        filter: None,
+    };
+
+    let tables =
+        tables_from_command(&experiment, &statuses, fmt(vec![GroupBy::Seed], true)).unwrap();
+
+    assert_eq!(2, tables.len());
+    assert_eq!(vec!["0", "7"], tables[0].body[0]);
+    assert_eq!(vec!["2", "7"], tables[0].body[1]);
+    assert_eq!(vec!["1", "8"], tables[1].body[0]);
+
+    // the average of the replicates lists the seeds it is made of
+    let tables = tables_from_command(&experiment, &statuses, fmt(vec![], false)).unwrap();
+
+    assert_eq!(vec![vec!["0,1,2,3", "7,8,7,8"]], tables[0].body);
+}
+
+#[test]
+fn test_program_parameter_columns() {
+    let tempdir = TempDir::new("tests").unwrap();
+    let mut config = Config::from_file(
+        Path::new("src/gourd/experiments/tests/test_resources/config_program_parameters.toml"),
+        &REAL_FS,
+    )
+    .unwrap();
+    config.output_path = tempdir.path().to_path_buf();
+    config.metrics_path = tempdir.path().to_path_buf();
+    config.experiments_folder = tempdir.path().to_path_buf();
+
+    let experiment =
+        Experiment::from_config(&config, Local::now(), Environment::Local, &REAL_FS).unwrap();
+
+    let statuses: BTreeMap<usize, Status> = (0..experiment.runs.len())
+        .map(|id| (id, completed(0, Termination::Exited)))
+        .collect();
+
+    let fmt = |parameters: Vec<&str>| CsvFormatting {
+        group: vec![],
+        format: Some(vec![CsvColumn::Program]),
+        termination: vec![],
+        replicates: true,
+        output: None,
+        metrics: vec![],
+        parameters: parameters.into_iter().map(String::from).collect(),
+        metadata: vec![],
+        group_metadata: vec![],
+        filter: None,
+    };
+
+    let tables = tables_from_command(&experiment, &statuses, fmt(vec!["threads"])).unwrap();
+
+    // every variant is its own series, `check` has no parameters
+    let rows: Vec<Vec<String>> = tables[0].body.iter().map(|row| row[1..].to_vec()).collect();
+    assert_eq!(
+        vec![
+            vec!["solver[alpha=0.5,threads=1]", "1"],
+            vec!["solver[alpha=0.5,threads=1]", "1"],
+            vec!["check", "N/A"],
+            vec!["check", "N/A"],
+            vec!["solver[alpha=0.5,threads=2]", "2"],
+            vec!["solver[alpha=0.5,threads=2]", "2"],
+            vec!["check", "N/A"],
+            vec!["check", "N/A"],
+        ],
+        rows
+    );
+
+    assert!(tables_from_command(&experiment, &statuses, fmt(vec!["thread"])).is_err());
+}
+
+/// The experiment of `config_input_table.toml`, with a run of every input of
+/// the CSV input table.
+fn input_table_experiment(tempdir: &TempDir) -> Experiment {
+    let mut config = Config::from_file(
+        Path::new("src/gourd/experiments/tests/test_resources/config_input_table.toml"),
+        &REAL_FS,
+    )
+    .unwrap();
+    config.output_path = tempdir.path().to_path_buf();
+    config.metrics_path = tempdir.path().to_path_buf();
+    config.experiments_folder = tempdir.path().to_path_buf();
+
+    Experiment::from_config(&config, Local::now(), Environment::Local, &REAL_FS).unwrap()
+}
+
+#[test]
+fn test_input_metadata_columns_groups_and_filters() {
+    let tempdir = TempDir::new("tests").unwrap();
+    let experiment = input_table_experiment(&tempdir);
+
+    let statuses: BTreeMap<usize, Status> = (0..experiment.runs.len())
+        .map(|id| (id, completed(0, Termination::Exited)))
+        .collect();
+
+    let fmt = |group_metadata: Vec<&str>, filter: Option<&str>| CsvFormatting {
+        group: vec![],
+        format: Some(vec![CsvColumn::Args]),
+        termination: vec![],
+        replicates: true,
+        output: None,
+        metrics: vec![],
+        parameters: vec![],
+        metadata: vec!["n".to_string(), "family".to_string()],
+        group_metadata: group_metadata.into_iter().map(String::from).collect(),
+        filter: filter.map(String::from),
+    };
+    let rows = |table: &Table| -> Vec<Vec<String>> {
+        table.body.iter().map(|row| row[2..].to_vec()).collect()
+    };
+
+    // the inputs are named by the `name` column
+    let tables = tables_from_command(&experiment, &statuses, fmt(vec![], None)).unwrap();
+    assert_eq!(1, tables.len());
+    assert_eq!(
+        vec![
+            vec!["10", "grid"],
+            vec!["1000", "random"],
+            vec!["100", "grid"],
+            vec!["10", "random"]
+        ],
+        rows(&tables[0])
+    );
+
+    let tables = tables_from_command(&experiment, &statuses, fmt(vec!["family"], None)).unwrap();
+    assert_eq!(
+        vec![
+            vec![vec!["10", "grid"], vec!["100", "grid"]],
+            vec![vec!["1000", "random"], vec!["10", "random"]]
+        ],
+        tables.iter().map(rows).collect::<Vec<_>>()
+    );
+
+    let tables = tables_from_command(
+        &experiment,
+        &statuses,
+        fmt(vec![], Some("n < 1000 && family == 'random' || n >= 100")),
+    )
+    .unwrap();
+    assert_eq!(
+        vec![
+            vec!["1000", "random"],
+            vec!["100", "grid"],
+            vec!["10", "random"]
+        ],
+        rows(&tables[0])
+    );
+
+    assert!(tables_from_command(&experiment, &statuses, fmt(vec!["size"], None)).is_err());
+    assert!(tables_from_command(&experiment, &statuses, fmt(vec![], Some("size > 1"))).is_err());
+    assert!(tables_from_command(&experiment, &statuses, fmt(vec![], Some("n >"))).is_err());
+}
+
+#[test]
+fn test_values_by_metadata() {
+    let tempdir = TempDir::new("tests").unwrap();
+    let experiment = input_table_experiment(&tempdir);
+
+    let statuses: BTreeMap<usize, Status> = (0..experiment.runs.len())
+        .map(|id| {
+            let mut status = completed(0, Termination::Exited);
+
+            if let FsState::Completed(measurement) = &mut status.fs_status.completion {
+                measurement
+                    .custom
+                    .insert("cost".to_string(), MetricValue::Int(id as i64));
+            }
+
+            (id, status)
+        })
+        .collect();
+
+    // the two inputs with n = 10 are averaged
+    let values =
+        crate::analyse::get_values_by_metadata(&statuses, &experiment, "n", Some("cost")).unwrap();
+    assert_eq!(
+        BTreeMap::from([(
+            "solver".to_string(),
+            vec![(10.0, 1.5), (100.0, 2.0), (1000.0, 1.0)]
+        )]),
+        values
+    );
+
+    let values = crate::analyse::get_values_by_metadata(&statuses, &experiment, "n", None).unwrap();
+    assert_eq!(3, values["solver"].len());
+
+    // text cannot be plotted
+    let values =
+        crate::analyse::get_values_by_metadata(&statuses, &experiment, "family", None).unwrap();
+    assert!(values.is_empty());
+}
+
+#[test]
+fn test_extracted_metric_columns() {
+    let experiment = experiment_with_runs(2);
+
+    let mut statuses = BTreeMap::new();
+    for (id, objective) in [(0, 2.5), (1, 3.5)] {
+        let mut status = completed(0, Termination::Exited);
+        status
+            .fs_status
+            .extracted
+            .insert("objective".to_string(), MetricValue::Float(objective));
+        if let FsState::Completed(m) = &mut status.fs_status.completion {
+            m.custom
+                .insert("objective".to_string(), MetricValue::Float(0.0));
+        }
+        statuses.insert(id, status);
+    }
+
+    let fmt = CsvFormatting {
+        group: vec![],
+        format: Some(vec![]),
+        termination: vec![],
+        replicates: true,
+        output: None,
+        metrics: vec!["objective".to_string()],
+        parameters: vec![],
+        metadata: vec![],
+        group_metadata: vec![],
+        filter: None,
+    };
+
+    let table = &tables_from_command(&experiment, &statuses, fmt).unwrap()[0];
+
+    // an extracted value replaces the metric that the program reported
+    assert_eq!(vec!["0", "2.5"], table.body[0]);
+    assert_eq!(vec!["average", "3.00"], table.footer.clone().unwrap());
+}
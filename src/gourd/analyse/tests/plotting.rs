@@ -7,6 +7,7 @@ use gourd_lib::experiment::InternalProgram;
 use gourd_lib::experiment::Run;
 use gourd_lib::experiment::RunInput;
 use gourd_lib::measurement::Measurement;
+use gourd_lib::measurement::Termination;
 use tempdir::TempDir;
 
 use super::*;
@@ -77,8 +78,15 @@ fn test_analysis_png_plot_success() {
                 wall_micros: Duration::from_nanos(0),
                 exit_code: 0,
                 rusage: Some(crate::analyse::tests::TEST_RUSAGE),
+                termination: Termination::Exited,
+                cgroup: None,
+                custom: BTreeMap::new(),
+                host: None,
+                extracted: None,
+                labels: None,
             }),
-            afterscript_completion: None,
+            extracted: BTreeMap::new(),
+            labels: vec![],
         },
         slurm_status: Some(SlurmBasedStatus {
             completion: SlurmState::Success,
@@ -91,13 +99,20 @@ fn test_analysis_png_plot_success() {
         wall_micros: Duration::from_nanos(0),
         exit_code: 0,
         rusage: None,
+        termination: Termination::Exited,
+        cgroup: None,
+        custom: BTreeMap::new(),
+        host: None,
+        extracted: None,
+        labels: None,
     });
     statuses.insert(
         0,
         Status {
             fs_status: FileSystemBasedStatus {
                 completion: crate::status::FsState::Pending,
-                afterscript_completion: Some(Some(String::from("lol-label"))),
+                extracted: BTreeMap::new(),
+                labels: vec![String::from("lol-label")],
             },
             slurm_status: None,
             slurm_file_text: None,
@@ -122,7 +137,11 @@ fn test_analysis_png_plot_success() {
         generated_from_input: None,
         parent: None,
         limits: Default::default(),
+        enforced_limits: Default::default(),
         group: None,
+        replicate: 0,
+        seed: None,
+        env: BTreeMap::new(),
     };
     let experiment = Experiment {
         runs: vec![run.clone(), run.clone(), run.clone(), run],
@@ -137,10 +156,21 @@ fn test_analysis_png_plot_success() {
         seq: 0,
         env: Environment::Local,
         labels: Default::default(),
+        extractions: BTreeMap::new(),
+        constraints: Default::default(),
+        sampling: None,
+        pairings: vec![],
+        group_limits: BTreeMap::new(),
+        parameter_limits: BTreeMap::new(),
+        variables: BTreeMap::new(),
         slurm: None,
         num_threads: 0,
         chunks: vec![],
         groups: vec![],
+        measurement: Default::default(),
+        env_vars: BTreeMap::new(),
+        clean_env: false,
+        env_whitelist: Vec::new(),
     };
 
     let png_output_path = tmp_dir.path().join("analysis.png");
@@ -155,3 +185,67 @@ fn test_analysis_png_plot_success() {
     assert!(&svg_output_path.exists());
     assert!(fs::read(&svg_output_path).is_ok_and(|r| !r.is_empty()));
 }
+
+/// A sample with the given time and resident memory.
+fn sample(time_ms: u64, rss_mib: u64) -> Sample {
+    Sample {
+        time_ms,
+        rss_bytes: rss_mib * 1024 * 1024,
+        cpu: 1.0,
+        threads: 2,
+        processes: 1,
+    }
+}
+
+#[test]
+fn test_get_sample_series_per_program() {
+    let samples = vec![
+        (0, "a".to_string(), vec![sample(0, 1), sample(500, 3)]),
+        (1, "a".to_string(), vec![sample(0, 2)]),
+        (2, "b".to_string(), vec![sample(1000, 4)]),
+    ];
+
+    let series = get_sample_series(samples, SampleMetric::Rss, SamplesPer::Program);
+
+    let mut expected = SampleSeries::new();
+    expected.insert(
+        "a".to_string(),
+        vec![vec![(0.0, 1.0), (0.5, 3.0)], vec![(0.0, 2.0)]],
+    );
+    expected.insert("b".to_string(), vec![vec![(1.0, 4.0)]]);
+
+    assert_eq!(expected, series);
+}
+
+#[test]
+fn test_get_sample_series_per_run() {
+    let samples = vec![
+        (0, "a".to_string(), vec![sample(0, 1)]),
+        (1, "a".to_string(), vec![sample(0, 2)]),
+    ];
+
+    let series = get_sample_series(samples, SampleMetric::Threads, SamplesPer::Run);
+
+    assert_eq!(Some(&vec![vec![(0.0, 2.0)]]), series.get("run 0"));
+    assert_eq!(Some(&vec![vec![(0.0, 2.0)]]), series.get("run 1"));
+}
+
+#[test]
+fn test_make_samples_plot() {
+    let tmp_dir = TempDir::new("testing").unwrap();
+    let output_path = tmp_dir.path().join("samples.svg");
+
+    let series = get_sample_series(
+        vec![(0, "a".to_string(), vec![sample(0, 1), sample(100, 2)])],
+        SampleMetric::Cpu,
+        SamplesPer::Run,
+    );
+
+    assert!(make_samples_plot(
+        series,
+        SampleMetric::Cpu,
+        SVGBackend::new(&output_path, (300, 300))
+    )
+    .is_ok());
+    assert!(output_path.exists());
+}
@@ -0,0 +1,471 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::iter::once;
+use std::path::Path;
+
+use anyhow::Context;
+use anyhow::Error;
+use anyhow::Result;
+use chrono::Local;
+use gourd_lib::bailc;
+use gourd_lib::config::extract::OutputSource;
+use gourd_lib::config::parameters::validate_parameters;
+use gourd_lib::config::Config;
+use gourd_lib::config::UserProgram;
+use gourd_lib::ctx;
+use gourd_lib::experiment::inputs::expand_inputs;
+use gourd_lib::experiment::programs::expand_programs;
+use gourd_lib::experiment::Environment;
+use gourd_lib::experiment::Experiment;
+use gourd_lib::file_system::FileOperations;
+
+use crate::cli::printing::format_table;
+use crate::experiments::sampling::draw_sample;
+use crate::experiments::ExperimentExt;
+use crate::wrapper::verify_arch;
+
+/// Everything that was found while checking a configuration.
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    /// The mistakes that would stop the experiment from being created or run.
+    pub problems: Vec<Error>,
+
+    /// Settings that are allowed, but are likely mistakes.
+    pub warnings: Vec<String>,
+
+    /// The experiment that would be created, if there are no problems.
+    pub experiment: Option<Experiment>,
+
+    /// What the constraints left out of the experiment, if there are any.
+    pub excluded: Option<Exclusions>,
+}
+
+/// What the constraints on the parameters left out of an experiment.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Exclusions {
+    /// The pairs of a program and an input, with their parameter values.
+    pub combinations: usize,
+
+    /// The runs of those pairs, and of the programs that run after them.
+    pub runs: usize,
+}
+
+impl CheckReport {
+    /// Record the error of a check, if it failed and was not found before.
+    fn record<T>(&mut self, result: Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                let message = format!("{error:#}");
+
+                if !self.problems.iter().any(|p| format!("{p:#}") == message) {
+                    self.problems.push(error);
+                }
+
+                None
+            }
+        }
+    }
+}
+
+/// Check a configuration without creating anything.
+///
+/// Every program and input is expanded on its own so that all of their
+/// problems are found at once, not only the first one. If there are no
+/// problems, the experiment is created in memory, `fs` should be a dry run.
+///
+/// The binaries are checked against `arch`, the architecture of this machine.
+pub fn check_config(conf: &Config, arch: &str, fs: &impl FileOperations) -> CheckReport {
+    let mut report = CheckReport::default();
+
+    // The programs are expanded with the valid parameters only, so that an
+    // invalid parameter is not reported again for every program.
+    let mut valid = conf.clone();
+
+    if let Some(parameters) = &conf.parameters {
+        valid.parameters = Some(
+            parameters
+                .iter()
+                .filter(|(name, parameter)| {
+                    report
+                        .record(validate_parameters(&BTreeMap::from([(
+                            name.to_string(),
+                            (*parameter).clone(),
+                        )])))
+                        .is_some()
+                })
+                .map(|(name, parameter)| (name.clone(), parameter.clone()))
+                .collect(),
+        );
+    }
+
+    // With sampling, only the sampled variants are checked.
+    let sample = report.record(draw_sample(conf)).flatten();
+    let (program_points, input_points) = sample
+        .map(|sample| (sample.programs, sample.inputs))
+        .unwrap_or_default();
+
+    for (name, program) in &conf.programs {
+        // The dependencies are checked by name in `check_next`.
+        let mut alone = program.clone();
+        alone.next.clear();
+
+        let Some(expanded) = report.record(expand_programs(
+            &BTreeMap::from([(name.clone(), alone)]),
+            &valid,
+            &program_points,
+            fs,
+        )) else {
+            continue;
+        };
+
+        for program in expanded {
+            if let Err(error) = verify_arch(&program.binary, arch, fs) {
+                if conf.slurm.is_some() {
+                    // The nodes of the cluster may have another architecture.
+                    report.warnings.push(format!(
+                        "Program {} may not run on this machine: {}",
+                        program.name,
+                        error.root_cause()
+                    ));
+                } else {
+                    report.record(Err::<(), _>(error));
+                }
+            }
+
+            if let Some(afterscript) = &program.afterscript {
+                report.record(check_executable(&program.name, afterscript));
+            }
+        }
+    }
+
+    check_next(&conf.programs, &mut report);
+
+    for (name, input) in &conf.inputs {
+        report.record(expand_inputs(
+            &BTreeMap::from([(name.clone(), input.clone())]),
+            &conf.parameters,
+            &conf.constraints.clone().unwrap_or_default(),
+            &input_points,
+            fs,
+        ));
+    }
+
+    check_labels(conf, &mut report);
+    check_groups(conf, &mut report);
+    check_extractions(conf, &mut report);
+
+    if report.problems.is_empty() {
+        report.experiment = report.record(Experiment::from_config(
+            conf,
+            Local::now(),
+            Environment::Local,
+            fs,
+        ));
+    }
+
+    if let (Some(experiment), Some(_)) = (&report.experiment, &conf.constraints) {
+        let mut unconstrained = conf.clone();
+        unconstrained.constraints = None;
+
+        report.excluded =
+            Experiment::from_config(&unconstrained, Local::now(), Environment::Local, fs)
+                .ok()
+                .map(|all| Exclusions {
+                    combinations: combinations(&all) - combinations(experiment),
+                    runs: all.runs.len() - experiment.runs.len(),
+                });
+    }
+
+    report
+}
+
+/// How many pairs of a program and an input an experiment runs.
+fn combinations(experiment: &Experiment) -> usize {
+    experiment
+        .runs
+        .iter()
+        .filter(|run| run.parent.is_none())
+        .map(|run| (run.program, &run.generated_from_input))
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+/// Check that the `next` programs exist and do not form a cycle.
+fn check_next(programs: &BTreeMap<String, UserProgram>, report: &mut CheckReport) {
+    for (name, program) in programs {
+        for child in &program.next {
+            if !programs.contains_key(child) {
+                report.record(unknown_next(name, child));
+            }
+        }
+    }
+
+    let mut done = Vec::new();
+
+    for name in programs.keys() {
+        let mut path = Vec::new();
+        find_cycles(name, programs, &mut path, &mut done, report);
+    }
+}
+
+/// Fail because program `name` runs `child`, which does not exist.
+fn unknown_next(name: &str, child: &str) -> Result<()> {
+    bailc!(
+        "Incorrect program dependency: {}", child;
+        "Program {child} runs on {name}, but there's no program called {child}!",;
+        "Please make sure all programs exist and spelling is correct",
+    );
+}
+
+/// Fail because the programs of `cycle` depend on each other.
+fn cyclic_next(cycle: &str) -> Result<()> {
+    bailc!(
+        "A cycle was found in the program dependencies: {cycle}", ;
+        "The `next` field in the program definitions created a circular dependency", ;
+        "Remove one of the programs from the `next` of another",
+    );
+}
+
+/// A depth first search that reports every cycle in the `next` programs.
+///
+/// `path` holds the programs that lead to `name`, `done` the programs whose
+/// dependencies were all searched.
+fn find_cycles<'a>(
+    name: &'a str,
+    programs: &'a BTreeMap<String, UserProgram>,
+    path: &mut Vec<&'a str>,
+    done: &mut Vec<&'a str>,
+    report: &mut CheckReport,
+) {
+    if done.contains(&name) {
+        return;
+    }
+
+    if let Some(start) = path.iter().position(|p| *p == name) {
+        let cycle = [&path[start..], &[name]].concat().join(" -> ");
+
+        report.record(cyclic_next(&cycle));
+
+        return;
+    }
+
+    path.push(name);
+
+    for child in programs.get(name).map(|p| p.next.as_slice()).unwrap_or(&[]) {
+        if let Some((child, _)) = programs.get_key_value(child) {
+            find_cycles(child, programs, path, done, report);
+        }
+    }
+
+    path.pop();
+    done.push(name);
+}
+
+/// Check that the afterscript of a program can be executed.
+fn check_executable(name: &str, afterscript: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        use gourd_lib::constants::CMD_DOC_STYLE;
+
+        let mode = afterscript
+            .metadata()
+            .with_context(ctx!(
+              "Could not find the afterscript {afterscript:?} of program {name}", ;
+              "Ensure that the file exists and you have permissions to access it",
+            ))?
+            .permissions()
+            .mode();
+
+        if mode & 0o111 == 0 {
+            bailc!(
+                "The afterscript of program {name} is not executable", ;
+                "The afterscript {afterscript:?} is run after every run of {name}", ;
+                "Try {CMD_DOC_STYLE} chmod +x {afterscript:?} {CMD_DOC_STYLE:#}",
+            );
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = (name, afterscript);
+
+    Ok(())
+}
+
+/// Look for labels that cannot work as intended.
+fn check_labels(conf: &Config, report: &mut CheckReport) {
+    let Some(labels) = conf.labels.as_ref().filter(|l| !l.is_empty()) else {
+        return;
+    };
+
+    if labels.values().any(|l| l.regex.is_some())
+        && conf.programs.values().all(|p| p.afterscript.is_none())
+    {
+        report.warnings.push(
+            "There are labels that match the afterscript output \
+            but no program has an afterscript, so they are never assigned"
+                .to_string(),
+        );
+    }
+
+    for (name, label) in labels {
+        report.record(label.validate(name));
+
+        if label.regex.as_ref().is_some_and(|regex| regex.is_match("")) {
+            report.warnings.push(format!(
+                "The regex of label {name} matches an empty afterscript output, \
+                so it is assigned to every run with an afterscript"
+            ));
+        }
+    }
+}
+
+/// Warn about groups with resource limits that no input is in.
+fn check_groups(conf: &Config, report: &mut CheckReport) {
+    for name in conf.groups.iter().flat_map(BTreeMap::keys) {
+        if !conf
+            .inputs
+            .values()
+            .any(|input| input.group.as_ref() == Some(name))
+        {
+            report.warnings.push(format!(
+                "No input is in the group {name}, so its resource limits are not used"
+            ));
+        }
+    }
+}
+
+/// Check that the extractions can capture a value and have an output to read.
+fn check_extractions(conf: &Config, report: &mut CheckReport) {
+    for (name, extraction) in conf.extractions.iter().flatten() {
+        report.record(extraction.validate(name));
+
+        if extraction.source == OutputSource::Afterscript
+            && conf.programs.values().all(|p| p.afterscript.is_none())
+        {
+            report.warnings.push(format!(
+                "Extraction {name} reads the afterscript output, \
+                but no program has an afterscript"
+            ));
+        }
+    }
+}
+
+/// A table of how many runs the experiment has per program and per group.
+pub fn run_summary(experiment: &Experiment) -> String {
+    let mut programs: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut groups: BTreeMap<&str, usize> = BTreeMap::new();
+
+    for run in &experiment.runs {
+        *programs
+            .entry(&experiment.programs[run.program].name)
+            .or_default() += 1;
+
+        if let Some(group) = &run.group {
+            *groups.entry(group).or_default() += 1;
+        }
+    }
+
+    let table = |header: &str, counts: BTreeMap<&str, usize>| {
+        let mut rows = vec![vec![header.to_string(), "runs".to_string()]];
+        rows.extend(
+            counts
+                .into_iter()
+                .map(|(name, count)| vec![name.to_string(), count.to_string()]),
+        );
+
+        format_table(rows)
+    };
+
+    let mut summary = format!(
+        "The experiment would have {} runs\n\n{}",
+        experiment.runs.len(),
+        table("program", programs)
+    );
+
+    if !groups.is_empty() {
+        summary.push_str("\n\n");
+        summary.push_str(&table("group", groups));
+    }
+
+    summary
+}
+
+/// A sentence about what the constraints left out of the experiment.
+pub fn exclusion_summary(excluded: &Exclusions) -> String {
+    format!(
+        "The constraints excluded {} combinations of programs and inputs, {} runs",
+        excluded.combinations, excluded.runs
+    )
+}
+
+/// The most inputs that [`pairing_matrix`] shows as columns.
+const MATRIX_COLUMNS: usize = 8;
+
+/// A table of which inputs the programs run on.
+///
+/// The rows are the programs that do not run after another program. With
+/// few inputs, there is a column for every input, otherwise only the number
+/// of inputs of every program.
+pub fn pairing_matrix(experiment: &Experiment) -> String {
+    let mut pairs: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+
+    for program in &experiment.programs {
+        pairs.entry(&program.name).or_default();
+    }
+
+    for run in &experiment.runs {
+        if run.parent.is_some() {
+            // A program that runs after another is not a row.
+            pairs.remove(experiment.programs[run.program].name.as_str());
+        }
+    }
+
+    for run in experiment.runs.iter().filter(|run| run.parent.is_none()) {
+        if let (Some(inputs), Some(input)) = (
+            pairs.get_mut(experiment.programs[run.program].name.as_str()),
+            &run.generated_from_input,
+        ) {
+            inputs.insert(input);
+        }
+    }
+
+    let rows: Vec<Vec<String>> = if experiment.inputs.len() <= MATRIX_COLUMNS {
+        let header = once("program".to_string())
+            .chain(experiment.inputs.keys().cloned())
+            .collect();
+
+        once(header)
+            .chain(pairs.iter().map(|(program, inputs)| {
+                once(program.to_string())
+                    .chain(experiment.inputs.keys().map(|input| {
+                        if inputs.contains(input.as_str()) {
+                            "x".to_string()
+                        } else {
+                            String::new()
+                        }
+                    }))
+                    .collect()
+            }))
+            .collect()
+    } else {
+        let header = vec!["program".to_string(), "inputs".to_string()];
+
+        once(header)
+            .chain(pairs.iter().map(|(program, inputs)| {
+                vec![
+                    program.to_string(),
+                    format!("{} of {}", inputs.len(), experiment.inputs.len()),
+                ]
+            }))
+            .collect()
+    };
+
+    format!("The programs run on these inputs\n\n{}", format_table(rows))
+}
+
+#[cfg(test)]
+#[path = "tests/mod.rs"]
+mod tests;
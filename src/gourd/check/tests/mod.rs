@@ -0,0 +1,350 @@
+use std::fs;
+
+use gourd_lib::file_system::FileSystemInteractor;
+use tempdir::TempDir;
+
+use super::*;
+
+/// A file system interactor that does not touch files.
+const DRY_FS: FileSystemInteractor = FileSystemInteractor { dry_run: true };
+
+/// Write a configuration into a new temporary folder, together with an
+/// executable script `ok.sh`, a text file `text` and a non-executable script
+/// `after.sh`.
+///
+/// `{dir}` in the configuration is replaced by the folder.
+fn write_config(config: &str) -> (Config, TempDir) {
+    let dir = TempDir::new("check").unwrap();
+    let path = |name: &str| dir.path().join(name);
+
+    fs::write(path("ok.sh"), "#!/bin/sh\necho ok\n").unwrap();
+    fs::write(path("text"), "not a program").unwrap();
+    fs::write(path("after.sh"), "#!/bin/sh\n").unwrap();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(path("ok.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::set_permissions(path("after.sh"), fs::Permissions::from_mode(0o644)).unwrap();
+    }
+
+    let config = format!(
+        r#"
+        output_path = "{{dir}}/output"
+        metrics_path = "{{dir}}/metrics"
+        experiments_folder = "{{dir}}/experiments"
+        {config}
+        "#
+    )
+    .replace("{dir}", dir.path().to_str().unwrap());
+
+    fs::write(path("gourd.toml"), config).unwrap();
+
+    let conf = Config::from_file(&path("gourd.toml"), &DRY_FS).unwrap();
+
+    (conf, dir)
+}
+
+/// The messages of every problem in a report.
+fn problems(report: &CheckReport) -> Vec<String> {
+    report.problems.iter().map(|p| format!("{p:#}")).collect()
+}
+
+#[test]
+fn test_valid_config_is_summarised() {
+    let (conf, dir) = write_config(
+        r#"
+        [program.a]
+        binary = "{dir}/ok.sh"
+        next = ["b"]
+
+        [program.b]
+        binary = "{dir}/ok.sh"
+
+        [input.x]
+        arguments = ["1"]
+        group = "small"
+
+        [input.y]
+        arguments = ["2"]
+        "#,
+    );
+
+    let report = check_config(&conf, "x86_64", &DRY_FS);
+
+    assert!(report.problems.is_empty(), "{:?}", problems(&report));
+    assert!(report.warnings.is_empty());
+
+    let summary = run_summary(report.experiment.as_ref().unwrap());
+    assert!(summary.starts_with("The experiment would have 4 runs"));
+    assert!(summary.contains("a       | 2"), "{summary}");
+    assert!(summary.contains("b       | 2"), "{summary}");
+    assert!(summary.contains("small | 1"), "{summary}");
+
+    // Nothing was created.
+    assert!(!dir.path().join("output").exists());
+    assert!(!dir.path().join("experiments").exists());
+}
+
+#[test]
+fn test_every_problem_is_reported() {
+    let (conf, _dir) = write_config(
+        r#"
+        [program.text]
+        binary = "{dir}/text"
+
+        [program.a]
+        binary = "{dir}/ok.sh"
+        next = ["b", "missing"]
+
+        [program.b]
+        binary = "{dir}/ok.sh"
+        next = ["a"]
+
+        [program.noreps]
+        binary = "{dir}/ok.sh"
+        repetitions = 0
+
+        [input.x]
+        arguments = ["param|size"]
+
+        [parameter.size]
+        "#,
+    );
+
+    let report = check_config(&conf, "x86_64", &DRY_FS);
+    let problems = problems(&report);
+
+    assert!(report.experiment.is_none());
+    #[cfg(target_os = "linux")]
+    assert!(
+        problems
+            .iter()
+            .any(|p| p.contains("Could not parse the file as ELF")),
+        "{problems:?}"
+    );
+    assert!(problems.iter().any(|p| p.contains("dependency: missing")));
+    assert!(problems.iter().any(|p| p.contains("a -> b -> a")));
+    assert!(problems
+        .iter()
+        .any(|p| p.contains("noreps has no repetitions")));
+    assert!(problems.iter().any(|p| p.contains("currently has none")));
+
+    // The invalid parameter is found once, although the input also uses it.
+    assert_eq!(
+        problems
+            .iter()
+            .filter(|p| p.contains("currently has none"))
+            .count(),
+        1
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_afterscript_must_be_executable() {
+    let (conf, _dir) = write_config(
+        r#"
+        [program.a]
+        binary = "{dir}/ok.sh"
+        afterscript = "{dir}/after.sh"
+
+        [input.x]
+        arguments = ["1"]
+        "#,
+    );
+
+    let report = check_config(&conf, "x86_64", &DRY_FS);
+
+    assert_eq!(report.problems.len(), 1, "{:?}", problems(&report));
+    assert!(problems(&report)[0].contains("afterscript of program a is not executable"));
+}
+
+#[test]
+fn test_label_warnings() {
+    let (conf, _dir) = write_config(
+        r#"
+        [program.a]
+        binary = "{dir}/ok.sh"
+
+        [input.x]
+        arguments = ["1"]
+
+        [label.anything]
+        regex = ".*"
+        "#,
+    );
+
+    let report = check_config(&conf, "x86_64", &DRY_FS);
+
+    assert!(report.problems.is_empty(), "{:?}", problems(&report));
+    assert!(report.experiment.is_some());
+    assert_eq!(report.warnings.len(), 2);
+    assert!(report.warnings[0].contains("no program has an afterscript"));
+    assert!(report.warnings[1].contains("label anything matches"));
+}
+
+#[test]
+fn test_cycles_are_found_once() {
+    let (conf, _dir) = write_config(
+        r#"
+        [program.a]
+        binary = "{dir}/ok.sh"
+        next = ["b"]
+
+        [program.b]
+        binary = "{dir}/ok.sh"
+        next = ["c"]
+
+        [program.c]
+        binary = "{dir}/ok.sh"
+        next = ["a"]
+
+        [program.d]
+        binary = "{dir}/ok.sh"
+        next = ["d"]
+
+        [input.x]
+        arguments = ["1"]
+        "#,
+    );
+
+    let report = check_config(&conf, "x86_64", &DRY_FS);
+    let problems = problems(&report);
+
+    assert_eq!(problems.len(), 2, "{problems:?}");
+    assert!(problems[0].contains("a -> b -> c -> a"));
+    assert!(problems[1].contains("d -> d"));
+}
+
+#[test]
+fn test_constraints_are_summarised() {
+    let (conf, _dir) = write_config(
+        r#"
+        [program.a]
+        binary = "{dir}/ok.sh"
+        arguments = ["-t", "param|threads"]
+        next = ["b"]
+
+        [program.b]
+        binary = "{dir}/ok.sh"
+
+        [input.x]
+        arguments = ["param|size"]
+
+        [parameter.threads]
+        values = ["1", "4"]
+
+        [parameter.size]
+        values = ["2", "8"]
+
+        [constraints]
+        exclude = ["threads > size"]
+        "#,
+    );
+
+    let report = check_config(&conf, "x86_64", &DRY_FS);
+
+    assert!(report.problems.is_empty(), "{:?}", problems(&report));
+
+    // a[threads=4] is not run on the input of size 2, nor is b after it
+    assert_eq!(6, report.experiment.as_ref().unwrap().runs.len());
+    assert_eq!(
+        Some(Exclusions {
+            combinations: 1,
+            runs: 2
+        }),
+        report.excluded
+    );
+    assert!(exclusion_summary(&report.excluded.unwrap()).contains("excluded 1 combinations"));
+}
+
+#[test]
+fn test_constraints_use_known_parameters() {
+    let (conf, _dir) = write_config(
+        r#"
+        [program.a]
+        binary = "{dir}/ok.sh"
+
+        [input.x]
+        arguments = ["1"]
+
+        [constraints]
+        require = ["threads <= cores"]
+        "#,
+    );
+
+    let report = check_config(&conf, "x86_64", &DRY_FS);
+
+    assert!(problems(&report)[0].contains("Unknown parameter threads"));
+}
+
+#[test]
+fn test_pairing_matrix() {
+    let (conf, _dir) = write_config(
+        r#"
+        [program.a]
+        binary = "{dir}/ok.sh"
+        inputs = ["x*"]
+        next = ["b"]
+        [program.b]
+        binary = "{dir}/ok.sh"
+        [program.c]
+        binary = "{dir}/ok.sh"
+        exclude_groups = ["hard"]
+
+        [input.x1]
+        arguments = ["1"]
+        group = "hard"
+        [input.x2]
+        arguments = ["2"]
+        [input.y]
+        arguments = ["3"]
+        "#,
+    );
+
+    let report = check_config(&conf, "x86_64", &DRY_FS);
+    assert!(report.problems.is_empty(), "{:?}", problems(&report));
+
+    let matrix = pairing_matrix(report.experiment.as_ref().unwrap());
+    let rows: Vec<&str> = matrix.lines().skip(2).map(str::trim_end).collect();
+
+    // `b` runs after `a`, so it is not a row
+    assert_eq!(
+        vec![
+            "program | x1 | x2 | y",
+            "a       | x  | x  |",
+            "c       |    | x  | x"
+        ],
+        rows
+    );
+}
+
+#[test]
+fn test_unused_groups_are_reported() {
+    let (conf, _dir) = write_config(
+        r#"
+        [program.a]
+        binary = "{dir}/ok.sh"
+
+        [input.x]
+        arguments = ["1"]
+        group = "easy"
+
+        [group.easy]
+        resource_limits = { cpus = 2 }
+
+        [group.hrad]
+        resource_limits = { time_limit = "1h" }
+        "#,
+    );
+
+    let report = check_config(&conf, "x86_64", &DRY_FS);
+
+    assert!(report.problems.is_empty(), "{:?}", problems(&report));
+    assert_eq!(
+        vec!["No input is in the group hrad, so its resource limits are not used"],
+        report.warnings
+    );
+}
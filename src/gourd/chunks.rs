@@ -5,6 +5,7 @@ use anyhow::Context;
 use anyhow::Result;
 use gourd_lib::bailc;
 use gourd_lib::config::slurm::ResourceLimits;
+use gourd_lib::config::termination::GracefulTermination;
 use gourd_lib::experiment::Experiment;
 use gourd_lib::experiment::Run;
 use log::debug;
@@ -25,6 +26,12 @@ pub struct Chunk {
     ///
     /// This field is immutable.
     resource_limits: ResourceLimits,
+
+    /// How the runs of this chunk are asked to stop before the time limit.
+    ///
+    /// This field is immutable.
+    #[serde(default)]
+    termination: Option<GracefulTermination>,
 }
 
 impl Chunk {
@@ -34,6 +41,12 @@ impl Chunk {
     pub fn limits(&self) -> ResourceLimits {
         self.resource_limits
     }
+
+    /// How the runs of this chunk are asked to stop before the time limit, if
+    /// at all.
+    pub fn termination(&self) -> Option<&GracefulTermination> {
+        self.termination.as_ref()
+    }
 }
 
 impl PartialOrd for Chunk {
@@ -98,8 +111,14 @@ impl Chunkable for Experiment {
             );
         }
 
+        let termination = |run: &Run| {
+            self.programs
+                .get(run.program)
+                .and_then(|p| p.termination.clone())
+        };
+
         let separated = runs
-            .chunk_by(|a, b| a.1.limits == b.1.limits)
+            .chunk_by(|a, b| a.1.limits == b.1.limits && termination(a.1) == termination(b.1))
             .collect::<Vec<&[(usize, &Run)]>>();
 
         for c in separated {
@@ -107,6 +126,7 @@ impl Chunkable for Experiment {
                 chunks.push(Chunk {
                     runs: f.iter().map(|(i, _)| *i).collect(),
                     resource_limits: f[0].1.limits,
+                    termination: termination(f[0].1),
                 });
             }
         }
@@ -64,6 +64,26 @@ pub enum RunSubcommand {
     Slurm {},
 }
 
+/// Arguments supplied with the `schema` command.
+#[derive(Args, Debug, Clone, Copy)]
+pub struct SchemaStruct {
+    /// The file to describe.
+    #[command(subcommand)]
+    pub subcommand: SchemaSubcommand,
+}
+
+/// Enum for subcommands of the `schema` subcommand.
+#[derive(Subcommand, Debug, Copy, Clone)]
+pub enum SchemaSubcommand {
+    /// Print the JSON Schema of gourd.toml.
+    #[command()]
+    Config,
+
+    /// Print the JSON Schema of the experiment lockfiles.
+    #[command()]
+    Experiment,
+}
+
 /// Arguments for the Rerun command.
 #[derive(Args, Debug, Clone)]
 pub struct RerunOptions {
@@ -192,11 +212,70 @@ pub enum AnalyseSubcommand {
         /// If you want to save to a specific file
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Plot a metric that the programs reported themselves, instead of
+        /// a cactus plot.
+        #[arg(short, long)]
+        metric: Option<String>,
+
+        /// Plot the runtime, or the `metric`, against this metadata of the
+        /// inputs, like their size.
+        #[arg(short, long)]
+        by: Option<String>,
     },
 
     /// Generate tables for the metrics of the runs in this experiment.
     #[command()]
     Table(CsvFormatting),
+
+    /// Plot the resource usage over time sampled during the runs.
+    #[command()]
+    Samples(SamplesPlot),
+}
+
+/// Plot the samples of resource usage recorded by the wrapper.
+#[derive(Args, Debug, Clone)]
+pub struct SamplesPlot {
+    /// Which resource to plot.
+    #[arg(short, long, default_value = "rss")]
+    pub metric: SampleMetric,
+
+    /// Colour the curves by run or by program.
+    #[arg(short, long, default_value = "run")]
+    pub per: SamplesPer,
+
+    /// Only plot these runs [default: all runs with samples].
+    #[arg(short, long, value_delimiter = ',', num_args = 1..)]
+    pub runs: Vec<usize>,
+
+    /// What file format to make the plot in.
+    /// Options are `png` (default), `svg`
+    #[arg(short, long, default_value = "png")]
+    pub format: PlotType,
+
+    /// If you want to save to a specific file
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// The sampled resources that can be plotted.
+#[derive(ValueEnum, Debug, Clone, PartialEq, Eq, Copy)]
+pub enum SampleMetric {
+    /// Resident memory of the process tree.
+    Rss,
+    /// CPU utilisation of the process tree, in cores.
+    Cpu,
+    /// Number of threads in the process tree.
+    Threads,
+}
+
+/// How the curves of a samples plot are distinguished.
+#[derive(ValueEnum, Debug, Clone, PartialEq, Eq, Copy)]
+pub enum SamplesPer {
+    /// One curve and colour per run.
+    Run,
+    /// One colour per program, with a curve for each of its runs.
+    Program,
 }
 
 /// Construct a CSV by specifying desired columns and any grouping of runs.
@@ -212,6 +291,36 @@ pub struct CsvFormatting {
     #[arg(short, long, value_delimiter = ',', num_args = 1..)]
     pub format: Option<Vec<CsvColumn>>,
 
+    /// Add columns for metrics that the programs reported themselves.
+    #[arg(short, long, value_delimiter = ',', num_args = 1..)]
+    pub metrics: Vec<String>,
+
+    /// Add columns for the values of parameters in the program arguments.
+    #[arg(short, long, value_delimiter = ',', num_args = 1..)]
+    pub parameters: Vec<String>,
+
+    /// Add columns for the metadata of the inputs.
+    #[arg(long, value_delimiter = ',', num_args = 1..)]
+    pub metadata: Vec<String>,
+
+    /// Group together runs whose inputs have the same values for this
+    /// metadata.
+    #[arg(long, value_delimiter = ',', num_args = 1..)]
+    pub group_metadata: Vec<String>,
+
+    /// Only include runs whose inputs have metadata that matches this
+    /// condition, like "n >= 1000 && family == 'random'".
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Only include runs that ended in one of these ways.
+    #[arg(short, long, value_delimiter = ',', num_args = 1..)]
+    pub termination: Vec<TerminationFilter>,
+
+    /// Show every replicate in its own row, instead of their average.
+    #[arg(long)]
+    pub replicates: bool,
+
     /// If you want to save to a specific file
     #[arg(short, long)]
     pub output: Option<PathBuf>,
@@ -226,6 +335,25 @@ pub enum GroupBy {
     Input,
     /// Group together runs that have the same input group.
     Group,
+    /// Group together runs that ended in the same way.
+    Termination,
+    /// Group together runs that were executed on the same host.
+    Host,
+    /// Group together runs that have the same seed.
+    Seed,
+}
+
+/// The ways in which a run can end, for filtering tables.
+#[derive(ValueEnum, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Copy)]
+pub enum TerminationFilter {
+    /// The program exited by itself, with any exit code.
+    Exited,
+    /// The program was terminated by a signal.
+    Signalled,
+    /// The program stopped after the termination signal before its time limit.
+    Interrupted,
+    /// The program was killed for exceeding a resource limit.
+    Killed,
 }
 
 /// Enum for the columns that can be included in the CSV.
@@ -237,6 +365,10 @@ pub enum CsvColumn {
     File,
     /// The arguments that were passed to the program.
     Args,
+    /// The index of the run among the repetitions of its program and input.
+    Replicate,
+    /// The seed of the run.
+    Seed,
     /// The group that the run was in.
     Group,
     /// The afterscript that was run.
@@ -249,6 +381,12 @@ pub enum CsvColumn {
     FsStatus,
     /// The run process exit code
     ExitCode,
+    /// Why the run process ended
+    Termination,
+    /// The signal that terminated the run process
+    Signal,
+    /// The host that the run was executed on
+    Host,
     /// Process wall time
     WallTime,
     /// Process user time
@@ -283,6 +421,14 @@ pub enum CsvColumn {
     NVCsw,
     /// Involuntary context switches
     NIvCsw,
+    /// Peak memory of the cgroup, in bytes
+    CgroupMemoryPeak,
+    /// CPU time of the cgroup
+    CgroupCpuTime,
+    /// Bytes read by the cgroup
+    CgroupIoRead,
+    /// Bytes written by the cgroup
+    CgroupIoWrite,
 }
 
 /// Enum for the output format of the analysis.
@@ -308,11 +454,16 @@ impl PlotType {
 
 /// Enum for root-level `gourd` commands.
 #[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum GourdCommand {
     /// Create an experiment from configuration and run it.
     #[command()]
     Run(RunStruct),
 
+    /// Check the configuration for mistakes without creating anything.
+    #[command()]
+    Check,
+
     /// Set up a template of an experiment configuration.
     #[command()]
     Init(InitStruct),
@@ -337,6 +488,10 @@ pub enum GourdCommand {
     #[command()]
     Analyse(AnalyseStruct),
 
+    /// Print a JSON Schema of the configuration or of the experiment files.
+    #[command()]
+    Schema(SchemaStruct),
+
     /// Print information about the version.
     #[command()]
     Version,
@@ -11,6 +11,8 @@ use clap::FromArgMatches;
 use colog::default_builder;
 use colog::formatter;
 use gourd_lib::bailc;
+use gourd_lib::config::limits::EnforcedLimits;
+use gourd_lib::config::slurm::ResourceLimits;
 use gourd_lib::config::Config;
 use gourd_lib::constants::CMD_DOC_STYLE;
 use gourd_lib::constants::CMD_STYLE;
@@ -21,13 +23,15 @@ use gourd_lib::constants::TERTIARY_STYLE;
 use gourd_lib::ctx;
 use gourd_lib::experiment::Environment;
 use gourd_lib::experiment::Experiment;
-use gourd_lib::file_system::FileOperations;
 use gourd_lib::file_system::FileSystemInteractor;
+use gourd_lib::schema::config_schema;
+use gourd_lib::schema::experiment_schema;
 use indicatif::MultiProgress;
 use indicatif_log_bridge::LogWrapper;
 use log::debug;
 use log::info;
 use log::trace;
+use log::warn;
 use log::LevelFilter;
 
 use super::def::ContinueStruct;
@@ -36,6 +40,13 @@ use super::log::LogTokens;
 use super::printing::get_styles;
 use crate::analyse::csvs::tables_from_command;
 use crate::analyse::plotting::analysis_plot;
+use crate::analyse::plotting::metadata_plot;
+use crate::analyse::plotting::metric_plot;
+use crate::analyse::plotting::samples_plot;
+use crate::check::check_config;
+use crate::check::exclusion_summary;
+use crate::check::pairing_matrix;
+use crate::check::run_summary;
 use crate::chunks::Chunkable;
 use crate::cli::def::AnalyseStruct;
 use crate::cli::def::AnalyseSubcommand;
@@ -43,6 +54,8 @@ use crate::cli::def::CancelStruct;
 use crate::cli::def::Cli;
 use crate::cli::def::GourdCommand;
 use crate::cli::def::RunSubcommand;
+use crate::cli::def::SchemaStruct;
+use crate::cli::def::SchemaSubcommand;
 use crate::cli::def::StatusStruct;
 use crate::cli::printing::print_version;
 use crate::experiments::run::generate_new_run;
@@ -103,7 +116,7 @@ pub async fn process_command(cmd: &Cli) -> Result<()> {
     ) -> Result<Experiment> {
         debug!("Reading the config: {:?}", cmd.config);
 
-        let conf: Config = file_system.try_read_toml(&cmd.config)?;
+        let conf = Config::from_file(&cmd.config, file_system)?;
         let exp = if let Some(id) = experiment_id {
             Experiment::experiment_from_folder(*id, &conf.experiments_folder, file_system)?
         } else {
@@ -294,6 +307,8 @@ pub async fn process_command(cmd: &Cli) -> Result<()> {
                 AnalyseSubcommand::Plot {
                     format,
                     output: save_a,
+                    metric,
+                    by,
                 },
             output: save_b,
         }) => {
@@ -327,7 +342,20 @@ pub async fn process_command(cmd: &Cli) -> Result<()> {
             if cmd.dry {
                 return Ok(());
             } else {
-                let out = analysis_plot(&out_path, statuses, &experiment, *format)?;
+                let out = match (by, metric) {
+                    (Some(key), metric) => metadata_plot(
+                        &out_path,
+                        &statuses,
+                        &experiment,
+                        key,
+                        metric.as_deref(),
+                        *format,
+                    )?,
+                    (None, Some(key)) => {
+                        metric_plot(&out_path, &statuses, &experiment, key, *format)?
+                    }
+                    (None, None) => analysis_plot(&out_path, statuses, &experiment, *format)?,
+                };
                 info!("Plot saved to:");
                 println!("{PATH_STYLE}{}{PATH_STYLE:#}", out.display());
                 // non-info printing can let scripts easily get the path from
@@ -335,6 +363,35 @@ pub async fn process_command(cmd: &Cli) -> Result<()> {
             }
         }
 
+        GourdCommand::Analyse(AnalyseStruct {
+            experiment_id,
+            subcommand: AnalyseSubcommand::Samples(samples),
+            output: save,
+        }) => {
+            let experiment = read_experiment(experiment_id, cmd, &file_system)?;
+
+            let out_path = samples
+                .output
+                .clone()
+                .or(save.clone())
+                .unwrap_or(experiment.home.join(format!(
+                    "samples_{}.{}",
+                    experiment.seq,
+                    samples.format.ext()
+                )));
+
+            if cmd.dry {
+                info!(
+                    "Would have saved the samples plot to {}",
+                    out_path.display()
+                );
+            } else {
+                let out = samples_plot(&out_path, &experiment, samples)?;
+                info!("Plot saved to:");
+                println!("{PATH_STYLE}{}{PATH_STYLE:#}", out.display());
+            }
+        }
+
         GourdCommand::Analyse(AnalyseStruct {
             experiment_id,
             subcommand: AnalyseSubcommand::Table(csv),
@@ -453,8 +510,67 @@ pub async fn process_command(cmd: &Cli) -> Result<()> {
             }
         }
 
+        GourdCommand::Check => {
+            let conf = Config::from_file(&cmd.config, &file_system)?;
+
+            // Nothing is created while checking, even without `--dry`.
+            let report = check_config(
+                &conf,
+                env::consts::ARCH,
+                &FileSystemInteractor { dry_run: true },
+            );
+
+            for warning in &report.warnings {
+                warn!("{warning}");
+            }
+
+            for problem in &report.problems {
+                eprintln!(
+                    "{ERROR_STYLE}error:{ERROR_STYLE:#} {}",
+                    problem.root_cause()
+                );
+                eprintln!("{problem}");
+            }
+
+            if let Some(experiment) = &report.experiment {
+                println!("{}", run_summary(experiment));
+            }
+
+            if let Some(excluded) = &report.excluded {
+                println!("\n{}", exclusion_summary(excluded));
+            }
+
+            if let Some(experiment) = report.experiment.as_ref().filter(|experiment| {
+                !experiment.pairings.is_empty()
+                    || experiment.programs.iter().any(|p| !p.selector.is_empty())
+            }) {
+                println!("\n{}", pairing_matrix(experiment));
+            }
+
+            if !report.problems.is_empty() {
+                let count = report.problems.len();
+
+                bailc!(
+                    "Found {count} problems in the configuration {:?}", cmd.config;
+                    "", ;
+                    "Fix them and run {CMD_STYLE}gourd check{CMD_STYLE:#} again",
+                );
+            }
+
+            info!("The configuration {:?} is valid", cmd.config);
+        }
+
         GourdCommand::Version => print_version(cmd.script),
 
+        GourdCommand::Schema(SchemaStruct { subcommand }) => {
+            let schema = match subcommand {
+                SchemaSubcommand::Config => config_schema()?,
+                SchemaSubcommand::Experiment => experiment_schema()?,
+            };
+
+            print!("{schema}");
+        }
+
         GourdCommand::Continue(ContinueStruct { experiment_id }) => {
             let mut experiment = read_experiment(experiment_id, cmd, &file_system)?;
 
@@ -516,6 +632,9 @@ pub async fn process_command(cmd: &Cli) -> Result<()> {
 
             trace!("Selected runs: {selected_runs:?}");
 
+            let program_limits: Vec<ResourceLimits> =
+                experiment.programs.iter().map(|p| p.limits).collect();
+
             // NOTE: when rerunning we should only update the limits of the new runs,
             // and not of the whole experiment.
             query_changing_resource_limits(
@@ -529,22 +648,36 @@ pub async fn process_command(cmd: &Cli) -> Result<()> {
                 let new_id = experiment.runs.len();
                 let old_run = &experiment.runs[*run_id];
 
-                experiment.runs.push(generate_new_run(
+                // since we still update & save the limits for every program,
+                // new resource limits are fetched from the old run's program.
+                // in a future stateless gourd, we will only update the limits for new runs,
+                // instead of the limits of the entire program.
+                // If they were not changed, the rerun keeps the limits of the old
+                // run, which include those of its input.
+                let new_limits = experiment.programs[old_run.program].limits;
+                let changed = new_limits != program_limits[old_run.program];
+                let limits = if changed { new_limits } else { old_run.limits };
+
+                let mut new_run = generate_new_run(
                     new_id,
                     old_run.program,
                     old_run.input.clone(),
                     old_run.generated_from_input.clone(),
                     old_run.group.clone(),
-                    // since we still update & save the limits for every program,
-                    // new resource limits are fetched from the old run's program.
-                    // in a future stateless gourd, we will only update the limits for new runs,
-                    // instead of the limits of the entire program.
-                    experiment.programs[experiment.runs[*run_id].program].limits,
+                    limits,
                     old_run.parent,
                     &experiment,
                     &file_system,
-                )?);
+                )?;
+                new_run.replicate = old_run.replicate;
+                new_run.seed = old_run.seed;
+                new_run.enforced_limits = if changed {
+                    EnforcedLimits::ALL
+                } else {
+                    new_run.enforced_limits.or(old_run.enforced_limits)
+                };
 
+                experiment.runs.push(new_run);
                 experiment.runs[*run_id].rerun = Some(new_id);
             }
 
@@ -1,10 +1,12 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::VecDeque;
 use std::path::PathBuf;
 
-use anyhow::Context;
 use anyhow::Result;
-use gourd_lib::bailc;
 use gourd_lib::experiment::Experiment;
+use gourd_lib::experiment::FieldRef;
+use gourd_lib::experiment::InternalProgram;
 use gourd_lib::experiment::Run;
 use gourd_lib::experiment::RunInput;
 use gourd_lib::file_system::FileOperations;
@@ -21,11 +23,15 @@ enum Step {
 }
 
 /// A depth first search for creating the program tree.
+///
+/// If the experiment is sampled, programs only run on the inputs that they
+/// are `sampled` with.
 pub(super) fn dfs(
     visitation: &mut [usize],
     start: usize,
     runs: &mut Vec<Run>,
     exp: &Experiment,
+    sampled: Option<&BTreeSet<(usize, FieldRef)>>,
     fs: &impl FileOperations,
 ) -> Result<()> {
     // Since the run amount can be in the millions I don't want to rely on tail
@@ -35,53 +41,65 @@ pub(super) fn dfs(
 
     while let Some(step) = next.pop_front() {
         if let Step::Entry(node, parent) = step {
-            // We jumped backward in the search tree.
-            if visitation[node] == 2 {
-                bailc!(
-                    "A cycle was found in the program dependencies!",;
-                    "The `next` field in some program definition created a circular dependency",;
-                    "This incident will be reported",
-                );
-            }
-
-            // We've seen this node in a different part of the search tree.
-            if visitation[node] == 1 {
+            // A program that runs on the outputs of several programs is entered
+            // once for each of them, which ends as the dependencies are acyclic.
+            if visitation[node] != 0 && parent.is_none() {
                 continue;
             }
 
-            // We've never seen this node before.
-
             visitation[node] = 2;
 
             let mut children = Vec::new();
 
+            let program = &exp.programs[node];
+
             if parent.is_none() {
                 for (input_name, input) in &exp.inputs {
-                    let child = generate_new_run(
-                        runs.len(),
-                        node,
-                        RunInput {
-                            file: input.input.clone(),
-                            args: input.arguments.clone(),
-                        },
-                        Some(input_name.clone()),
-                        input.metadata.group.clone(),
-                        exp.programs[node].limits,
-                        None,
-                        exp,
-                        fs,
-                    )?;
-
-                    children.push((runs.len(), child.output_path.clone()));
-                    runs.push(child);
+                    if !exp.pairs(program, input_name, input)
+                        || excluded(exp, program, &input.metadata.parameters)
+                        || sampled.is_some_and(|pairs| !pairs.contains(&(node, input_name.clone())))
+                    {
+                        continue;
+                    }
+
+                    // The inputs, their groups and the parameter values can
+                    // change the limits of the program.
+                    let (limits, enforced) =
+                        exp.run_limits(node, Some(input_name), program.limits)?;
+
+                    for replicate in 0..program.repetitions {
+                        let mut child = generate_new_run(
+                            runs.len(),
+                            node,
+                            RunInput {
+                                file: input.input.clone(),
+                                args: input.arguments.clone(),
+                            },
+                            Some(input_name.clone()),
+                            input.metadata.group.clone(),
+                            limits,
+                            None,
+                            exp,
+                            fs,
+                        )?;
+                        child.replicate = replicate;
+                        child.seed = seed(program, replicate);
+                        // limits set for the input are enforced too
+                        child.enforced_limits = child.enforced_limits.or(enforced);
+
+                        children.push((runs.len(), child.output_path.clone()));
+                        runs.push(child);
+                    }
                 }
             } else if let Some(pchildren) = parent {
+                // a postprocessing program runs once on every output, and
+                // belongs to the same replicate as the run that produced it
                 for pchild in pchildren {
-                    let child = generate_new_run(
+                    let mut child = generate_new_run(
                         runs.len(),
                         node,
                         RunInput {
-                            file: Some(pchild.1),
+                            file: Some(pchild.1.clone()),
                             args: runs[pchild.0].input.args.clone(),
                         },
                         None,
@@ -91,13 +109,18 @@ pub(super) fn dfs(
                         exp,
                         fs,
                     )?;
+                    child.replicate = runs[pchild.0].replicate;
+                    child.seed = runs[pchild.0].seed;
+                    // the limits are inherited, and so is whether they are enforced
+                    child.enforced_limits =
+                        child.enforced_limits.or(runs[pchild.0].enforced_limits);
 
                     children.push((runs.len(), child.output_path.clone()));
                     runs.push(child);
                 }
             }
 
-            for child in &exp.programs[node].next {
+            for child in &program.next {
                 next.push_back(Step::Entry(*child, Some(children.clone())));
             }
 
@@ -109,3 +132,29 @@ pub(super) fn dfs(
 
     Ok(())
 }
+
+/// Whether the constraints leave out a program on an input, because of the
+/// values of their parameters together.
+pub(super) fn excluded(
+    exp: &Experiment,
+    program: &InternalProgram,
+    input: &BTreeMap<String, String>,
+) -> bool {
+    if exp.constraints.is_empty() || program.parameters.is_empty() || input.is_empty() {
+        return false;
+    }
+
+    let mut values = program.parameters.clone();
+    values.extend(input.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    exp.constraints.excludes(&values)
+}
+
+/// The seed of a replicate.
+fn seed(program: &InternalProgram, replicate: usize) -> Option<u64> {
+    program
+        .seeds
+        .as_ref()
+        .filter(|seeds| !seeds.is_empty())
+        .map(|seeds| seeds[replicate % seeds.len()])
+}
@@ -7,6 +7,7 @@ use chrono::DateTime;
 use chrono::Local;
 use gourd_lib::bailc;
 use gourd_lib::config::Config;
+use gourd_lib::constants::ENV_WHITELIST_DEFAULT;
 use gourd_lib::ctx;
 use gourd_lib::experiment::inputs::expand_inputs;
 use gourd_lib::experiment::programs::expand_programs;
@@ -16,6 +17,7 @@ use gourd_lib::file_system::FileOperations;
 use log::debug;
 
 use crate::experiments::dfs::dfs;
+use crate::experiments::sampling::draw_sample;
 
 /// Search through the run dependency graph to create the linear-connected runs
 mod dfs;
@@ -23,6 +25,9 @@ mod dfs;
 /// Generating new runs
 pub mod run;
 
+/// Sampling the combinations of programs and inputs
+pub(crate) mod sampling;
+
 /// Extension trait for the shared `Experiment` struct.
 pub trait ExperimentExt {
     /// Initialize a new experiment from a `config`.
@@ -66,11 +71,50 @@ impl ExperimentExt for Experiment {
             .unwrap_or(0)
             + 1;
 
+        // Only the variants in the sample are built.
+        let sample = draw_sample(conf)?;
+        let (program_points, input_points) = sample
+            .as_ref()
+            .map(|sample| (sample.programs.clone(), sample.inputs.clone()))
+            .unwrap_or_default();
+
         // First we will explode all programs from the initial set to their final set.
-        let expanded_programs = expand_programs(&conf.programs, conf, fs)?;
+        let expanded_programs = expand_programs(&conf.programs, conf, &program_points, fs)?;
+
+        let constraints = conf.constraints.clone().unwrap_or_default();
 
         // Now we will expand all inputs in a similar manner.
-        let expanded_inputs = expand_inputs(&conf.inputs, &conf.parameters, fs)?;
+        let expanded_inputs = expand_inputs(
+            &conf.inputs,
+            &conf.parameters,
+            &constraints,
+            &input_points,
+            fs,
+        )?;
+
+        if conf
+            .measurement
+            .and_then(|m| m.sample_interval)
+            .is_some_and(|interval| interval.is_zero())
+        {
+            bailc!(
+                "The sample interval cannot be zero", ;
+                "Resource usage cannot be sampled continuously", ;
+                "Set `sample_interval` in [measurement] to a positive duration, like \"100ms\"",
+            );
+        }
+
+        let extractions = conf.extractions.clone().unwrap_or_default();
+
+        for (name, extraction) in &extractions {
+            extraction.validate(name)?;
+        }
+
+        let labels = conf.labels.clone().unwrap_or_default();
+
+        for (name, label) in &labels {
+            label.validate(name)?;
+        }
 
         // Modifications to the slurm configurations
         let slurm = if let Some(mut slurm_conf) = conf.slurm.clone() {
@@ -85,8 +129,8 @@ impl ExperimentExt for Experiment {
 
         // get the groups from inputs
         let groups = expanded_inputs
-            .iter()
-            .filter_map(|(_, input)| input.metadata.group.clone())
+            .values()
+            .filter_map(|input| input.metadata.group.clone())
             .collect();
 
         let mut experiment = Self {
@@ -110,8 +154,32 @@ impl ExperimentExt for Experiment {
                 },
                 |l| l.num_threads,
             ),
+            measurement: conf.measurement.unwrap_or_default(),
+            env_vars: conf.env.clone().unwrap_or_default(),
+            clean_env: conf.clean_env,
+            env_whitelist: conf
+                .env_whitelist
+                .clone()
+                .unwrap_or_else(ENV_WHITELIST_DEFAULT),
             resource_limits: conf.resource_limits,
-            labels: conf.labels.clone().unwrap_or_default(),
+            labels,
+            extractions,
+            constraints,
+            sampling: conf.sampling,
+            pairings: conf.pairings.clone().unwrap_or_default(),
+            group_limits: conf
+                .groups
+                .iter()
+                .flatten()
+                .filter_map(|(name, group)| Some((name.clone(), group.resource_limits.clone()?)))
+                .collect(),
+            parameter_limits: conf
+                .parameters
+                .iter()
+                .flatten()
+                .filter_map(|(name, p)| Some((name.clone(), p.resource_limits.clone()?)))
+                .collect(),
+            variables: conf.variables.clone(),
 
             slurm,
 
@@ -129,27 +197,56 @@ impl ExperimentExt for Experiment {
             }
         }
 
+        // Every program has to be reachable from one that runs on the inputs,
+        // which is the case if the dependencies are acyclic.
+        let mut remaining = in_degrees.clone();
+        let mut ready: Vec<usize> = (0..remaining.len())
+            .filter(|prog| remaining[*prog] == 0)
+            .collect();
+
+        while let Some(prog) = ready.pop() {
+            for next_prog in &experiment.programs[prog].next {
+                remaining[*next_prog] -= 1;
+
+                if remaining[*next_prog] == 0 {
+                    ready.push(*next_prog);
+                }
+            }
+        }
+
+        if let Some(prog) = remaining.iter().position(|degree| *degree > 0) {
+            bailc!(
+                "A cycle was found in the program dependencies.",;
+                "The `next` field in the program definitions created a circular dependency",;
+                "Fix the dependencies for {:?}",experiment.programs[prog].name
+            );
+        }
+
+        let sampled = sample.map(|sample| sample.pairs(&experiment, &in_degrees));
+
         let mut visitation = vec![0usize; experiment.programs.len()];
         let mut runs = Vec::new();
 
         for (prog, degree) in in_degrees.iter().enumerate() {
             if *degree == 0 {
-                dfs(&mut visitation, prog, &mut runs, &experiment, fs)?;
-            }
-        }
-
-        for (prog, visit) in visitation.iter().enumerate() {
-            if *visit != 1 {
-                bailc!(
-                    "A cycle was found in the program dependencies.",;
-                    "The `next` field in the program definitions created a circular dependency",;
-                    "Fix the dependencies for {:?}",experiment.programs[prog].name
-                );
+                dfs(
+                    &mut visitation,
+                    prog,
+                    &mut runs,
+                    &experiment,
+                    sampled.as_ref(),
+                    fs,
+                )?;
             }
         }
 
         experiment.runs = runs;
 
+        // Unknown placeholders are found now rather than when the runs start.
+        for run_id in 0..experiment.runs.len() {
+            experiment.run_arguments(run_id)?;
+        }
+
         Ok(experiment)
     }
 
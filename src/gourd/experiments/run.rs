@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
 use gourd_lib::config::slurm::ResourceLimits;
 use gourd_lib::experiment::Experiment;
@@ -22,6 +24,33 @@ pub fn generate_new_run(
     fs: &impl FileOperations,
 ) -> Result<Run> {
     let seq = experiment.seq;
+
+    // A clean environment only keeps the whitelisted variables, as they are
+    // now, and the more specific variables override the general ones.
+    let mut env = BTreeMap::new();
+    if experiment.clean_env {
+        env.extend(
+            experiment
+                .env_whitelist
+                .iter()
+                .filter_map(|key| Some((key.clone(), std::env::var(key).ok()?))),
+        );
+    }
+    env.extend(experiment.env_vars.clone());
+    if let Some(program) = experiment.programs.get(program) {
+        env.extend(program.env.clone());
+    }
+    if let Some(input) = input.as_ref().and_then(|i| experiment.inputs.get(i)) {
+        env.extend(input.env.clone());
+    }
+
+    // Limits are only enforced if the user set them.
+    let enforced_limits = experiment
+        .programs
+        .get(program)
+        .map(|p| p.enforced_limits)
+        .unwrap_or_default();
+
     Ok(Run {
         program,
         input: run_input,
@@ -47,10 +76,14 @@ pub fn generate_new_run(
         )?,
         afterscript_output: None,
         limits,
+        enforced_limits,
         slurm_id: None,
         rerun: None,
         generated_from_input: input,
         parent,
         group: input_group,
+        replicate: 0,
+        seed: None,
+        env,
     })
 }
@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use gourd_lib::config::parameters::validate_parameters;
+use gourd_lib::config::parameters::ParameterSpace;
+use gourd_lib::config::parameters::Points;
+use gourd_lib::config::Config;
+use gourd_lib::experiment::Experiment;
+use gourd_lib::experiment::FieldRef;
+use gourd_lib::experiment::InternalInput;
+use gourd_lib::experiment::InternalProgram;
+use gourd_lib::experiment::Metadata;
+use log::debug;
+
+/// The name of a program or an input in the configuration and the values of
+/// the parameters of one of its variants.
+type Variant = (String, BTreeMap<String, String>);
+
+/// The sample of an experiment, drawn before any variant is built.
+#[derive(Debug, Default)]
+pub(crate) struct Sample {
+    /// The points of the parameters of the programs that are built.
+    pub programs: Points,
+
+    /// The points of the parameters of the inputs that are built.
+    pub inputs: Points,
+
+    /// The variants of a program and an input that run together.
+    pairs: BTreeSet<(Variant, Variant)>,
+}
+
+/// The parameters of a program or an input in the configuration.
+struct Template<'a> {
+    /// The name in the configuration.
+    name: &'a String,
+
+    /// The parameters that it uses, if any.
+    space: Option<ParameterSpace<'a>>,
+
+    /// The indices of the values of every parameter, from the smallest.
+    orders: Vec<Vec<usize>>,
+}
+
+impl<'a> Template<'a> {
+    /// A template with the parameters in `space`.
+    fn new(name: &'a String, space: Option<ParameterSpace<'a>>) -> Self {
+        let orders = space.as_ref().map_or_else(Vec::new, |space| {
+            (0..space.counts().len()).map(|n| space.order(n)).collect()
+        });
+
+        Template {
+            name,
+            space,
+            orders,
+        }
+    }
+
+    /// How many values every parameter has.
+    fn counts(&self) -> Vec<usize> {
+        self.space
+            .as_ref()
+            .map_or_else(Vec::new, ParameterSpace::counts)
+    }
+
+    /// The point of the variant whose values are `ranks` from the smallest.
+    fn point(&self, ranks: &[usize]) -> Vec<usize> {
+        self.orders
+            .iter()
+            .zip(ranks)
+            .map(|(order, rank)| order[*rank])
+            .collect()
+    }
+
+    /// The values of the parameters at `point`.
+    fn values(&self, point: &[usize]) -> BTreeMap<String, String> {
+        self.space
+            .as_ref()
+            .map_or_else(BTreeMap::new, |space| space.values(point))
+    }
+}
+
+/// Draws the sample of the experiment, if it has `sampling`.
+///
+/// The sample is drawn once from the parameters of every program that runs on
+/// the inputs together with those of every input it runs on. Pairs without
+/// parameters always run. Only the numbers of values of the parameters are
+/// used to draw it, so the variants that are not in the sample are never
+/// built.
+pub(crate) fn draw_sample(conf: &Config) -> Result<Option<Sample>> {
+    let Some(sampling) = conf.sampling else {
+        return Ok(None);
+    };
+
+    sampling.validate()?;
+
+    let no_parameters = BTreeMap::new();
+    let parameters = match &conf.parameters {
+        Some(parameters) => {
+            validate_parameters(parameters)?;
+            parameters
+        }
+        None => &no_parameters,
+    };
+
+    let constraints = conf.constraints.clone().unwrap_or_default();
+    let pairings = conf.pairings.clone().unwrap_or_default();
+
+    // Postprocessing programs run on the outputs of the sampled runs.
+    let postprocessing: BTreeSet<&String> = conf
+        .programs
+        .values()
+        .flat_map(|program| &program.next)
+        .collect();
+
+    let mut inputs = Vec::new();
+    for (name, input) in &conf.inputs {
+        let space = ParameterSpace::of_input(name, input, parameters)?;
+
+        // The input as far as the selection of inputs is concerned.
+        let selected = InternalInput {
+            input: None,
+            arguments: input.arguments.clone(),
+            metadata: Metadata {
+                glob_from: None,
+                is_fetched: false,
+                group: input.group.clone(),
+                parameters: BTreeMap::new(),
+                values: input.metadata.clone(),
+                origin: Some(name.clone()),
+            },
+            env: input.env.clone(),
+            limit_overrides: input.resource_limits.clone().unwrap_or_default(),
+        };
+
+        inputs.push((Template::new(name, space), selected));
+    }
+
+    let mut programs = Vec::new();
+    let mut blocks = Vec::new();
+    let mut counts = Vec::new();
+
+    for (name, program) in &conf.programs {
+        if postprocessing.contains(name) {
+            continue;
+        }
+
+        let template = Template::new(name, ParameterSpace::of_program(name, program, parameters)?);
+
+        let selecting = InternalProgram {
+            name: name.clone(),
+            selector: program.selector(),
+            ..Default::default()
+        };
+
+        for (input, (input_template, selected)) in inputs.iter().enumerate() {
+            let paired = selecting.selector.selects(input_template.name, selected)
+                && (pairings.is_empty()
+                    || pairings
+                        .iter()
+                        .any(|pairing| pairing.pairs(&selecting, input_template.name, selected)));
+
+            if !paired || (template.space.is_none() && input_template.space.is_none()) {
+                continue;
+            }
+
+            let mut block_counts = template.counts();
+            block_counts.extend(input_template.counts());
+
+            blocks.push((programs.len(), input));
+            counts.push(block_counts);
+        }
+
+        programs.push(template);
+    }
+
+    let mut sample = Sample::default();
+
+    // The variants that are in no pair are not built.
+    for template in &programs {
+        if template.space.is_some() {
+            sample
+                .programs
+                .insert(template.name.clone(), BTreeSet::new());
+        }
+    }
+
+    for (template, _) in &inputs {
+        if template.space.is_some() {
+            sample.inputs.insert(template.name.clone(), BTreeSet::new());
+        }
+    }
+
+    // The points of the program and the input of a block.
+    let split = |block: usize, ranks: &[usize]| {
+        let (program, input) = blocks[block];
+        let (program_ranks, input_ranks) = ranks.split_at(programs[program].orders.len());
+
+        (
+            programs[program].point(program_ranks),
+            inputs[input].0.point(input_ranks),
+        )
+    };
+
+    let chosen = sampling.choose(&counts, |block, ranks| {
+        let (program, input) = blocks[block];
+        let (program_point, input_point) = split(block, ranks);
+
+        let program_values = programs[program].values(&program_point);
+        let input_values = inputs[input].0.values(&input_point);
+
+        let mut values = program_values.clone();
+        values.extend(input_values.clone());
+
+        !(constraints.excludes(&program_values)
+            || constraints.excludes(&input_values)
+            || constraints.excludes(&values))
+    })?;
+
+    debug!(
+        "Sampled {} combinations of programs and inputs",
+        chosen.len()
+    );
+
+    for (block, ranks) in chosen {
+        let (program, input) = blocks[block];
+        let (program_point, input_point) = split(block, &ranks);
+
+        let program = &programs[program];
+        let input = &inputs[input].0;
+
+        sample.pairs.insert((
+            (program.name.clone(), program.values(&program_point)),
+            (input.name.clone(), input.values(&input_point)),
+        ));
+
+        if program.space.is_some() {
+            sample
+                .programs
+                .entry(program.name.clone())
+                .or_default()
+                .insert(program_point);
+        }
+
+        if input.space.is_some() {
+            sample
+                .inputs
+                .entry(input.name.clone())
+                .or_default()
+                .insert(input_point);
+        }
+    }
+
+    Ok(Some(sample))
+}
+
+impl Sample {
+    /// The pairs of a program that runs on the inputs and an input in the
+    /// `experiment` that were sampled.
+    pub(crate) fn pairs(
+        &self,
+        experiment: &Experiment,
+        in_degrees: &[usize],
+    ) -> BTreeSet<(usize, FieldRef)> {
+        let mut pairs = BTreeSet::new();
+
+        for (node, program) in experiment.programs.iter().enumerate() {
+            if in_degrees[node] != 0 {
+                continue;
+            }
+
+            // Variants are named like `solver[x=3]`.
+            let origin = match program.name.split_once('[') {
+                Some((origin, _)) if !program.parameters.is_empty() => origin,
+                _ => program.name.as_str(),
+            };
+
+            for (name, input) in &experiment.inputs {
+                if !experiment.pairs(program, name, input) {
+                    continue;
+                }
+
+                let unparameterised =
+                    program.parameters.is_empty() && input.metadata.parameters.is_empty();
+
+                let sampled = || {
+                    input.metadata.origin.as_ref().is_some_and(|input_origin| {
+                        self.pairs.contains(&(
+                            (origin.to_string(), program.parameters.clone()),
+                            (input_origin.clone(), input.metadata.parameters.clone()),
+                        ))
+                    })
+                };
+
+                if unparameterised || sampled() {
+                    pairs.insert((node, name.clone()));
+                }
+            }
+        }
+
+        pairs
+    }
+}
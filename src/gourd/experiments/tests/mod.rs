@@ -1,10 +1,40 @@
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use gourd_lib::config::limits::EnforcedLimits;
+use gourd_lib::config::slurm::ResourceLimits;
 use tempdir::TempDir;
 
 use super::*;
 use crate::test_utils::REAL_FS;
 
+/// The configuration in `test_resources`, which writes everything to a new
+/// temporary directory.
+fn config_from(config: &str) -> (TempDir, Config) {
+    let tempdir = TempDir::new("tests").unwrap();
+    let mut config: Config = Config::from_file(
+        &Path::new("src/gourd/experiments/tests/test_resources").join(config),
+        &REAL_FS,
+    )
+    .unwrap();
+    config.output_path = PathBuf::from(tempdir.path());
+    config.metrics_path = PathBuf::from(tempdir.path());
+    config.experiments_folder = PathBuf::from(tempdir.path());
+
+    (tempdir, config)
+}
+
+/// The experiment of the configuration in `test_resources`.
+fn experiment_from(config: &str) -> (TempDir, Experiment) {
+    let (tempdir, config) = config_from(config);
+
+    let experiment =
+        Experiment::from_config(&config, Local::now(), Environment::Local, &REAL_FS).unwrap();
+
+    (tempdir, experiment)
+}
+
 #[test]
 fn latest_id_invalid_folder() {
     let error = Experiment::latest_id_from_folder(Path::new(
@@ -20,15 +50,7 @@ fn latest_id_invalid_folder() {
 
 #[test]
 fn latest_id_correct() {
-    let tempdir = TempDir::new("tests").unwrap();
-    let mut config: Config = Config::from_file(
-        Path::new("src/gourd/experiments/tests/test_resources/config_id_testing.toml"),
-        &REAL_FS,
-    )
-    .unwrap();
-    config.output_path = PathBuf::from(tempdir.path());
-    config.metrics_path = PathBuf::from(tempdir.path());
-    config.experiments_folder = PathBuf::from(tempdir.path());
+    let (tempdir, config) = config_from("config_id_testing.toml");
 
     // test other files in dir that should be ignored
     fs::create_dir(tempdir.path().join("39.lock")).unwrap();
@@ -48,3 +70,260 @@ fn latest_id_correct() {
     let id = Experiment::latest_id_from_folder(tempdir.path()).unwrap();
     assert_eq!(id, Some(8));
 }
+
+#[test]
+fn repetitions_and_warmup() {
+    let (_tempdir, experiment) = experiment_from("config_repetitions.toml");
+
+    let runs: Vec<(&str, usize)> = experiment
+        .runs
+        .iter()
+        .map(|r| (experiment.programs[r.program].name.as_str(), r.replicate))
+        .collect();
+
+    // the warmups are not runs of their own, the wrapper runs them before
+    // every measured run
+    assert_eq!(vec![("b", 0), ("b", 1), ("c", 0), ("c", 1), ("c", 2)], runs);
+
+    let warmups: Vec<usize> = experiment.programs.iter().map(|p| p.warmup).collect();
+    assert_eq!(vec![1, 0], warmups);
+
+    // no limits were set, so the default ones are not enforced
+    assert!(experiment.runs.iter().all(|run| !run.enforced_limits.any()));
+}
+
+#[test]
+fn postprocessing_replicates() {
+    let (_tempdir, experiment) = experiment_from("config_postprocessing.toml");
+
+    let runs: Vec<(&str, usize, Option<u64>, Option<usize>)> = experiment
+        .runs
+        .iter()
+        .map(|r| {
+            (
+                experiment.programs[r.program].name.as_str(),
+                r.replicate,
+                r.seed,
+                r.parent,
+            )
+        })
+        .collect();
+
+    // the postprocessing runs once for every measured run, whatever its own
+    // repetitions and warmup
+    assert_eq!(
+        vec![
+            ("a", 0, Some(11), None),
+            ("a", 1, Some(12), None),
+            ("b", 0, Some(11), Some(0)),
+            ("b", 1, Some(12), Some(1)),
+        ],
+        runs
+    );
+}
+
+#[test]
+fn seeds() {
+    let (_tempdir, mut config) = config_from("config_seeds.toml");
+
+    let experiment =
+        Experiment::from_config(&config, Local::now(), Environment::Local, &REAL_FS).unwrap();
+
+    let runs: Vec<(&str, usize, Option<u64>)> = experiment
+        .runs
+        .iter()
+        .map(|r| {
+            (
+                experiment.programs[r.program].name.as_str(),
+                r.replicate,
+                r.seed,
+            )
+        })
+        .collect();
+
+    // the repetitions of a program override the experiment-wide seeds
+    assert_eq!(
+        vec![
+            ("b", 0, Some(42)),
+            ("b", 1, Some(43)),
+            ("c", 0, Some(7)),
+            ("c", 1, Some(3)),
+            ("c", 2, Some(5)),
+            ("d", 0, None),
+        ],
+        runs
+    );
+
+    config.repetitions = Some(2);
+    assert!(Experiment::from_config(&config, Local::now(), Environment::Local, &REAL_FS).is_err());
+}
+
+#[test]
+fn program_parameters() {
+    let (_tempdir, experiment) = experiment_from("config_program_parameters.toml");
+
+    let names: Vec<&str> = experiment
+        .programs
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(
+        vec![
+            "check",
+            "solver[alpha=0.5,threads=1]",
+            "solver[alpha=0.5,threads=2]"
+        ],
+        names
+    );
+
+    let variant = &experiment.programs[2];
+    assert_eq!(vec!["--threads", "2", "--alpha=0.5"], variant.arguments);
+    assert_eq!(
+        BTreeMap::from([
+            ("alpha".to_string(), "0.5".to_string()),
+            ("threads".to_string(), "2".to_string()),
+        ]),
+        variant.parameters
+    );
+
+    // every variant runs on every input, and `check` runs on all of their outputs
+    assert_eq!(vec![0], variant.next);
+    assert_eq!(2, experiment.runs.iter().filter(|r| r.program == 2).count());
+    assert_eq!(4, experiment.runs.iter().filter(|r| r.program == 0).count());
+}
+
+#[test]
+fn sampled_parameters() {
+    let (_tempdir, config) = config_from("config_sampling.toml");
+
+    let experiment =
+        Experiment::from_config(&config, Local::now(), Environment::Local, &REAL_FS).unwrap();
+
+    // 8 of the 12800 combinations of a variant of the solver and an input run
+    assert_eq!(8, experiment.runs.len());
+    assert!(experiment.programs.len() <= 8);
+    assert_eq!(2, experiment.inputs.len());
+    assert_eq!(config.sampling, experiment.sampling);
+
+    // the latin hypercube spreads the runs over the values of the program and
+    // of the input together
+    let mut threads: Vec<usize> = experiment
+        .runs
+        .iter()
+        .map(|r| {
+            (experiment.programs[r.program].parameters["threads"]
+                .parse::<usize>()
+                .unwrap()
+                - 1)
+                / 8
+        })
+        .collect();
+    threads.sort();
+    assert_eq!((0..8).collect::<Vec<_>>(), threads);
+
+    let small = experiment
+        .runs
+        .iter()
+        .filter(|r| r.input.args.contains(&"10".to_string()))
+        .count();
+    assert_eq!(4, small);
+
+    for program in &experiment.programs {
+        assert_eq!(
+            vec![
+                "--threads".to_string(),
+                program.parameters["threads"].clone(),
+                format!("--alpha={}", program.parameters["alpha"]),
+            ],
+            program.arguments
+        );
+    }
+
+    // the same seed gives the same sample
+    let again =
+        Experiment::from_config(&config, Local::now(), Environment::Local, &REAL_FS).unwrap();
+    assert_eq!(experiment.programs, again.programs);
+    assert_eq!(experiment.runs.len(), again.runs.len());
+}
+
+#[test]
+fn sampled_parameters_are_not_all_built() {
+    let (_tempdir, experiment) = experiment_from("config_sampling_large.toml");
+
+    // only the variants of the 10 of the 10^18 combinations are built
+    assert_eq!(10, experiment.runs.len());
+    assert!(experiment.programs.len() <= 10);
+    assert!(experiment.inputs.len() <= 10);
+}
+
+#[test]
+fn selected_inputs_and_pairings() {
+    let (_tempdir, experiment) = experiment_from("config_pairings.toml");
+
+    let pairs: Vec<(&str, &str)> = experiment
+        .runs
+        .iter()
+        .map(|run| {
+            (
+                experiment.programs[run.program].name.as_str(),
+                run.generated_from_input.as_deref().unwrap(),
+            )
+        })
+        .collect();
+
+    // the pairings choose the inputs, and the selectors of the programs leave
+    // some of them out
+    assert_eq!(
+        vec![
+            ("exact", "tiny_a"),
+            ("heuristic", "tiny_a"),
+            ("heuristic", "tiny_b"),
+            ("solver[threads=1]", "tiny_b"),
+            ("solver[threads=2]", "tiny_b"),
+        ],
+        pairs
+    );
+}
+
+#[test]
+fn limits_of_inputs_groups_and_parameters() {
+    let (_tempdir, experiment) = experiment_from("config_limits.toml");
+
+    let limits = |program: &str, input: &str| {
+        experiment
+            .runs
+            .iter()
+            .find(|run| {
+                experiment.programs[run.program].name == program
+                    && run.generated_from_input.as_deref() == Some(input)
+            })
+            .map(|run| run.limits)
+            .unwrap()
+    };
+
+    // the program limits are used where nothing else is set
+    assert_eq!(
+        ResourceLimits {
+            time_limit: Duration::from_secs(60),
+            cpus: 1,
+            mem_per_cpu: 128,
+        },
+        limits("solver[threads=1]", "small")
+    );
+
+    // the input overrides its group, and the parameter value sets the cpus
+    assert_eq!(
+        ResourceLimits {
+            time_limit: Duration::from_secs(200),
+            cpus: 4,
+            mem_per_cpu: 1024,
+        },
+        limits("solver[threads=4]", "large")
+    );
+
+    // the limits were set by the user, so the wrapper enforces them
+    assert!(experiment
+        .runs
+        .iter()
+        .all(|run| run.enforced_limits == EnforcedLimits::ALL));
+}
@@ -52,12 +52,25 @@ pub fn init_interactive(
         programs: Default::default(),
         inputs: Default::default(),
         parameters: None,
+        constraints: None,
+        sampling: None,
+        pairings: None,
+        groups: None,
+        extractions: None,
         slurm: None,
         resource_limits: None,
         wrapper: WRAPPER_DEFAULT(),
         labels: None,
+        variables: Default::default(),
         local: None,
         input_schema: None,
+        repetitions: None,
+        seeds: None,
+        warmup: None,
+        measurement: None,
+        env: None,
+        clean_env: false,
+        env_whitelist: None,
     };
 
     let custom_paths = if script_mode {
@@ -10,6 +10,8 @@ use gourd_lib::bailc;
 use gourd_lib::constants::NAME_STYLE;
 use gourd_lib::constants::PRIMARY_STYLE;
 use gourd_lib::constants::TASK_LIMIT;
+use gourd_lib::constants::WRAPPER_FAILED_EXIT_CODE;
+use log::debug;
 use log::error;
 use log::trace;
 
@@ -35,7 +37,13 @@ pub async fn run_locally(
         /// Error in case of wrapper failure.
         fn handle_output(join: io::Result<Output>) {
             if let Ok(exit) = join {
-                if !exit.status.success() {
+                if exit.status.code() == Some(WRAPPER_FAILED_EXIT_CODE) {
+                    // The failure is shown as the status of the run.
+                    debug!(
+                        "The wrapper failed: {}",
+                        String::from_utf8_lossy(&exit.stderr)
+                    );
+                } else if !exit.status.success() {
                     error!("Failed to run gourd wrapper: {:?}", exit.status);
                     error!(
                         "Wrapper returned: {}",
@@ -69,6 +69,9 @@ pub mod rerun;
 /// An implementation for allocating queued jobs to chunks
 pub mod chunks;
 
+/// Checking a configuration without running it.
+pub mod check;
+
 /// Analysing runs - collecting metrics, exporting, plotting.
 pub mod analyse;
 
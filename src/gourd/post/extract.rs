@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+
+use gourd_lib::config::extract::OutputSource;
+use gourd_lib::experiment::Experiment;
+use gourd_lib::file_system::FileOperations;
+use gourd_lib::measurement::MetricValue;
+use log::trace;
+
+/// Read the values of the extractions of an experiment from the outputs of a
+/// finished run.
+///
+/// An output that cannot be read, like the afterscript output of a run whose
+/// afterscript did not run yet, gives no values.
+pub fn extract_values(
+    run_id: usize,
+    experiment: &Experiment,
+    fs: &impl FileOperations,
+) -> BTreeMap<String, MetricValue> {
+    let run = &experiment.runs[run_id];
+
+    // Every output is read once, and only if an extraction needs it.
+    let mut outputs: BTreeMap<OutputSource, Option<String>> = BTreeMap::new();
+    let mut values = BTreeMap::new();
+
+    for (name, extraction) in &experiment.extractions {
+        let output = outputs
+            .entry(extraction.source)
+            .or_insert_with(|| match extraction.source {
+                OutputSource::Stdout => fs.read_utf8(&run.output_path).ok(),
+                OutputSource::Stderr => fs.read_utf8(&run.err_path).ok(),
+                OutputSource::Afterscript => run.afterscript_output.clone(),
+            });
+
+        if let Some(value) = output.as_deref().and_then(|text| extraction.extract(text)) {
+            trace!("Extracted {name} = {value} from run {run_id}");
+            values.insert(name.clone(), value);
+        }
+    }
+
+    values
+}
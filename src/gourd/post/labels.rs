@@ -1,34 +1,99 @@
-use anyhow::Result;
+use std::collections::BTreeMap;
+
+use gourd_lib::config::labels::RunOutcome;
+use gourd_lib::config::limits::limit_variables;
 use gourd_lib::experiment::Experiment;
-use log::debug;
+use gourd_lib::file_system::FileOperations;
+use gourd_lib::measurement::Measurement;
+use gourd_lib::measurement::MetricValue;
 use log::trace;
-use log::warn;
 
-/// Assigns a label to a run.
-pub fn assign_label(
+/// Assigns labels to a finished run.
+///
+/// The labels are ordered from the highest priority down, and labels with the
+/// same priority by name.
+pub fn assign_labels(
     run_id: usize,
-    source_text: &str,
+    measurement: &Measurement,
+    extracted: &BTreeMap<String, MetricValue>,
     experiment: &Experiment,
-) -> Result<Option<String>> {
-    debug!("Assigning label for text {source_text:?}");
-
-    let mut result_label: Option<String> = None;
-
-    let label_map = &experiment.labels;
-    let mut keys = label_map.keys().collect::<Vec<&String>>();
-    keys.sort_unstable_by(|a, b| label_map[*b].priority.cmp(&label_map[*a].priority));
-
-    for l in keys {
-        let label = &label_map[l];
-        if label.regex.is_match(source_text) {
-            if let Some(ref r) = result_label {
-                warn!("The afterscript for run {run_id:?} matches multiple labels: {r} and {l}");
-            } else {
-                trace!("{source_text} matches {l}");
-                result_label = Some(l.clone());
-            }
-        }
+    fs: &impl FileOperations,
+) -> Vec<String> {
+    let run = &experiment.runs[run_id];
+
+    // The outputs are only read if a label needs them.
+    let streams = experiment
+        .labels
+        .values()
+        .any(|label| label.reads_streams());
+    let stdout = streams
+        .then(|| fs.read_utf8(&run.output_path).ok())
+        .flatten();
+    let stderr = streams.then(|| fs.read_utf8(&run.err_path).ok()).flatten();
+
+    let outcome = RunOutcome {
+        exit_code: measurement.exit_code,
+        signal: measurement.termination.signal(),
+        stdout: stdout.as_deref(),
+        stderr: stderr.as_deref(),
+        afterscript: run.afterscript_output.as_deref(),
+        variables: outcome_variables(run_id, measurement, extracted, experiment),
+    };
+
+    let mut labels: Vec<&String> = experiment
+        .labels
+        .iter()
+        .filter(|(_, label)| label.matches(&outcome))
+        .map(|(name, _)| name)
+        .collect();
+
+    labels.sort_by(|a, b| {
+        experiment.labels[*b]
+            .priority
+            .cmp(&experiment.labels[*a].priority)
+    });
+
+    trace!("Run {run_id} has the labels {labels:?}");
+
+    labels.into_iter().cloned().collect()
+}
+
+/// The numbers that the metric thresholds of labels can use for a run.
+///
+/// These are the numeric custom and extracted metrics, and the measurements
+/// and resource limits of the run, with times in seconds.
+fn outcome_variables(
+    run_id: usize,
+    measurement: &Measurement,
+    extracted: &BTreeMap<String, MetricValue>,
+    experiment: &Experiment,
+) -> BTreeMap<String, f64> {
+    let limits = &experiment.runs[run_id].limits;
+
+    let mut metrics = measurement.custom.clone();
+    metrics.extend(extracted.clone());
+
+    let mut variables = limit_variables(&metrics, std::iter::empty());
+
+    variables.extend([
+        (
+            "wall_time".to_string(),
+            measurement.wall_micros.as_secs_f64(),
+        ),
+        ("exit_code".to_string(), measurement.exit_code as f64),
+        ("time_limit".to_string(), limits.time_limit.as_secs_f64()),
+        ("cpus".to_string(), limits.cpus as f64),
+        ("mem_per_cpu".to_string(), limits.mem_per_cpu as f64),
+    ]);
+
+    if let Some(rusage) = &measurement.rusage {
+        variables.extend([
+            ("user_time".to_string(), rusage.utime.as_secs_f64()),
+            ("system_time".to_string(), rusage.stime.as_secs_f64()),
+            // In KiB, as `getrusage` reports it.
+            ("max_rss".to_string(), rusage.maxrss as f64),
+        ]);
     }
 
-    Ok(result_label)
+    variables
 }
@@ -3,3 +3,6 @@ pub mod afterscript;
 
 /// Functionality for assigning labels.
 pub mod labels;
+
+/// Functionality for reading values from the output of runs.
+pub mod extract;
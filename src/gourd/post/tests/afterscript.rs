@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::fs::Permissions;
 use std::io::Write;
@@ -39,6 +40,16 @@ fn test_run_afterscript_for_run_good_weather() {
                 afterscript: Some(script_path.clone()),
                 resource_limits: None,
                 next: vec![],
+                repetitions: None,
+                seeds: None,
+                termination_signal: None,
+                grace_period: None,
+                warmup: None,
+                env: BTreeMap::new(),
+                inputs: None,
+                exclude_inputs: vec![],
+                groups: None,
+                exclude_groups: vec![],
             },
         )]
         .into(),
@@ -50,6 +61,9 @@ fn test_run_afterscript_for_run_good_weather() {
                 fetch: None,
                 group: None,
                 arguments: vec!["hi".into()],
+                env: BTreeMap::new(),
+                metadata: BTreeMap::new(),
+                resource_limits: None,
             },
         )]
         .into(),
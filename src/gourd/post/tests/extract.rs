@@ -0,0 +1,70 @@
+use std::fs;
+
+use gourd_lib::config::Config;
+use gourd_lib::experiment::Environment;
+use gourd_lib::experiment::Experiment;
+use gourd_lib::file_system::FileSystemInteractor;
+use gourd_lib::measurement::MetricValue;
+use tempdir::TempDir;
+
+use crate::experiments::ExperimentExt;
+use crate::post::extract::extract_values;
+
+#[test]
+fn test_extract_from_outputs() {
+    let fs = FileSystemInteractor { dry_run: false };
+    let dir = TempDir::new("extract").unwrap();
+    let config = dir.path().join("gourd.toml");
+
+    fs::write(
+        &config,
+        format!(
+            r#"
+            output_path = "{0}/output"
+            metrics_path = "{0}/metrics"
+            experiments_folder = "{0}/experiments"
+            [program.a]
+            binary = "/bin/sleep"
+            afterscript = "/bin/echo"
+            [input.b]
+            arguments = ["1"]
+            [extract.nodes]
+            regex = "nodes: (?<value>\\d+)"
+            type = "int"
+            aggregate = "max"
+            [extract.warning]
+            regex = "warning: (?<value>.+)"
+            source = "stderr"
+            aggregate = "first"
+            [extract.objective]
+            regex = "objective (?<value>[0-9.]+)"
+            source = "afterscript"
+            type = "float"
+            "#,
+            dir.path().display()
+        ),
+    )
+    .unwrap();
+
+    let conf = Config::from_file(&config, &fs).unwrap();
+    let mut exp =
+        Experiment::from_config(&conf, chrono::Local::now(), Environment::Local, &fs).unwrap();
+
+    fs::write(&exp.runs[0].output_path, "nodes: 5\nnodes: 17\nnodes: 3\n").unwrap();
+    fs::write(&exp.runs[0].err_path, "warning: slow\nwarning: slower\n").unwrap();
+
+    // the afterscript has not run yet
+    let values = extract_values(0, &exp, &fs);
+    assert_eq!(Some(&MetricValue::Int(17)), values.get("nodes"));
+    assert_eq!(
+        Some(&MetricValue::Text("slow".to_string())),
+        values.get("warning")
+    );
+    assert_eq!(None, values.get("objective"));
+
+    exp.runs[0].afterscript_output = Some("objective 12.5".to_string());
+    assert_eq!(
+        Some(&MetricValue::Float(12.5)),
+        extract_values(0, &exp, &fs).get("objective")
+    );
+}
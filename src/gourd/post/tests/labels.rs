@@ -1,54 +1,152 @@
-use std::fs::File;
-use std::io::Write;
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::Duration;
 
+use gourd_lib::config::labels::signal_number;
 use gourd_lib::config::Config;
 use gourd_lib::experiment::Environment;
 use gourd_lib::experiment::Experiment;
 use gourd_lib::file_system::FileSystemInteractor;
+use gourd_lib::measurement::Measurement;
+use gourd_lib::measurement::MetricValue;
+use gourd_lib::measurement::Termination;
 use tempdir::TempDir;
 
 use crate::experiments::ExperimentExt;
-use crate::post::labels::assign_label;
+use crate::post::labels::assign_labels;
+
+/// An experiment with one run of a program with an afterscript, and these
+/// labels.
+fn experiment(labels: &str, fs: &FileSystemInteractor) -> (Experiment, TempDir) {
+    let dir = TempDir::new("labels").expect("A temp folder could not be created.");
+    let config = dir.path().join("gourd.toml");
+
+    fs::write(
+        &config,
+        format!(
+            r#"
+            output_path = "{0}/output"
+            metrics_path = "{0}/metrics"
+            experiments_folder = "{0}/experiments"
+            [program.a]
+            binary = "/bin/sleep"
+            afterscript = "/bin/echo"
+            [program.a.resource_limits]
+            time_limit = "10s"
+            cpus = 1
+            mem_per_cpu = 512
+            [input.b]
+            arguments = ["1"]
+            {labels}
+            "#,
+            dir.path().display()
+        ),
+    )
+    .expect("The test file could not be written.");
+
+    let conf = Config::from_file(&config, fs).unwrap();
+    let exp = Experiment::from_config(&conf, chrono::Local::now(), Environment::Local, fs).unwrap();
+
+    (exp, dir)
+}
+
+/// A measurement of a run that took `seconds` and stopped this way.
+fn measurement(seconds: u64, exit_code: i32, termination: Termination) -> Measurement {
+    Measurement {
+        wall_micros: Duration::from_secs(seconds),
+        exit_code,
+        rusage: None,
+        termination,
+        cgroup: None,
+        custom: BTreeMap::new(),
+        host: None,
+        extracted: None,
+        labels: None,
+    }
+}
 
 #[test]
 fn test_add_label_to_run() {
     let fs = FileSystemInteractor { dry_run: true };
-    let dir = TempDir::new("config_folder").expect("A temp folder could not be created.");
-    let file_pb = dir.path().join("file.toml");
-    let config_contents = r#"
-             output_path = "./goose"
-             metrics_path = "./🪿/"
-             experiments_folder = "/tmp/gourd/experiments/"
-             [program.a]
-             binary = "/bin/sleep"
-             arguments = []
-             afterscript = "/bin/echo"
-             [input.b]
-             arguments = ["1"]
-             [input.c]
-             arguments = ["2"]
-             [label.found_hello]
-             priority = 0
-             regex = "hello"
-             [label.found_world]
-             priority = 1
-             regex = "world"
-         "#;
-    let mut file = File::create(file_pb.as_path()).expect("A file could not be created.");
-    file.write_all(config_contents.as_bytes())
-        .expect("The test file could not be written.");
-
-    let conf = Config::from_file(file_pb.as_path(), &fs).unwrap();
-    let exp =
-        Experiment::from_config(&conf, chrono::Local::now(), Environment::Local, &fs).unwrap();
-    assert!(conf.labels.is_some());
+    let (mut exp, _dir) = experiment(
+        r#"
+        [label.found_hello]
+        priority = 0
+        regex = "hello"
+        [label.found_world]
+        priority = 1
+        regex = "world"
+        "#,
+        &fs,
+    );
+    let done = measurement(1, 0, Termination::Exited);
+    let labels = |exp: &Experiment| assign_labels(0, &done, &BTreeMap::new(), exp, &fs);
+
+    assert!(labels(&exp).is_empty());
+
+    exp.runs[0].afterscript_output = Some("hello".to_string());
+    assert_eq!(labels(&exp), vec!["found_hello".to_string()]);
+
+    // every label that matches is assigned, the highest priority first
+    exp.runs[0].afterscript_output = Some("hello world".to_string());
+    assert_eq!(
+        labels(&exp),
+        vec!["found_world".to_string(), "found_hello".to_string()]
+    );
+}
+
+#[test]
+fn test_labels_of_outcomes() {
+    let fs = FileSystemInteractor { dry_run: false };
+    let (exp, _dir) = experiment(
+        r#"
+        [label.crashed]
+        exit_codes = "1-255"
+        priority = 2
+        [label.segfault]
+        signals = ["SEGV"]
+        priority = 3
+        [label.panicked]
+        stderr = "panicked"
+        [label.slow]
+        metrics = ["wall_time > 0.9 * time_limit"]
+        rerun_by_default = false
+        [label.many_nodes]
+        metrics = ["nodes >= 1000"]
+        stdout = "optimal"
+        "#,
+        &fs,
+    );
+
+    fs::write(&exp.runs[0].output_path, "optimal after 1200 nodes").unwrap();
+    fs::write(&exp.runs[0].err_path, "thread 'main' panicked").unwrap();
+
+    let nodes = BTreeMap::from([("nodes".to_string(), MetricValue::Int(1200))]);
+    let none = BTreeMap::new();
+    let labels = |measurement: &Measurement, extracted: &BTreeMap<String, MetricValue>| {
+        assign_labels(0, measurement, extracted, &exp, &fs)
+    };
+
+    assert_eq!(
+        labels(&measurement(1, 101, Termination::Exited), &none),
+        vec!["crashed".to_string(), "panicked".to_string()]
+    );
+
+    let signalled = Termination::Signalled {
+        signal: signal_number("SEGV").unwrap(),
+        core_dumped: false,
+    };
     assert_eq!(
-        assign_label(0, "hello", &exp).expect("tested fn failed"),
-        Some("found_hello".to_string())
+        labels(&measurement(1, 139, signalled), &none)[..2],
+        ["segfault".to_string(), "crashed".to_string()]
     );
 
     assert_eq!(
-        assign_label(1, "hello world", &exp).expect("tested fn failed"),
-        Some("found_world".to_string())
+        labels(&measurement(10, 0, Termination::Exited), &nodes),
+        vec![
+            "many_nodes".to_string(),
+            "panicked".to_string(),
+            "slow".to_string()
+        ]
     );
 }
@@ -1,4 +1,6 @@
 /// Tests for the functionality of afterscripts.
 pub mod afterscript;
+/// Tests for extracting values from the outputs.
+pub mod extract;
 /// Tests for labels.
 pub mod labels;
@@ -31,6 +31,9 @@ pub(super) enum RerunStatus {
 
     /// Failed with an exit code
     FailedExitCode(i32),
+
+    /// The wrapper failed to run it
+    FailedWrapper(String),
 }
 
 impl Display for RerunStatus {
@@ -41,6 +44,7 @@ impl Display for RerunStatus {
             RerunStatus::FinishedSuccessLabel(l) => write!(f, "Finished with label {l}"),
             RerunStatus::FailedErrorLabel(l) => write!(f, "Failed with label {l}"),
             RerunStatus::FailedExitCode(c) => write!(f, "Failed with exit code {c}"),
+            RerunStatus::FailedWrapper(e) => write!(f, "The wrapper failed: {e}"),
         }
     }
 }
@@ -104,6 +104,11 @@ pub(super) fn check_single_run_failed(
             Ok(*specific_run)
         }
 
+        RerunStatus::FailedWrapper(e) => {
+            debug!("Scheduling rerun for run #{specific_run} whose wrapper failed: {e}");
+            Ok(*specific_run)
+        }
+
         RerunStatus::FailedErrorLabel(l) => {
             debug!("Scheduling rerun for run #{specific_run} that failed with label {l}");
             Ok(*specific_run)
@@ -14,20 +14,25 @@ pub(super) fn status_of_single_run(
     let runs_status = &statuses[run_id];
 
     // 2. check if run has completed
-    match runs_status.fs_status.completion {
+    match &runs_status.fs_status.completion {
         FsState::Pending | FsState::Running => Ok(RerunStatus::NotFinished),
 
+        FsState::WrapperFailed(reason) => Ok(RerunStatus::FailedWrapper(reason.clone())),
+
         FsState::Completed(m) => {
-            // 3. check if the run failed
+            let labels = &runs_status.fs_status.labels;
+
+            // 3. check if a label marks the run as failed
+            for label in labels {
+                if experiment.get_label(label)?.rerun_by_default {
+                    return Ok(RerunStatus::FailedErrorLabel(label.clone()));
+                }
+            }
+
+            // 4. check if the run failed
             if m.exit_code == 0 {
-                // run the afterscript to get a label
-                if let Some(Some(label)) = &runs_status.fs_status.afterscript_completion {
-                    let lb = experiment.get_label(label)?;
-                    if lb.rerun_by_default {
-                        Ok(RerunStatus::FailedErrorLabel(label.clone()))
-                    } else {
-                        Ok(RerunStatus::FinishedSuccessLabel(label.clone()))
-                    }
+                if let Some(label) = labels.first() {
+                    Ok(RerunStatus::FinishedSuccessLabel(label.clone()))
                 } else {
                     Ok(RerunStatus::FinishedExitZero)
                 }
@@ -6,6 +6,7 @@ use anyhow::Context;
 use anyhow::Result;
 use gourd_lib::bailc;
 use gourd_lib::config::slurm::SlurmConfig;
+use gourd_lib::config::termination::GracefulTermination;
 use gourd_lib::constants::CMD_DOC_STYLE;
 use gourd_lib::constants::MAIL_TYPE_VALID_OPTIONS;
 use gourd_lib::experiment::Experiment;
@@ -142,6 +143,21 @@ pub fn parse_optional_args(slurm_config: &SlurmConfig) -> String {
     result
 }
 
+/// The `--signal` option that makes Slurm send the termination signal of the
+/// runs to the wrapper before the time limit of the job.
+///
+/// The wrapper passes the signal on to the program.
+pub fn termination_signal_arg(termination: Option<&GracefulTermination>) -> String {
+    match termination {
+        Some(termination) => format!(
+            "#SBATCH --signal=B:{}@{}\n",
+            termination.signal,
+            termination.grace_period.as_secs_f64().ceil().max(1.0) as u64
+        ),
+        None => "".to_string(),
+    }
+}
+
 #[cfg(test)]
 #[path = "tests/handler.rs"]
 mod tests;
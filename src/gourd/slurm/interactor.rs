@@ -22,6 +22,7 @@ use log::info;
 use log::trace;
 
 use super::handler::parse_optional_args;
+use super::handler::termination_signal_arg;
 use super::SacctOutput;
 use crate::chunks::Chunk;
 use crate::chunks::Chunkable;
@@ -214,6 +215,7 @@ impl SlurmInteractor for SlurmCli {
         let chunk_index = experiment.register_runs(&chunk.runs);
 
         let optional_args = parse_optional_args(slurm_config);
+        let signal_arg = termination_signal_arg(chunk.termination());
 
         // `%A` gets replaced with array *job* id, `%a` with the array *task* id
         // this is read in `src/gourd/status/slurm_files.rs` to get the output.
@@ -236,10 +238,10 @@ impl SlurmInteractor for SlurmCli {
 #SBATCH --account=\"{}\"
 #SBATCH --output={:?}
 #SBATCH --error={:?}
-{}
+{}{}
 set -x
 
-{} {} {} $SLURM_ARRAY_TASK_ID
+exec {} {} {} $SLURM_ARRAY_TASK_ID
 ",
             slurm_config.experiment_name,
             0,
@@ -252,6 +254,7 @@ set -x
             slurm_out,
             slurm_err,
             optional_args,
+            signal_arg,
             experiment.wrapper,
             exp_path.display(),
             chunk_index
@@ -86,3 +86,18 @@ fn parse_optional_args_test_custom_args() {
 
     assert_eq!(output, desired_output)
 }
+
+#[test]
+fn termination_signal_arg_test() {
+    assert_eq!("", termination_signal_arg(None));
+
+    let termination = GracefulTermination {
+        signal: "USR1".to_string(),
+        grace_period: std::time::Duration::from_millis(29500),
+    };
+
+    assert_eq!(
+        "#SBATCH --signal=B:USR1@30\n",
+        termination_signal_arg(Some(&termination))
+    );
+}
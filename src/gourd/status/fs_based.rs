@@ -1,15 +1,17 @@
 use std::collections::BTreeMap;
+use std::path::Path;
 
 use anyhow::Result;
 use gourd_lib::experiment::Experiment;
 use gourd_lib::file_system::FileOperations;
+use gourd_lib::measurement::Measurement;
 use gourd_lib::measurement::Metrics;
 use log::trace;
-use log::warn;
 
 use super::FileSystemBasedStatus;
 use super::StatusProvider;
-use crate::post::labels::assign_label;
+use crate::post::extract::extract_values;
+use crate::post::labels::assign_labels;
 use crate::status::FsState;
 
 /// Provide job status information based on the files system information.
@@ -41,32 +43,42 @@ where
                 }
             };
 
-            let completion = match metrics {
+            let mut completion = match metrics {
                 Some(inner) => match inner {
                     Metrics::Done(metrics) => FsState::Completed(metrics),
                     Metrics::NotCompleted => FsState::Running,
+                    Metrics::WrapperFailed { reason } => FsState::WrapperFailed(reason),
                 },
                 None => FsState::Pending,
             };
 
-            let mut afterscript_completion = None;
-
-            if run.afterscript_output.is_some() && completion.has_succeeded() {
-                afterscript_completion = match Self::get_afterscript_status(run_id, experiment) {
-                    Ok(status) => Some(status),
-                    Err(e) => {
-                        warn!(
-                            "No output found for afterscript of run #{run_id}: {e}\n\
-                            Check that the afterscript correctly places the output in a file.",
-                        );
-                        None
+            let (extracted, labels) = if let FsState::Completed(measurement) = &mut completion {
+                match (&measurement.extracted, &measurement.labels) {
+                    (Some(extracted), Some(labels)) => (extracted.clone(), labels.clone()),
+                    _ => {
+                        let extracted = extract_values(run_id, experiment, fs);
+                        let labels = assign_labels(run_id, measurement, &extracted, experiment, fs);
+
+                        // The outputs only change until the afterscript ran.
+                        if run.afterscript_output.is_some()
+                            || experiment.get_program(run)?.afterscript.is_none()
+                        {
+                            measurement.extracted = Some(extracted.clone());
+                            measurement.labels = Some(labels.clone());
+                            store_measurement(fs, &run.metrics_path, measurement);
+                        }
+
+                        (extracted, labels)
                     }
-                };
-            }
+                }
+            } else {
+                (BTreeMap::new(), Vec::new())
+            };
 
             let status = FileSystemBasedStatus {
                 completion,
-                afterscript_completion,
+                extracted,
+                labels,
             };
 
             statuses.insert(run_id, status);
@@ -76,15 +88,14 @@ where
     }
 }
 
-impl FileBasedProvider {
-    /// Get the completion of an afterscript.
-    pub fn get_afterscript_status(run_id: usize, exp: &Experiment) -> Result<Option<String>> {
-        let run = &exp.runs[run_id];
-
-        if let Some(text_output) = run.afterscript_output.clone() {
-            assign_label(run_id, &text_output, exp)
-        } else {
-            Ok(None)
-        }
+/// Write back the metrics of a finished run, so that what gourd derived from
+/// its outputs is not derived again.
+fn store_measurement(fs: &impl FileOperations, path: &Path, measurement: &Measurement) {
+    if let Err(e) = fs.try_write_toml(path, &Metrics::Done(measurement.clone())) {
+        trace!("Failed to store the metrics: {e:?}");
     }
 }
+
+#[cfg(test)]
+#[path = "tests/fs_based.rs"]
+mod tests;
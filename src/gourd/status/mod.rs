@@ -9,6 +9,7 @@ use gourd_lib::experiment::Environment;
 use gourd_lib::experiment::Experiment;
 use gourd_lib::file_system::FileOperations;
 use gourd_lib::measurement::Measurement;
+use gourd_lib::measurement::MetricValue;
 use indicatif::MultiProgress;
 use log::debug;
 
@@ -87,7 +88,8 @@ impl SlurmState {
 }
 
 /// This possible status of a job, reported by the file system.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
 pub enum FsState {
     /// The job has not yet started.
     Pending,
@@ -97,17 +99,28 @@ pub enum FsState {
 
     /// The job completed.
     Completed(Measurement),
+
+    /// The wrapper failed to run the job, with this error.
+    WrapperFailed(String),
 }
 
 impl FsState {
     /// Check if this state means that the run is completed.
+    ///
+    /// A run whose wrapper failed will not progress either, so it counts as
+    /// completed.
     pub fn is_completed(&self) -> bool {
-        matches!(self, FsState::Completed(_))
+        matches!(self, FsState::Completed(_) | FsState::WrapperFailed(_))
     }
 
     /// Check if this state means that the run has succeded.
+    ///
+    /// A run that exited with 0 after being asked to stop has succeeded too.
     pub fn has_succeeded(&self) -> bool {
-        matches!(self, FsState::Completed(Measurement { exit_code: 0, .. }))
+        matches!(
+            self,
+            FsState::Completed(m) if m.exit_code == 0 && m.termination.is_orderly()
+        )
     }
 }
 
@@ -117,9 +130,11 @@ pub struct FileSystemBasedStatus {
     /// State of completion of the run
     pub completion: FsState,
 
-    /// If the afterscript completed successfully, this will contain the label,
-    /// if one was assigned.
-    pub afterscript_completion: Option<Option<String>>,
+    /// The values that were read from the outputs of the run, by name.
+    pub extracted: BTreeMap<String, MetricValue>,
+
+    /// The labels of the run, from the highest priority down.
+    pub labels: Vec<String>,
 }
 
 /// Structure of slurm based status
@@ -167,7 +182,12 @@ impl Status {
     /// Check if we know this job to have failed.
     pub fn has_failed(&self, experiment: &Experiment) -> bool {
         let a = match self.fs_status.completion {
-            FsState::Completed(Measurement { exit_code, .. }) => exit_code != 0,
+            FsState::Completed(Measurement {
+                exit_code,
+                termination,
+                ..
+            }) => exit_code != 0 || !termination.is_orderly(),
+            FsState::WrapperFailed(_) => true,
             _ => false,
         };
         let b = match self.slurm_status {
@@ -206,16 +226,12 @@ impl Status {
             Some(_) => false,
             None => false,
         };
-        let c = match &self.fs_status.afterscript_completion {
-            Some(Some(label)) => {
-                if let Some(l) = &experiment.labels.get(label) {
-                    l.rerun_by_default
-                } else {
-                    false
-                }
-            }
-            _ => false,
-        };
+        let c = self.fs_status.labels.iter().any(|label| {
+            experiment
+                .labels
+                .get(label)
+                .is_some_and(|l| l.rerun_by_default)
+        });
         a || b || c
     }
 
@@ -228,6 +244,18 @@ impl Status {
     pub fn is_pending(&self) -> bool {
         self.slurm_status.is_none() && matches!(self.fs_status.completion, FsState::Pending)
     }
+
+    /// The value of a metric that was read from the output of the run, or
+    /// else reported by the program itself.
+    pub fn metric(&self, key: &str) -> Option<&MetricValue> {
+        self.fs_status
+            .extracted
+            .get(key)
+            .or(match &self.fs_status.completion {
+                FsState::Completed(measurement) => measurement.custom.get(key),
+                _ => None,
+            })
+    }
 }
 
 /// This type maps between `run_id` and the [Status] of the run.
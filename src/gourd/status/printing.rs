@@ -21,6 +21,7 @@ use gourd_lib::experiment::Environment;
 use gourd_lib::experiment::Experiment;
 use gourd_lib::experiment::FieldRef;
 use gourd_lib::experiment::Run;
+use gourd_lib::measurement::Termination;
 use log::info;
 
 use super::ExperimentStatus;
@@ -58,21 +59,39 @@ impl Display for FsState {
         match self {
             FsState::Pending => write!(f, "pending?"),
             FsState::Running => write!(f, "running!"),
+            FsState::WrapperFailed(reason) => {
+                if f.alternate() {
+                    write!(f, "{ERROR_STYLE}wrapper failed{ERROR_STYLE:#}: {reason}")
+                } else {
+                    write!(f, "{ERROR_STYLE}wrapper failed{ERROR_STYLE:#}")
+                }
+            }
             FsState::Completed(metrics) => {
                 if f.sign_minus() {
                     // reduced output, guarantees similar length output to pending? and running!
                     write!(f, "completed")
+                } else if !metrics.termination.is_orderly() {
+                    write!(
+                        f,
+                        "{ERROR_STYLE}failed, {}{ERROR_STYLE:#}",
+                        metrics.termination
+                    )
                 } else if metrics.exit_code == 0 {
+                    let interrupted = match metrics.termination {
+                        Termination::Interrupted { .. } => " after the termination signal",
+                        _ => "",
+                    };
+
                     if f.alternate() {
                         write!(
                             f,
-                            "{PRIMARY_STYLE}success{PRIMARY_STYLE:#} {NAME_STYLE}wall clock time{NAME_STYLE:#}: {}",
+                            "{PRIMARY_STYLE}success{PRIMARY_STYLE:#}{interrupted} {NAME_STYLE}wall clock time{NAME_STYLE:#}: {}",
                             humantime::Duration::from(metrics.wall_micros)
                         )
                     } else {
                         write!(
                             f,
-                            "{PRIMARY_STYLE}success{PRIMARY_STYLE:#}, took: {}",
+                            "{PRIMARY_STYLE}success{PRIMARY_STYLE:#}{interrupted}, took: {}",
                             humantime::Duration::from(metrics.wall_micros)
                         )
                     }
@@ -114,10 +133,34 @@ impl Display for Status {
                 }
             }
 
-            if let FsState::Completed(measurement) = self.fs_status.completion {
+            if let FsState::Completed(measurement) = &self.fs_status.completion {
                 if let Some(rusage) = measurement.rusage {
                     write!(f, "{NAME_STYLE}metrics{NAME_STYLE:#}:\n{rusage}")?;
                 }
+
+                if let Some(cgroup) = measurement.cgroup {
+                    write!(f, "{NAME_STYLE}cgroup metrics{NAME_STYLE:#}:\n{cgroup}")?;
+                }
+
+                if !measurement.custom.is_empty() {
+                    writeln!(f, "{NAME_STYLE}custom metrics{NAME_STYLE:#}:")?;
+
+                    for (key, value) in &measurement.custom {
+                        writeln!(f, "  {NAME_STYLE}{key}{NAME_STYLE:#}: {value}")?;
+                    }
+                }
+
+                if let Some(host) = &measurement.host {
+                    write!(f, "{NAME_STYLE}host{NAME_STYLE:#}:\n{host}")?;
+                }
+            }
+
+            if !self.fs_status.extracted.is_empty() {
+                writeln!(f, "{NAME_STYLE}extracted values{NAME_STYLE:#}:")?;
+
+                for (key, value) in &self.fs_status.extracted {
+                    writeln!(f, "  {NAME_STYLE}{key}{NAME_STYLE:#}: {value}")?;
+                }
             }
         } else {
             // Short summary.
@@ -327,6 +370,23 @@ fn long_status(
     Ok(())
 }
 
+/// The labels of a run, in red if they make it fail.
+fn styled_labels(labels: &[String], experiment: &Experiment) -> String {
+    labels
+        .iter()
+        .map(|label| {
+            let style = if experiment.labels[label].rerun_by_default {
+                ERROR_STYLE
+            } else {
+                PRIMARY_STYLE
+            };
+
+            format!("{style}{label}{style:#}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Display runs of some group.
 fn display_runs(
     group: bool,
@@ -369,24 +429,18 @@ fn display_runs(
 
         writeln!(f)?;
 
-        if let Some(Some(label_text)) = &status.fs_status.afterscript_completion {
-            let display_style = if experiment.labels[label_text].rerun_by_default {
-                ERROR_STYLE
-            } else {
-                PRIMARY_STYLE
-            };
-
-            write!(
+        if !status.fs_status.labels.is_empty() {
+            writeln!(
                 f,
-                "  {run_id: >numw$}a {:.<width$}.... \
-                            label: {display_style}{label_text}{display_style:#}",
-                "afterscript",
+                "  {run_id: >numw$}  {:.<width$}.... {}",
+                "labels",
+                styled_labels(&status.fs_status.labels, experiment),
                 numw = longest_index,
                 width = longest_input,
             )?;
+        }
 
-            writeln!(f)?;
-        } else if let Some(None) = &status.fs_status.afterscript_completion {
+        if run.afterscript_output.is_some() && status.fs_status.completion.has_succeeded() {
             write!(
                 f,
                 "  {run_id: >longest_index$}a {TERTIARY_STYLE}afterscript ran \
@@ -441,14 +495,46 @@ pub fn display_job(
 
         writeln!(
             f,
-            "  {NAME_STYLE}arguments{NAME_STYLE:#}: {:?}\n",
+            "  {NAME_STYLE}arguments{NAME_STYLE:#}: {:?}",
             run.input.args
         )?;
 
+        // The arguments after filling in the placeholders.
+        writeln!(
+            f,
+            "  {NAME_STYLE}command line{NAME_STYLE:#}: {:?}{}\n",
+            exp.run_arguments(id)?,
+            if exp.input_in_arguments(id)? {
+                ", the input file is not passed on stdin"
+            } else {
+                ""
+            }
+        )?;
+
+        if !run.env.is_empty() || exp.clean_env {
+            writeln!(
+                f,
+                "{NAME_STYLE}environment{NAME_STYLE:#}{}:",
+                if exp.clean_env { " (clean)" } else { "" }
+            )?;
+
+            for (key, value) in &run.env {
+                writeln!(f, "  {key}={value}")?;
+            }
+
+            writeln!(f)?;
+        }
+
         if let Some(group) = &run.group {
             writeln!(f, "{NAME_STYLE}group{NAME_STYLE:#}: {group}")?;
         }
 
+        writeln!(f, "{NAME_STYLE}replicate{NAME_STYLE:#}: {}", run.replicate)?;
+
+        if let Some(seed) = run.seed {
+            writeln!(f, "{NAME_STYLE}seed{NAME_STYLE:#}: {seed}")?;
+        }
+
         writeln!(
             f,
             "{NAME_STYLE}output path{NAME_STYLE:#}: {PATH_STYLE}{}{PATH_STYLE:#}",
@@ -500,65 +586,56 @@ pub fn display_job(
 
         writeln!(f, "{status:#}")?;
 
-        if let Some(Some(label_text)) = &status.fs_status.afterscript_completion {
-            let display_style = if exp.labels[label_text].rerun_by_default {
-                ERROR_STYLE
-            } else {
-                PRIMARY_STYLE
-            };
-
+        if !status.fs_status.labels.is_empty() {
             writeln!(
                 f,
-                "{NAME_STYLE}afterscript ran and assigned \
-                    label{NAME_STYLE:#}: {display_style}{label_text}{display_style:#}",
+                "{NAME_STYLE}labels{NAME_STYLE:#}: {}",
+                styled_labels(&status.fs_status.labels, exp)
             )?;
 
             writeln!(f)?;
-        } else if let Some(None) = &status.fs_status.afterscript_completion {
-            if let Some(mut out) = exp.runs[id].afterscript_output.clone() {
-                writeln!(
-                    f,
-                    "{TERTIARY_STYLE}afterscript ran successfully{TERTIARY_STYLE:#}",
-                )?;
-
-                let truncate_output = |x: &mut String| {
-                    let mut touch = false;
-                    if x.len() > TRUNCATE_AFTERSCRIPT_OUTPUT.0 {
-                        touch = true;
-                        x.truncate(TRUNCATE_AFTERSCRIPT_OUTPUT.0);
-                    }
-                    if x.lines().count() > TRUNCATE_AFTERSCRIPT_OUTPUT.1 {
-                        *x = x.lines().take(TRUNCATE_AFTERSCRIPT_OUTPUT.1).collect();
-                        touch = true;
-                    }
-                    touch
-                };
+        }
 
-                if truncate_output(&mut out) {
-                    debug!("truncating afterscript output for gourd status -i {id}");
-                    writeln!(
-                        f,
-                        "afterscript output was too long, run {CMD_DOC_STYLE} gourd status {} -i {id} --after-out {CMD_DOC_STYLE:#} to view entire output
+        if let Some(mut out) = exp.runs[id]
+            .afterscript_output
+            .clone()
+            .filter(|_| status.fs_status.completion.has_succeeded())
+        {
+            writeln!(
+                f,
+                "{TERTIARY_STYLE}afterscript ran successfully{TERTIARY_STYLE:#}",
+            )?;
 
-shortened output:\n{PARAGRAPH_STYLE}{out}[truncated]{PARAGRAPH_STYLE:#}",
-                        exp.seq,
-                    )?;
-                } else {
-                    writeln!(
-                        f,
-                        "afterscript output:\n{PARAGRAPH_STYLE}{out}{PARAGRAPH_STYLE:#}",
-                    )?;
+            let truncate_output = |x: &mut String| {
+                let mut touch = false;
+                if x.len() > TRUNCATE_AFTERSCRIPT_OUTPUT.0 {
+                    touch = true;
+                    x.truncate(TRUNCATE_AFTERSCRIPT_OUTPUT.0);
+                }
+                if x.lines().count() > TRUNCATE_AFTERSCRIPT_OUTPUT.1 {
+                    *x = x.lines().take(TRUNCATE_AFTERSCRIPT_OUTPUT.1).collect();
+                    touch = true;
                 }
+                touch
+            };
+
+            if truncate_output(&mut out) {
+                debug!("truncating afterscript output for gourd status -i {id}");
+                writeln!(
+                    f,
+                    "afterscript output was too long, run {CMD_DOC_STYLE} gourd status {} -i {id} --after-out {CMD_DOC_STYLE:#} to view entire output
 
-                writeln!(f)?;
+shortened output:\n{PARAGRAPH_STYLE}{out}[truncated]{PARAGRAPH_STYLE:#}",
+                    exp.seq,
+                )?;
             } else {
-                unreachable!(
-                    "this is not supposed to happen, \
-                    please contact the developers on \
-                    https://github.com/ConSol-Lab/gourd/issues/new \
-                    with screenshots (AFT_COMPL_OUT_IS_NONE)"
-                );
+                writeln!(
+                    f,
+                    "afterscript output:\n{PARAGRAPH_STYLE}{out}{PARAGRAPH_STYLE:#}",
+                )?;
             }
+
+            writeln!(f)?;
         }
 
         if let Some(new_id) = run.rerun {
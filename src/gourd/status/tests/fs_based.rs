@@ -0,0 +1,86 @@
+use std::fs;
+use std::time::Duration;
+
+use gourd_lib::config::Config;
+use gourd_lib::experiment::Environment;
+use gourd_lib::file_system::FileSystemInteractor;
+use gourd_lib::measurement::MetricValue;
+use gourd_lib::measurement::Termination;
+use tempdir::TempDir;
+
+use super::*;
+use crate::experiments::ExperimentExt;
+
+#[test]
+fn test_extracted_values_and_labels_are_stored() {
+    let fs = FileSystemInteractor { dry_run: false };
+    let dir = TempDir::new("fs_based").unwrap();
+    let config = dir.path().join("gourd.toml");
+
+    fs::write(
+        &config,
+        format!(
+            r#"
+            output_path = "{0}/output"
+            metrics_path = "{0}/metrics"
+            experiments_folder = "{0}/experiments"
+            [program.a]
+            binary = "/bin/sleep"
+            [program.b]
+            binary = "/bin/sleep"
+            afterscript = "/bin/echo"
+            [input.c]
+            arguments = ["1"]
+            [extract.nodes]
+            regex = "nodes: (?<value>\\d+)"
+            type = "int"
+            [label.few]
+            stdout = "nodes: 5"
+            "#,
+            dir.path().display()
+        ),
+    )
+    .unwrap();
+
+    let conf = Config::from_file(&config, &fs).unwrap();
+    let exp =
+        Experiment::from_config(&conf, chrono::Local::now(), Environment::Local, &fs).unwrap();
+
+    let done = Metrics::Done(Measurement {
+        wall_micros: Duration::from_secs(1),
+        exit_code: 0,
+        rusage: None,
+        termination: Termination::Exited,
+        cgroup: None,
+        custom: BTreeMap::new(),
+        host: None,
+        extracted: None,
+        labels: None,
+    });
+
+    for run in &exp.runs {
+        fs.try_write_toml(&run.metrics_path, &done).unwrap();
+        fs::write(&run.output_path, "nodes: 5\n").unwrap();
+    }
+
+    let nodes = |statuses: &BTreeMap<usize, FileSystemBasedStatus>, run: usize| {
+        statuses[&run].extracted.get("nodes").cloned()
+    };
+
+    let statuses = FileBasedProvider::get_statuses(&fs, &exp).unwrap();
+    assert_eq!(Some(MetricValue::Int(5)), nodes(&statuses, 0));
+    assert_eq!(Some(MetricValue::Int(5)), nodes(&statuses, 1));
+    assert_eq!(vec!["few".to_string()], statuses[&0].labels);
+
+    for run in &exp.runs {
+        fs::write(&run.output_path, "nodes: 7\n").unwrap();
+    }
+
+    // The run without an afterscript keeps its values and labels, the other one
+    // is read again since its afterscript did not run yet.
+    let statuses = FileBasedProvider::get_statuses(&fs, &exp).unwrap();
+    assert_eq!(Some(MetricValue::Int(5)), nodes(&statuses, 0));
+    assert_eq!(Some(MetricValue::Int(7)), nodes(&statuses, 1));
+    assert_eq!(vec!["few".to_string()], statuses[&0].labels);
+    assert!(statuses[&1].labels.is_empty());
+}
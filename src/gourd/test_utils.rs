@@ -56,10 +56,23 @@ pub fn create_sample_experiment(
         inputs,
         input_schema: None,
         parameters: None,
+        constraints: None,
+        sampling: None,
+        pairings: None,
+        groups: None,
+        extractions: None,
         slurm: None,
         resource_limits: None,
         local: None,
         labels: Some(BTreeMap::new()),
+        variables: BTreeMap::new(),
+        repetitions: None,
+        seeds: None,
+        warmup: None,
+        measurement: None,
+        env: None,
+        clean_env: false,
+        env_whitelist: None,
     };
 
     (
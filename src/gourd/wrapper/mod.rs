@@ -17,13 +17,13 @@ use log::trace;
 use crate::chunks::Chunkable;
 use crate::status::ExperimentStatus;
 #[cfg(target_os = "linux")]
-use crate::wrapper::check_binary_linux::verify_arch;
+pub(crate) use crate::wrapper::check_binary_linux::verify_arch;
 #[cfg(target_os = "macos")]
-use crate::wrapper::check_binary_macos::verify_arch;
+pub(crate) use crate::wrapper::check_binary_macos::verify_arch;
 
 /// Verify the architecture of the binary.
 #[cfg(not(any(target_os = "macos", target_os = "linux")))]
-fn verify_arch(_: &PathBuf, _: &str, _: &impl FileOperations) -> Result<()> {
+pub(crate) fn verify_arch(_: &PathBuf, _: &str, _: &impl FileOperations) -> Result<()> {
     Ok(())
 }
 
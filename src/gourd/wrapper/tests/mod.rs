@@ -53,6 +53,16 @@ fn non_matching_arch() {
             afterscript: None,
             resource_limits: None,
             next: vec![],
+            repetitions: None,
+            seeds: None,
+            termination_signal: None,
+            grace_period: None,
+            warmup: None,
+            env: BTreeMap::new(),
+            inputs: None,
+            exclude_inputs: vec![],
+            groups: None,
+            exclude_groups: vec![],
         },
     );
 
@@ -66,6 +76,9 @@ fn non_matching_arch() {
             arguments: vec![],
             fetch: None,
             group: None,
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
 
@@ -106,6 +119,16 @@ fn matching_arch() {
             afterscript: None,
             resource_limits: None,
             next: vec![],
+            repetitions: None,
+            seeds: None,
+            termination_signal: None,
+            grace_period: None,
+            warmup: None,
+            env: BTreeMap::new(),
+            inputs: None,
+            exclude_inputs: vec![],
+            groups: None,
+            exclude_groups: vec![],
         },
     );
 
@@ -119,6 +142,9 @@ fn matching_arch() {
             fetch: None,
             group: None,
             arguments: vec![],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
 
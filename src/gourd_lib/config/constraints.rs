@@ -0,0 +1,373 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::anyhow;
+use anyhow::Context;
+use anyhow::Result;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::Visitor;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+use super::Parameter;
+use crate::bailc;
+
+/// The combinations of parameter values that are left out of an experiment.
+///
+/// # Examples
+///
+/// ```toml
+/// [constraints]
+/// exclude = ["algorithm == 'exact' && heuristic_level != 0"]
+/// require = ["threads <= size"]
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Constraints {
+    /// A combination is left out if it matches any of these conditions.
+    #[serde(default)]
+    pub exclude: Vec<Condition>,
+
+    /// A combination is left out if it does not match all of these conditions.
+    #[serde(default)]
+    pub require: Vec<Condition>,
+}
+
+impl Constraints {
+    /// Whether there are no constraints.
+    pub fn is_empty(&self) -> bool {
+        self.exclude.is_empty() && self.require.is_empty()
+    }
+
+    /// Whether a combination of parameter values is left out.
+    ///
+    /// A condition only applies to combinations that have all of its
+    /// parameters, `values` is keyed by `parameter` or `parameter.sub`.
+    pub fn excludes(&self, values: &BTreeMap<String, String>) -> bool {
+        self.exclude
+            .iter()
+            .any(|condition| condition.evaluate(values) == Some(true))
+            || self
+                .require
+                .iter()
+                .any(|condition| condition.evaluate(values) == Some(false))
+    }
+
+    /// Check that the conditions only use parameters that exist.
+    pub fn validate(&self, parameters: &Option<BTreeMap<String, Parameter>>) -> Result<()> {
+        for condition in self.exclude.iter().chain(&self.require) {
+            for name in condition.parameters() {
+                let (parameter, sub) = match name.split_once('.') {
+                    Some((parameter, sub)) => (parameter, Some(sub)),
+                    None => (name, None),
+                };
+
+                let found = parameters
+                    .as_ref()
+                    .and_then(|parameters| parameters.get(parameter))
+                    .is_some_and(|p| match (sub, &p.sub) {
+                        (None, None) => true,
+                        (Some(sub), Some(subs)) => subs.contains_key(sub),
+                        _ => false,
+                    });
+
+                if !found {
+                    bailc!(
+                        "Unknown parameter {name} in the constraint {:?}", condition.source;
+                        "Constraints compare the values of parameters", ;
+                        "Quote the value if it is not a parameter, like '{name}'",
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A condition on the values of parameters, like `threads > cores`.
+///
+/// A condition is made of comparisons that are joined by `&&` and `||`, where
+/// `&&` binds stronger. The sides of a comparison are parameters, numbers or
+/// quoted strings. Two numbers are compared as numbers, anything else as text.
+#[derive(Debug, Clone)]
+pub struct Condition {
+    /// The condition as it was written.
+    source: String,
+
+    /// Any of these lists of comparisons has to hold entirely.
+    any: Vec<Vec<Comparison>>,
+}
+
+/// One side of a comparison.
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    /// The value of a parameter.
+    Parameter(String),
+
+    /// A number or a quoted string.
+    Literal(String),
+}
+
+impl Operand {
+    /// The value of the operand, or [`None`] if it is a parameter without one.
+    fn value<'a>(&'a self, values: &'a BTreeMap<String, String>) -> Option<&'a str> {
+        match self {
+            Operand::Parameter(name) => values.get(name).map(String::as_str),
+            Operand::Literal(literal) => Some(literal.as_str()),
+        }
+    }
+}
+
+/// A comparison of two operands.
+#[derive(Debug, Clone, PartialEq)]
+struct Comparison {
+    /// The left side.
+    left: Operand,
+
+    /// The orderings for which the comparison holds.
+    holds: Vec<Ordering>,
+
+    /// The right side.
+    right: Operand,
+}
+
+impl Condition {
+    /// Parse a condition.
+    pub fn new(source: &str) -> Result<Condition> {
+        let tokens = tokenize(source)?;
+        let mut any = vec![vec![]];
+        let mut rest = tokens.as_slice();
+
+        loop {
+            let [left, operator, right, tail @ ..] = rest else {
+                return Err(anyhow!("expected a comparison like `threads <= 8`"));
+            };
+
+            let (Token::Operand(left), Token::Operator(operator), Token::Operand(right)) =
+                (left, operator, right)
+            else {
+                return Err(anyhow!("expected a comparison like `threads <= 8`"));
+            };
+
+            let holds = match operator.as_str() {
+                "==" => vec![Ordering::Equal],
+                "!=" => vec![Ordering::Less, Ordering::Greater],
+                "<" => vec![Ordering::Less],
+                "<=" => vec![Ordering::Less, Ordering::Equal],
+                ">" => vec![Ordering::Greater],
+                ">=" => vec![Ordering::Greater, Ordering::Equal],
+                other => return Err(anyhow!("expected a comparison, found `{other}`")),
+            };
+
+            if let Some(all) = any.last_mut() {
+                all.push(Comparison {
+                    left: left.clone(),
+                    holds,
+                    right: right.clone(),
+                });
+            }
+
+            match tail {
+                [] => break,
+                [Token::Operator(joint), tail @ ..] if joint == "&&" => rest = tail,
+                [Token::Operator(joint), tail @ ..] if joint == "||" => {
+                    any.push(vec![]);
+                    rest = tail;
+                }
+                _ => return Err(anyhow!("expected `&&` or `||` after a comparison")),
+            }
+        }
+
+        Ok(Condition {
+            source: source.to_string(),
+            any,
+        })
+    }
+
+    /// The parameters that the condition uses.
+    pub fn parameters(&self) -> impl Iterator<Item = &str> {
+        self.any
+            .iter()
+            .flatten()
+            .flat_map(|c| [&c.left, &c.right])
+            .filter_map(|operand| match operand {
+                Operand::Parameter(name) => Some(name.as_str()),
+                Operand::Literal(_) => None,
+            })
+    }
+
+    /// Whether the condition holds for these parameter values, or [`None`] if
+    /// one of its parameters has no value.
+    pub fn evaluate(&self, values: &BTreeMap<String, String>) -> Option<bool> {
+        let mut result = false;
+
+        for all in &self.any {
+            let mut holds = true;
+
+            for comparison in all {
+                let ordering = compare(
+                    comparison.left.value(values)?,
+                    comparison.right.value(values)?,
+                );
+                holds &= comparison.holds.contains(&ordering);
+            }
+
+            result |= holds;
+        }
+
+        Some(result)
+    }
+}
+
+/// Compare two values as numbers if they both are, and as text otherwise.
+fn compare(left: &str, right: &str) -> Ordering {
+    match (left.trim().parse::<f64>(), right.trim().parse::<f64>()) {
+        (Ok(left), Ok(right)) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+        _ => left.cmp(right),
+    }
+}
+
+/// A part of a condition.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A parameter, number or quoted string.
+    Operand(Operand),
+
+    /// A comparison, `&&` or `||`.
+    Operator(String),
+}
+
+/// The characters that end a word in a condition.
+const SEPARATORS: &str = "=!<>&|'\"";
+
+/// Split a condition into its parts.
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '\'' || c == '"' {
+            chars.next();
+
+            let mut text = String::new();
+
+            loop {
+                match chars.next() {
+                    Some(x) if x == c => break,
+                    Some(x) => text.push(x),
+                    None => return Err(anyhow!("the string {c}{text} is not closed")),
+                }
+            }
+
+            tokens.push(Token::Operand(Operand::Literal(text)));
+        } else if "=!<>&|".contains(c) {
+            chars.next();
+
+            let mut operator = c.to_string();
+            if let Some(&second) = chars.peek() {
+                if matches!(
+                    (c, second),
+                    ('=', '=') | ('!', '=') | ('<', '=') | ('>', '=') | ('&', '&') | ('|', '|')
+                ) {
+                    operator.push(second);
+                    chars.next();
+                }
+            }
+
+            tokens.push(Token::Operator(operator));
+        } else {
+            let mut word = String::new();
+
+            while let Some(&x) = chars.peek() {
+                if x.is_whitespace() || SEPARATORS.contains(x) {
+                    break;
+                }
+
+                word.push(x);
+                chars.next();
+            }
+
+            let operand = if word.parse::<f64>().is_ok() {
+                Operand::Literal(word)
+            } else if word.starts_with(|x: char| x.is_alphabetic() || x == '_')
+                && word
+                    .chars()
+                    .all(|x| x.is_alphanumeric() || x == '_' || x == '.')
+            {
+                Operand::Parameter(word)
+            } else {
+                return Err(anyhow!("`{word}` is neither a parameter nor a number"));
+            };
+
+            tokens.push(Token::Operand(operand));
+        }
+    }
+
+    Ok(tokens)
+}
+
+impl PartialEq for Condition {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Serialize for Condition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Condition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// The visitor for conditions.
+        struct ConditionVisitor;
+
+        impl Visitor<'_> for ConditionVisitor {
+            type Value = Condition;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a condition like \"threads <= 8\"")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Condition::new(v).map_err(|e| {
+                    serde::de::Error::custom(format!("This is not a valid condition: {e}"))
+                })
+            }
+        }
+
+        deserializer.deserialize_str(ConditionVisitor)
+    }
+}
+
+impl JsonSchema for Condition {
+    fn schema_name() -> String {
+        "Condition".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        String::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/constraints.rs"]
+mod tests;
@@ -33,6 +33,27 @@ where
     deserializer.deserialize_str(DurationVisitor {})
 }
 
+/// Deserializing an optional duration from a human-readable string.
+pub fn deserialize_optional_human_time_duration<'de, D>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_human_time_duration(deserializer).map(Some)
+}
+
+/// Serialize an optional duration into a human-readable format
+pub fn serialize_optional_duration<S: Serializer>(
+    duration: &Option<Duration>,
+    ser: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serialize_duration(duration, ser),
+        None => S::serialize_none(ser),
+    }
+}
+
 /// Serialize duration into a human-readable format
 pub fn serialize_duration<S: Serializer>(duration: &Duration, ser: S) -> Result<S::Ok, S::Error> {
     S::serialize_str(ser, &humantime::format_duration(*duration).to_string())
@@ -0,0 +1,205 @@
+use std::cmp::Ordering;
+
+use anyhow::Context;
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::Regex;
+use crate::bailc;
+use crate::measurement::MetricValue;
+
+/// The name of the capture group with the value, if a regex has several.
+pub const VALUE_GROUP: &str = "value";
+
+/// A value that is read from the output of every run with a regex.
+///
+/// # Examples
+///
+/// ```toml
+/// [extract.nodes]
+/// regex = "explored (?<value>\\d+) nodes"
+/// source = "stdout"
+/// type = "int"
+/// aggregate = "max"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Extraction {
+    /// The regex, with a named capture group around the value.
+    pub regex: Regex,
+
+    /// The output that the regex is matched against.
+    #[serde(default)]
+    pub source: OutputSource,
+
+    /// The type of the value.
+    #[serde(default, rename = "type")]
+    pub value_type: ValueType,
+
+    /// Which value is used if the regex matches more than once.
+    #[serde(default)]
+    pub aggregate: Aggregation,
+}
+
+/// An output of a run.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputSource {
+    /// The standard output of the program.
+    #[default]
+    Stdout,
+
+    /// The standard error of the program.
+    Stderr,
+
+    /// The output of the afterscript of the program.
+    Afterscript,
+}
+
+/// The type of an extracted value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    /// A whole number.
+    Int,
+
+    /// A number.
+    Float,
+
+    /// A duration like `1.5s` or `2m 3s`, or a number of seconds, which is
+    /// stored in seconds.
+    Duration,
+
+    /// Any text.
+    #[default]
+    String,
+}
+
+/// Which of several matches of an extraction is used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregation {
+    /// The first match.
+    First,
+
+    /// The last match.
+    #[default]
+    Last,
+
+    /// The largest value.
+    Max,
+
+    /// The smallest value.
+    Min,
+}
+
+impl Extraction {
+    /// Check that the regex has a capture group for the value.
+    pub fn validate(&self, name: &str) -> Result<()> {
+        if self.group().is_none() {
+            bailc!(
+              "The regex of extraction {name} has no capture group for the value", ;
+              "The value is captured by a named group like `(?<value>\\d+)`", ;
+              "Name the group `{VALUE_GROUP}` if the regex has more than one",
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The name of the capture group with the value: `value`, or the only
+    /// named group.
+    fn group(&self) -> Option<&str> {
+        let names: Vec<&str> = self.regex.capture_names().flatten().collect();
+
+        match names.as_slice() {
+            [name] => Some(name),
+            _ => names.into_iter().find(|name| *name == VALUE_GROUP),
+        }
+    }
+
+    /// The value in the text, or [`None`] if the regex does not match.
+    ///
+    /// Matches that are not of the type of the value are skipped.
+    pub fn extract(&self, text: &str) -> Option<MetricValue> {
+        let group = self.group()?;
+
+        let mut values = self
+            .regex
+            .captures_iter(text)
+            .filter_map(|captures| self.value_type.parse(captures.name(group)?.as_str()));
+
+        match self.aggregate {
+            Aggregation::First => values.next(),
+            Aggregation::Last => values.last(),
+            Aggregation::Max => values.max_by(compare),
+            Aggregation::Min => values.min_by(compare),
+        }
+    }
+}
+
+impl ValueType {
+    /// Read a value of this type.
+    pub fn parse(self, text: &str) -> Option<MetricValue> {
+        let text = text.trim();
+
+        match self {
+            ValueType::Int => text.parse().ok().map(MetricValue::Int),
+            ValueType::Float => text.parse().ok().map(MetricValue::Float),
+            ValueType::Duration => parse_seconds(text).map(MetricValue::Float),
+            ValueType::String => Some(MetricValue::Text(text.to_string())),
+        }
+    }
+}
+
+/// The seconds in a duration like `2m 3s` or `1.5s`, or in a number.
+fn parse_seconds(text: &str) -> Option<f64> {
+    if let Ok(seconds) = text.parse() {
+        return Some(seconds);
+    }
+
+    if let Ok(duration) = humantime::parse_duration(text) {
+        return Some(duration.as_secs_f64());
+    }
+
+    // A fraction with a single unit.
+    [
+        ("ms", 0.001),
+        ("s", 1.0),
+        ("min", 60.0),
+        ("m", 60.0),
+        ("h", 3600.0),
+    ]
+    .into_iter()
+    .find_map(|(unit, seconds)| {
+        let number: f64 = text.strip_suffix(unit)?.trim().parse().ok()?;
+        Some(number * seconds)
+    })
+}
+
+/// Compare two values of the same type.
+fn compare(a: &MetricValue, b: &MetricValue) -> Ordering {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => a.to_string().cmp(&b.to_string()),
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/extract.rs"]
+mod tests;
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use toml::Table;
+use toml::Value;
+
+use super::interpolation::interpolate_table;
+use super::interpolation::Variables;
+use crate::bailc;
+use crate::ctx;
+use crate::file_system::FileOperations;
+
+/// The key of the list of files that a configuration includes.
+pub const INCLUDE_KEY: &str = "include";
+
+/// The file that every value of a merged table came from, by its dotted key.
+type Origins = BTreeMap<String, PathBuf>;
+
+/// Merge the files included by a configuration into it.
+///
+/// The rules are:
+/// - Tables, such as `[slurm]` and the entries of `[program]`, `[input]`,
+///   `[parameter]` and `[label]`, are merged key by key.
+/// - Any other value, including a list, is replaced as a whole.
+/// - A file overrides the values of the files that it includes.
+/// - Files that are included side by side may not set the same key to
+///   different values.
+///
+/// The paths in `include` are relative to the file that includes them.
+/// The variables in the included files are resolved and added to
+/// `variables`, those of `table` should be resolved already.
+pub fn merge_includes(
+    path: &Path,
+    table: Table,
+    variables: &mut Variables,
+    fs: &impl FileOperations,
+) -> Result<Table> {
+    let mut stack = vec![fs.canonicalize(path)?];
+
+    Ok(merge_file(path, table, &mut stack, variables, fs)?.0)
+}
+
+/// Merge the includes of one file, `stack` holds the files that are being
+/// included to detect cycles.
+fn merge_file(
+    path: &Path,
+    mut table: Table,
+    stack: &mut Vec<PathBuf>,
+    variables: &mut Variables,
+    fs: &impl FileOperations,
+) -> Result<(Table, Origins)> {
+    let includes = match table.remove(INCLUDE_KEY) {
+        None => vec![],
+        Some(Value::Array(includes)) => includes,
+        Some(_) => {
+            bailc!(
+                "The `{INCLUDE_KEY}` of {path:?} is not a list", ;
+                "It lists the files that are merged into this one", ;
+                "Write it as {INCLUDE_KEY} = [\"common.toml\"]",
+            );
+        }
+    };
+
+    let mut merged = Table::new();
+    let mut origins = Origins::new();
+
+    for include in includes {
+        let Some(include) = include.as_str() else {
+            bailc!(
+                "The `{INCLUDE_KEY}` of {path:?} contains {include}", ;
+                "It lists the paths of the files that are merged into this one", ;
+                "Write the paths as strings",
+            );
+        };
+
+        let include_path = path.parent().unwrap_or(Path::new("")).join(include);
+        let canonical = fs.canonicalize(&include_path).with_context(ctx!(
+            "Could not find the file {include:?} included by {path:?}", ;
+            "Included paths are relative to the file that includes them",
+        ))?;
+
+        if stack.contains(&canonical) {
+            bailc!(
+                "{include_path:?} includes itself", ;
+                "It is included by {path:?}, which it includes", ;
+                "Remove one of the includes",
+            );
+        }
+
+        let mut included: Table = fs.try_read_toml(&include_path)?;
+        interpolate_table(&mut included, variables, &include_path)?;
+
+        stack.push(canonical);
+        let (included, included_origins) =
+            merge_file(&include_path, included, stack, variables, fs)?;
+        stack.pop();
+
+        merge_siblings(
+            &mut merged,
+            &mut origins,
+            included,
+            &included_origins,
+            "",
+            path,
+        )?;
+    }
+
+    override_with(&mut merged, &mut origins, table, path, "");
+
+    Ok((merged, origins))
+}
+
+/// Merge a table into another that was included next to it, failing if they
+/// set a key to different values.
+fn merge_siblings(
+    into: &mut Table,
+    origins: &mut Origins,
+    from: Table,
+    from_origins: &Origins,
+    prefix: &str,
+    includer: &Path,
+) -> Result<()> {
+    for (name, value) in from {
+        let key = format!("{prefix}{name}");
+
+        match (into.get_mut(&name), value) {
+            (Some(Value::Table(existing)), Value::Table(value)) => {
+                merge_siblings(
+                    existing,
+                    origins,
+                    value,
+                    from_origins,
+                    &format!("{key}."),
+                    includer,
+                )?;
+            }
+            (Some(existing), value) if *existing == value => {}
+            (Some(_), _) => {
+                let (first, second) = (origin(origins, &key), origin(from_origins, &key));
+
+                bailc!(
+                    "The key `{key}` is set to different values in {first:?} and {second:?}", ;
+                    "Files that are included side by side cannot override each other", ;
+                    "Set `{key}` in only one of them, or in {includer:?} to override both",
+                );
+            }
+            (None, value) => {
+                for (k, file) in from_origins {
+                    if *k == key || k.starts_with(&format!("{key}.")) {
+                        origins.insert(k.clone(), file.clone());
+                    }
+                }
+
+                into.insert(name, value);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Merge a table into another, its values take precedence.
+fn override_with(into: &mut Table, origins: &mut Origins, from: Table, file: &Path, prefix: &str) {
+    for (name, value) in from {
+        let key = format!("{prefix}{name}");
+
+        match (into.get_mut(&name), value) {
+            (Some(Value::Table(existing)), Value::Table(value)) => {
+                override_with(existing, origins, value, file, &format!("{key}."));
+            }
+            (_, value) => {
+                origins.retain(|k, _| *k != key && !k.starts_with(&format!("{key}.")));
+                record(origins, &key, &value, file);
+
+                into.insert(name, value);
+            }
+        }
+    }
+}
+
+/// Record the file that a value and everything in it came from.
+fn record(origins: &mut Origins, key: &str, value: &Value, file: &Path) {
+    match value {
+        Value::Table(table) => {
+            for (name, value) in table {
+                record(origins, &format!("{key}.{name}"), value, file);
+            }
+        }
+        _ => {
+            origins.insert(key.to_string(), file.to_path_buf());
+        }
+    }
+}
+
+/// The file that a key, or the first value in it, came from.
+fn origin<'a>(origins: &'a Origins, key: &str) -> &'a Path {
+    origins
+        .iter()
+        .find(|(k, _)| *k == key || k.starts_with(&format!("{key}.")))
+        .map_or(Path::new("?"), |(_, file)| file.as_path())
+}
+
+#[cfg(test)]
+#[path = "tests/include.rs"]
+mod tests;
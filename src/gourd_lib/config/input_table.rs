@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+
+use super::InputSchema;
+use super::SchemaInput;
+use super::UserInput;
+use crate::ctx;
+use crate::file_system::FileOperations;
+use crate::measurement::MetricValue;
+
+/// The column of a CSV input table with the names of the inputs.
+pub const NAME_COLUMN: &str = "name";
+
+/// The column of a CSV input table with the input files.
+pub const FILE_COLUMN: &str = "file";
+
+/// The column of a CSV input table with globs of input files.
+pub const GLOB_COLUMN: &str = "glob";
+
+/// The column of a CSV input table with the arguments, separated by spaces.
+pub const ARGUMENTS_COLUMN: &str = "arguments";
+
+/// The column of a CSV input table with the groups of the inputs.
+pub const GROUP_COLUMN: &str = "group";
+
+/// Read the inputs of an input schema.
+///
+/// A file ending in `.csv` is a table with a header, where the `name`,
+/// `file`, `glob`, `arguments` and `group` columns set those fields of the
+/// inputs, and every other column is metadata. Any other file is a TOML list
+/// of `[[input]]`s.
+pub fn read_input_table(path: &Path, fs: &impl FileOperations) -> Result<Vec<SchemaInput>> {
+    if path.extension().is_some_and(|extension| extension == "csv") {
+        read_csv_table(path, &fs.read_utf8(path)?)
+    } else {
+        Ok(fs.try_read_toml::<InputSchema>(path)?.inputs)
+    }
+}
+
+/// Read the inputs of a CSV input table from its contents.
+///
+/// A metadata column has the type that all of its values have: integer,
+/// float, boolean or else text. Empty cells are left out.
+pub fn read_csv_table(path: &Path, contents: &str) -> Result<Vec<SchemaInput>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(contents.as_bytes());
+
+    let header: Vec<String> = reader
+        .headers()
+        .with_context(ctx!(
+          "Could not read the header of {path:?}", ;
+          "The first line of an input table names its columns",
+        ))?
+        .iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut rows = vec![];
+
+    for (idx, record) in reader.records().enumerate() {
+        let record = record.with_context(ctx!(
+          "Could not read row {} of {path:?}", idx + 1;
+          "Every row needs a value for every column, which may be empty",
+        ))?;
+
+        rows.push(
+            header
+                .iter()
+                .zip(record.iter())
+                .filter(|(_, cell)| !cell.is_empty())
+                .map(|(column, cell)| (column.as_str(), cell.to_string()))
+                .collect::<BTreeMap<&str, String>>(),
+        );
+    }
+
+    let types: BTreeMap<&str, ColumnType> = header
+        .iter()
+        .map(|column| {
+            let cells = rows.iter().filter_map(|row| row.get(column.as_str()));
+            (column.as_str(), ColumnType::of(cells))
+        })
+        .collect();
+
+    Ok(rows
+        .into_iter()
+        .map(|mut row| {
+            let input = UserInput {
+                file: row.remove(FILE_COLUMN).map(PathBuf::from),
+                glob: row.remove(GLOB_COLUMN),
+                fetch: None,
+                group: row.remove(GROUP_COLUMN),
+                arguments: row
+                    .remove(ARGUMENTS_COLUMN)
+                    .map(|arguments| arguments.split_whitespace().map(str::to_string).collect())
+                    .unwrap_or_default(),
+                env: BTreeMap::new(),
+                metadata: BTreeMap::new(),
+                resource_limits: None,
+            };
+
+            let name = row.remove(NAME_COLUMN);
+
+            let metadata = row
+                .into_iter()
+                .map(|(column, cell)| (column.to_string(), types[column].parse(&cell)))
+                .collect();
+
+            SchemaInput {
+                name,
+                input: UserInput { metadata, ..input },
+            }
+        })
+        .collect())
+}
+
+/// The type of the values in a metadata column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    /// Every value is a whole number.
+    Int,
+
+    /// Every value is a number.
+    Float,
+
+    /// Every value is `true` or `false`.
+    Bool,
+
+    /// Anything else.
+    Text,
+}
+
+impl ColumnType {
+    /// The narrowest type that all of these values have.
+    fn of<'a>(cells: impl Iterator<Item = &'a String> + Clone) -> ColumnType {
+        let all = |parses: fn(&str) -> bool| cells.clone().all(|cell| parses(cell));
+
+        if all(|cell| cell.parse::<i64>().is_ok()) {
+            ColumnType::Int
+        } else if all(|cell| cell.parse::<f64>().is_ok()) {
+            ColumnType::Float
+        } else if all(|cell| cell.parse::<bool>().is_ok()) {
+            ColumnType::Bool
+        } else {
+            ColumnType::Text
+        }
+    }
+
+    /// A value of this type.
+    fn parse(self, cell: &str) -> MetricValue {
+        match self {
+            ColumnType::Int => cell.parse().map(MetricValue::Int).ok(),
+            ColumnType::Float => cell.parse().map(MetricValue::Float).ok(),
+            ColumnType::Bool => cell.parse().map(MetricValue::Bool).ok(),
+            ColumnType::Text => None,
+        }
+        .unwrap_or_else(|| MetricValue::Text(cell.to_string()))
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/input_table.rs"]
+mod tests;
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+
+use anyhow::Context;
+use anyhow::Result;
+use toml::Table;
+use toml::Value;
+
+use crate::bailc;
+
+/// The environment variables that a configuration uses and their values, `~`
+/// is recorded as the home folder.
+pub type Variables = BTreeMap<String, String>;
+
+/// Resolve `${VAR}`, `${VAR:-default}` and a leading `~` in every string of a
+/// configuration file.
+///
+/// Returns whether anything was resolved, the values of the variables that
+/// were used are added to `variables`.
+pub fn interpolate_table(
+    table: &mut Table,
+    variables: &mut Variables,
+    file: &Path,
+) -> Result<bool> {
+    let mut changed = false;
+
+    for (name, value) in table.iter_mut() {
+        changed |= interpolate_value(value, name, variables, file)?;
+    }
+
+    Ok(changed)
+}
+
+/// Resolve the variables in a value and everything in it, `key` is its
+/// dotted name.
+fn interpolate_value(
+    value: &mut Value,
+    key: &str,
+    variables: &mut Variables,
+    file: &Path,
+) -> Result<bool> {
+    let mut changed = false;
+
+    match value {
+        Value::String(text) => {
+            let resolved = interpolate(text, key, variables, file)?;
+
+            if resolved != *text {
+                *text = resolved;
+                changed = true;
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter_mut().enumerate() {
+                changed |= interpolate_value(value, &format!("{key}[{i}]"), variables, file)?;
+            }
+        }
+        Value::Table(table) => {
+            for (name, value) in table.iter_mut() {
+                changed |= interpolate_value(value, &format!("{key}.{name}"), variables, file)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(changed)
+}
+
+/// Resolve the variables in one string.
+///
+/// `$${` is written as a literal `${`.
+pub fn interpolate(
+    text: &str,
+    key: &str,
+    variables: &mut Variables,
+    file: &Path,
+) -> Result<String> {
+    let mut result = String::new();
+    let mut rest = text;
+
+    if rest == "~" || rest.starts_with("~/") {
+        let home = shellexpand::tilde("~").to_string();
+
+        variables.insert("~".to_string(), home.clone());
+        result.push_str(&home);
+        rest = &rest[1..];
+    }
+
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            result.push_str(&rest[..start]);
+            result.push('{');
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        result.push_str(&rest[..start]);
+
+        let Some(end) = rest[start..].find('}') else {
+            bailc!(
+                "The variable in `{key}` of {file:?} is not closed", ;
+                "The value is {text:?}", ;
+                "Write variables as ${{NAME}} or ${{NAME:-default}}",
+            );
+        };
+
+        let inner = &rest[start + 2..start + end];
+        let (name, default) = match inner.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (inner, None),
+        };
+
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            bailc!(
+                "Invalid variable name {name:?} in `{key}` of {file:?}", ;
+                "Variable names consist of letters, digits and underscores", ;
+                "Write $${{ for a literal ${{",
+            );
+        }
+
+        let value = match (env::var(name), default) {
+            (Ok(value), default) if !value.is_empty() || default.is_none() => value,
+            (_, Some(default)) => default.to_string(),
+            (_, None) => {
+                bailc!(
+                    "The environment variable {name} is not set", ;
+                    "It is used in `{key}` of {file:?}", ;
+                    "Set it, or give it a default value like ${{{name}:-default}}",
+                );
+            }
+        };
+
+        result.push_str(&value);
+        variables.insert(name.to_string(), value);
+
+        rest = &rest[start + end + 1..];
+    }
+
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+#[path = "tests/interpolation.rs"]
+mod tests;
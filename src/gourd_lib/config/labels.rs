@@ -0,0 +1,374 @@
+use std::collections::BTreeMap;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::ops::RangeInclusive;
+
+use anyhow::anyhow;
+use anyhow::Context;
+use anyhow::Result;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::InstanceType;
+use schemars::schema::Schema;
+use schemars::schema::SchemaObject;
+use schemars::schema::SingleOrVec;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::limits::LimitExpression;
+use super::Label;
+use super::Regex;
+use crate::bailc;
+
+/// The signals that a label can match, without the `SIG` prefix, and their
+/// numbers on this system.
+#[cfg(unix)]
+const SIGNALS: [(&str, i32); 18] = [
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("ILL", libc::SIGILL),
+    ("TRAP", libc::SIGTRAP),
+    ("ABRT", libc::SIGABRT),
+    ("BUS", libc::SIGBUS),
+    ("FPE", libc::SIGFPE),
+    ("KILL", libc::SIGKILL),
+    ("USR1", libc::SIGUSR1),
+    ("SEGV", libc::SIGSEGV),
+    ("USR2", libc::SIGUSR2),
+    ("PIPE", libc::SIGPIPE),
+    ("ALRM", libc::SIGALRM),
+    ("TERM", libc::SIGTERM),
+    ("XCPU", libc::SIGXCPU),
+    ("XFSZ", libc::SIGXFSZ),
+    ("SYS", libc::SIGSYS),
+];
+
+/// The signals that a label can match, of which there are none on this
+/// system.
+#[cfg(not(unix))]
+const SIGNALS: [(&str, i32); 0] = [];
+
+/// What is known about a finished run when its labels are assigned.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunOutcome<'a> {
+    /// The exit code of the program.
+    pub exit_code: i32,
+
+    /// The signal that terminated the program, if any.
+    pub signal: Option<i32>,
+
+    /// The standard output of the program, if it was read.
+    pub stdout: Option<&'a str>,
+
+    /// The standard error of the program, if it was read.
+    pub stderr: Option<&'a str>,
+
+    /// The output of the afterscript, if it ran.
+    pub afterscript: Option<&'a str>,
+
+    /// The numbers that the metric thresholds can use.
+    pub variables: BTreeMap<String, f64>,
+}
+
+impl Label {
+    /// Check that the label matches something and knows its signals.
+    pub fn validate(&self, name: &str) -> Result<()> {
+        if self.regex.is_none()
+            && self.stdout.is_none()
+            && self.stderr.is_none()
+            && self.exit_codes.is_none()
+            && self.signals.is_empty()
+            && self.metrics.is_empty()
+        {
+            bailc!(
+              "Label {name} has nothing to match", ;
+              "A label is assigned to the runs that match all of its conditions", ;
+              "Set at least one of `regex`, `stdout`, `stderr`, `exit_codes`, `signals` or `metrics`",
+            );
+        }
+
+        for signal in &self.signals {
+            if signal_number(signal).is_none() {
+                bailc!(
+                  "Label {name} matches the unknown signal {signal:?}", ;
+                  "", ;
+                  "Use one of {}", SIGNALS.map(|(name, _)| name).join(", "),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the label reads the standard output or error of a run.
+    pub fn reads_streams(&self) -> bool {
+        self.stdout.is_some() || self.stderr.is_some()
+    }
+
+    /// Whether a run with this outcome matches all conditions of the label.
+    ///
+    /// An output that was not read matches no regex.
+    pub fn matches(&self, outcome: &RunOutcome) -> bool {
+        let text = |regex: &Option<Regex>, text: Option<&str>| {
+            regex
+                .as_ref()
+                .is_none_or(|regex| text.is_some_and(|text| regex.is_match(text)))
+        };
+
+        text(&self.regex, outcome.afterscript)
+            && text(&self.stdout, outcome.stdout)
+            && text(&self.stderr, outcome.stderr)
+            && self
+                .exit_codes
+                .as_ref()
+                .is_none_or(|codes| codes.contains(outcome.exit_code))
+            && (self.signals.is_empty()
+                || outcome.signal.is_some_and(|signal| {
+                    self.signals
+                        .iter()
+                        .any(|name| signal_number(name) == Some(signal))
+                }))
+            && self
+                .metrics
+                .iter()
+                .all(|threshold| threshold.holds(&outcome.variables))
+    }
+}
+
+/// The number of a signal like `SEGV`, `sigsegv` or `11`.
+pub fn signal_number(name: &str) -> Option<i32> {
+    let upper = name.trim().to_ascii_uppercase();
+    let short = upper.strip_prefix("SIG").unwrap_or(&upper);
+
+    if let Ok(number) = short.parse() {
+        return Some(number);
+    }
+
+    SIGNALS
+        .iter()
+        .find(|(signal, _)| *signal == short)
+        .map(|(_, number)| *number)
+}
+
+/// Exit codes and ranges of exit codes, like `"1-127, 255"`, or a single code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "CodesSource", into = "CodesSource")]
+pub struct ExitCodes {
+    /// The codes as they were written.
+    source: String,
+
+    /// The ranges of codes.
+    ranges: Vec<RangeInclusive<i32>>,
+}
+
+/// Exit codes as they are written in a configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum CodesSource {
+    /// A single code.
+    Code(i32),
+
+    /// A list of codes and ranges.
+    Text(String),
+}
+
+impl ExitCodes {
+    /// Parse a comma separated list of codes and ranges like `128-255`.
+    pub fn new(source: &str) -> Result<ExitCodes> {
+        let ranges = source
+            .split(',')
+            .map(|part| {
+                let part = part.trim();
+
+                if let Ok(code) = part.parse() {
+                    return Ok(code..=code);
+                }
+
+                let (from, to) = part
+                    .split_once('-')
+                    .ok_or_else(|| anyhow!("`{part}` is not an exit code or a range"))?;
+                let parse = |x: &str| {
+                    x.trim()
+                        .parse::<i32>()
+                        .map_err(|_| anyhow!("`{x}` is not an exit code"))
+                };
+                let (from, to) = (parse(from)?, parse(to)?);
+
+                if from > to {
+                    return Err(anyhow!("the range `{part}` is empty"));
+                }
+
+                Ok(from..=to)
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(ExitCodes {
+            source: source.to_string(),
+            ranges,
+        })
+    }
+
+    /// Whether the code is one of these.
+    pub fn contains(&self, code: i32) -> bool {
+        self.ranges.iter().any(|range| range.contains(&code))
+    }
+}
+
+impl TryFrom<CodesSource> for ExitCodes {
+    type Error = String;
+
+    fn try_from(source: CodesSource) -> Result<Self, Self::Error> {
+        match source {
+            CodesSource::Code(code) => ExitCodes::new(&code.to_string()),
+            CodesSource::Text(text) => ExitCodes::new(&text),
+        }
+        .map_err(|e| format!("These are not valid exit codes: {e}"))
+    }
+}
+
+impl From<ExitCodes> for CodesSource {
+    fn from(codes: ExitCodes) -> Self {
+        match codes.source.parse() {
+            Ok(code) => CodesSource::Code(code),
+            Err(_) => CodesSource::Text(codes.source),
+        }
+    }
+}
+
+impl PartialEq for ExitCodes {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for ExitCodes {}
+
+impl Hash for ExitCodes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+    }
+}
+
+impl JsonSchema for ExitCodes {
+    fn schema_name() -> String {
+        "ExitCodes".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(SingleOrVec::Vec(vec![
+                InstanceType::String,
+                InstanceType::Integer,
+            ])),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// A comparison of two expressions, like `wall_time > 0.9 * time_limit`.
+///
+/// The expressions are written like resource limits, with the metrics of a
+/// run as variables.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Threshold {
+    /// The comparison as it was written.
+    source: String,
+
+    /// The left side.
+    left: LimitExpression,
+
+    /// One of `<`, `<=`, `>`, `>=`, `==` and `!=`.
+    operator: String,
+
+    /// The right side.
+    right: LimitExpression,
+}
+
+impl Threshold {
+    /// Parse a comparison.
+    pub fn new(source: &str) -> Result<Threshold> {
+        let Some(start) = source.find(['<', '>', '=', '!']) else {
+            return Err(anyhow!("there is no comparison like `>` or `<=`"));
+        };
+
+        let operator = ["<=", ">=", "==", "!=", "<", ">"]
+            .into_iter()
+            .find(|operator| source[start..].starts_with(operator))
+            .ok_or_else(|| anyhow!("`{}` is not a comparison", &source[start..start + 1]))?;
+
+        let side = |text: &str| {
+            LimitExpression::new(text.trim()).map_err(|e| anyhow!("in `{}`: {e}", text.trim()))
+        };
+
+        Ok(Threshold {
+            source: source.to_string(),
+            left: side(&source[..start])?,
+            operator: operator.to_string(),
+            right: side(&source[start + operator.len()..])?,
+        })
+    }
+
+    /// Whether the comparison holds for these variables.
+    ///
+    /// It does not hold if a side uses a variable that the run does not have.
+    pub fn holds(&self, variables: &BTreeMap<String, f64>) -> bool {
+        let (Ok(left), Ok(right)) = (self.left.value(variables), self.right.value(variables))
+        else {
+            return false;
+        };
+
+        match self.operator.as_str() {
+            "<" => left < right,
+            "<=" => left <= right,
+            ">" => left > right,
+            ">=" => left >= right,
+            "==" => left == right,
+            _ => left != right,
+        }
+    }
+}
+
+impl TryFrom<String> for Threshold {
+    type Error = String;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Threshold::new(&source).map_err(|e| format!("This is not a valid threshold: {e}"))
+    }
+}
+
+impl From<Threshold> for String {
+    fn from(threshold: Threshold) -> Self {
+        threshold.source
+    }
+}
+
+impl PartialEq for Threshold {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Threshold {}
+
+impl Hash for Threshold {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+    }
+}
+
+impl JsonSchema for Threshold {
+    fn schema_name() -> String {
+        "Threshold".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        generator.subschema_for::<String>()
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/labels.rs"]
+mod tests;
@@ -0,0 +1,542 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use anyhow::Context;
+use anyhow::Result;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::InstanceType;
+use schemars::schema::Schema;
+use schemars::schema::SchemaObject;
+use schemars::schema::SingleOrVec;
+use schemars::JsonSchema;
+use serde::de::Visitor;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+use super::slurm::ResourceLimits;
+use crate::bailc;
+use crate::ctx;
+use crate::measurement::MetricValue;
+
+/// Resource limits that replace those of the program for some of its runs.
+///
+/// Every limit is a number or an expression of the metadata of the input and
+/// the values of the parameters.
+///
+/// # Examples
+///
+/// ```toml
+/// [input.large]
+/// file = "large.graph"
+/// metadata = { n = 20000 }
+/// resource_limits = { time_limit = "10s * n / 1000", mem_per_cpu = 4096 }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LimitOverrides {
+    /// The time limit, a duration like `"2h"` or an expression in seconds.
+    pub time_limit: Option<LimitExpression>,
+
+    /// The number of CPUs.
+    pub cpus: Option<LimitExpression>,
+
+    /// The memory in MB per CPU.
+    pub mem_per_cpu: Option<LimitExpression>,
+}
+
+/// A group of inputs.
+///
+/// # Examples
+///
+/// ```toml
+/// [group.hard]
+/// resource_limits = { time_limit = "1h" }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InputGroup {
+    /// The resource limits of the runs on the inputs of this group.
+    pub resource_limits: Option<LimitOverrides>,
+}
+
+/// Which resource limits of a run the user set, the wrapper only enforces
+/// these.
+///
+/// The number of CPUs is not enforced on its own, it scales the CPU time and
+/// the memory that a run may use.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, JsonSchema)]
+pub struct EnforcedLimits {
+    /// Whether the wall clock time and the CPU time are limited.
+    #[serde(default)]
+    pub time_limit: bool,
+
+    /// Whether the memory is limited.
+    #[serde(default)]
+    pub mem_per_cpu: bool,
+}
+
+impl EnforcedLimits {
+    /// Every limit is enforced.
+    pub const ALL: EnforcedLimits = EnforcedLimits {
+        time_limit: true,
+        mem_per_cpu: true,
+    };
+
+    /// Whether any limit is enforced.
+    pub fn any(&self) -> bool {
+        self.time_limit || self.mem_per_cpu
+    }
+
+    /// The limits that are enforced in either.
+    pub fn or(self, other: EnforcedLimits) -> EnforcedLimits {
+        EnforcedLimits {
+            time_limit: self.time_limit || other.time_limit,
+            mem_per_cpu: self.mem_per_cpu || other.mem_per_cpu,
+        }
+    }
+}
+
+impl LimitOverrides {
+    /// Whether no limit is replaced.
+    pub fn is_empty(&self) -> bool {
+        *self == LimitOverrides::default()
+    }
+
+    /// These limits, and the limits of `general` that these do not set.
+    pub fn or(&self, general: &LimitOverrides) -> LimitOverrides {
+        LimitOverrides {
+            time_limit: self.time_limit.clone().or(general.time_limit.clone()),
+            cpus: self.cpus.clone().or(general.cpus.clone()),
+            mem_per_cpu: self.mem_per_cpu.clone().or(general.mem_per_cpu.clone()),
+        }
+    }
+
+    /// The limits that these set, and so are enforced.
+    pub fn enforced(&self) -> EnforcedLimits {
+        EnforcedLimits {
+            time_limit: self.time_limit.is_some(),
+            mem_per_cpu: self.mem_per_cpu.is_some(),
+        }
+    }
+
+    /// Replace the limits that are set, with the expressions evaluated for
+    /// these variables.
+    pub fn apply(
+        &self,
+        limits: ResourceLimits,
+        variables: &BTreeMap<String, f64>,
+    ) -> Result<ResourceLimits> {
+        let mut limits = limits;
+
+        if let Some(time_limit) = &self.time_limit {
+            limits.time_limit = time_limit.duration(variables)?;
+        }
+
+        if let Some(cpus) = &self.cpus {
+            limits.cpus = cpus.count(variables)?;
+        }
+
+        if let Some(mem_per_cpu) = &self.mem_per_cpu {
+            limits.mem_per_cpu = mem_per_cpu.count(variables)?;
+        }
+
+        Ok(limits)
+    }
+}
+
+/// The numbers that the expressions of a run can use: the numeric metadata
+/// of its input and the numeric values of its parameters.
+pub fn limit_variables<'a>(
+    metadata: &BTreeMap<String, MetricValue>,
+    parameters: impl Iterator<Item = (&'a String, &'a String)>,
+) -> BTreeMap<String, f64> {
+    let mut variables: BTreeMap<String, f64> = metadata
+        .iter()
+        .filter_map(|(key, value)| match value {
+            MetricValue::Int(x) => Some((key.clone(), *x as f64)),
+            MetricValue::Float(x) => Some((key.clone(), *x)),
+            MetricValue::Text(x) => x.trim().parse().ok().map(|x| (key.clone(), x)),
+            _ => None,
+        })
+        .collect();
+
+    for (name, value) in parameters {
+        if let Ok(value) = value.trim().parse() {
+            variables.insert(name.clone(), value);
+        }
+    }
+
+    variables
+}
+
+/// A resource limit as a number or an expression like `10s * n / 1000`.
+///
+/// An expression adds, subtracts, multiplies and divides numbers, durations
+/// like `10s` and variables, with parentheses. A duration is its number of
+/// seconds.
+#[derive(Debug, Clone)]
+pub struct LimitExpression {
+    /// The expression as it was written.
+    source: String,
+
+    /// The parsed expression.
+    expression: Expression,
+}
+
+/// A part of an expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    /// A number, durations are in seconds.
+    Number(f64),
+
+    /// A metadata value or a parameter value.
+    Variable(String),
+
+    /// The negation of an expression.
+    Negate(Box<Expression>),
+
+    /// An operator, one of `+-*/`, and its two sides.
+    Binary(Box<Expression>, char, Box<Expression>),
+}
+
+impl LimitExpression {
+    /// Parse an expression, or a duration like `"2h 30m"`.
+    pub fn new(source: &str) -> Result<LimitExpression> {
+        if let Ok(duration) = humantime::parse_duration(source) {
+            return Ok(LimitExpression {
+                source: source.to_string(),
+                expression: Expression::Number(duration.as_secs_f64()),
+            });
+        }
+
+        let tokens = tokenize(source)?;
+        let mut rest = tokens.as_slice();
+
+        let expression = parse_sum(&mut rest)?;
+
+        if let Some(token) = rest.first() {
+            return Err(anyhow!("unexpected {token:?}"));
+        }
+
+        Ok(LimitExpression {
+            source: source.to_string(),
+            expression,
+        })
+    }
+
+    /// The value of the expression for these variables.
+    pub fn evaluate(&self, variables: &BTreeMap<String, f64>) -> Result<f64> {
+        let value = self.value(variables).with_context(ctx!(
+          "The limit {:?} cannot be computed for a run", self.source;
+          "",
+        ))?;
+
+        if !value.is_finite() || value <= 0.0 {
+            bailc!(
+              "The limit {:?} is not positive for this run", self.source;
+              "It is {value}", ;
+              "Check the metadata of the input and the values of the parameters",
+            );
+        }
+
+        Ok(value)
+    }
+
+    /// The value of the expression for these variables, which can be zero or
+    /// negative.
+    pub fn value(&self, variables: &BTreeMap<String, f64>) -> Result<f64> {
+        evaluate(&self.expression, variables)
+    }
+
+    /// The value as a duration, in seconds.
+    pub fn duration(&self, variables: &BTreeMap<String, f64>) -> Result<Duration> {
+        Ok(Duration::from_secs_f64(self.evaluate(variables)?))
+    }
+
+    /// The value rounded up to a whole number.
+    pub fn count(&self, variables: &BTreeMap<String, f64>) -> Result<usize> {
+        Ok(self.evaluate(variables)?.ceil() as usize)
+    }
+}
+
+/// Compute the value of an expression.
+fn evaluate(expression: &Expression, variables: &BTreeMap<String, f64>) -> Result<f64> {
+    Ok(match expression {
+        Expression::Number(x) => *x,
+        Expression::Variable(name) => {
+            let Some(value) = variables.get(name) else {
+                bailc!(
+                  "Unknown variable {name}", ;
+                  "A limit can use the numeric metadata of the input and the numeric parameters", ;
+                  "Give every input of this program a number for {name}",
+                );
+            };
+
+            *value
+        }
+        Expression::Negate(inner) => -evaluate(inner, variables)?,
+        Expression::Binary(left, operator, right) => {
+            let (left, right) = (evaluate(left, variables)?, evaluate(right, variables)?);
+
+            match operator {
+                '+' => left + right,
+                '-' => left - right,
+                '*' => left * right,
+                _ => left / right,
+            }
+        }
+    })
+}
+
+/// Parse terms joined by `+` and `-`.
+fn parse_sum(tokens: &mut &[Token]) -> Result<Expression> {
+    let mut left = parse_product(tokens)?;
+
+    while let Some(Token::Operator(operator @ ('+' | '-'))) = tokens.first() {
+        *tokens = &tokens[1..];
+        left = Expression::Binary(Box::new(left), *operator, Box::new(parse_product(tokens)?));
+    }
+
+    Ok(left)
+}
+
+/// Parse factors joined by `*` and `/`.
+fn parse_product(tokens: &mut &[Token]) -> Result<Expression> {
+    let mut left = parse_factor(tokens)?;
+
+    while let Some(Token::Operator(operator @ ('*' | '/'))) = tokens.first() {
+        *tokens = &tokens[1..];
+        left = Expression::Binary(Box::new(left), *operator, Box::new(parse_factor(tokens)?));
+    }
+
+    Ok(left)
+}
+
+/// Parse a number, a variable, a negation or an expression in parentheses.
+fn parse_factor(tokens: &mut &[Token]) -> Result<Expression> {
+    let Some((first, rest)) = tokens.split_first() else {
+        return Err(anyhow!("the expression ends too early"));
+    };
+
+    *tokens = rest;
+
+    match first {
+        Token::Number(x) => Ok(Expression::Number(*x)),
+        Token::Variable(name) => Ok(Expression::Variable(name.clone())),
+        Token::Operator('-') => Ok(Expression::Negate(Box::new(parse_factor(tokens)?))),
+        Token::Operator('(') => {
+            let inner = parse_sum(tokens)?;
+
+            match tokens.split_first() {
+                Some((Token::Operator(')'), rest)) => {
+                    *tokens = rest;
+                    Ok(inner)
+                }
+                _ => Err(anyhow!("a `(` is not closed")),
+            }
+        }
+        Token::Operator(operator) => Err(anyhow!("unexpected `{operator}`")),
+    }
+}
+
+/// A part of an expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A number or a duration in seconds.
+    Number(f64),
+
+    /// A metadata value or a parameter value.
+    Variable(String),
+
+    /// One of `+-*/()`.
+    Operator(char),
+}
+
+/// The seconds in a unit of time.
+fn unit_seconds(unit: &str) -> Option<f64> {
+    match unit {
+        "ms" => Some(0.001),
+        "s" | "sec" => Some(1.0),
+        "m" | "min" => Some(60.0),
+        "h" => Some(3600.0),
+        "d" => Some(86400.0),
+        _ => None,
+    }
+}
+
+/// Split an expression into its parts.
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if "+-*/()".contains(c) {
+            chars.next();
+            tokens.push(Token::Operator(c));
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+
+            while let Some(&x) = chars.peek().filter(|x| x.is_ascii_digit() || **x == '.') {
+                number.push(x);
+                chars.next();
+            }
+
+            // An exponent like `1e3` or `2.5E-1`, but not a unit that starts
+            // with an `e`.
+            let mut ahead = chars.clone();
+            if ahead.next().is_some_and(|x| x == 'e' || x == 'E') {
+                let sign = ahead.next_if(|x| *x == '+' || *x == '-');
+
+                if ahead.peek().is_some_and(|x| x.is_ascii_digit()) {
+                    number.extend(chars.next());
+                    number.extend(sign);
+                    chars = ahead;
+
+                    while let Some(x) = chars.next_if(|x| x.is_ascii_digit()) {
+                        number.push(x);
+                    }
+                }
+            }
+
+            let mut unit = String::new();
+
+            while let Some(&x) = chars.peek().filter(|x| x.is_alphabetic()) {
+                unit.push(x);
+                chars.next();
+            }
+
+            let value: f64 = number
+                .parse()
+                .map_err(|_| anyhow!("`{number}` is not a number"))?;
+
+            let seconds = if unit.is_empty() {
+                1.0
+            } else {
+                unit_seconds(&unit).ok_or_else(|| anyhow!("`{unit}` is not a unit of time"))?
+            };
+
+            tokens.push(Token::Number(value * seconds));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut name = String::new();
+
+            while let Some(&x) = chars
+                .peek()
+                .filter(|x| x.is_alphanumeric() || **x == '_' || **x == '.')
+            {
+                name.push(x);
+                chars.next();
+            }
+
+            tokens.push(Token::Variable(name));
+        } else {
+            return Err(anyhow!("unexpected `{c}`"));
+        }
+    }
+
+    Ok(tokens)
+}
+
+impl PartialEq for LimitExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for LimitExpression {}
+
+impl Hash for LimitExpression {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.source.hash(state);
+    }
+}
+
+impl Serialize for LimitExpression {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.source.parse::<u64>() {
+            Ok(number) => serializer.serialize_u64(number),
+            Err(_) => serializer.serialize_str(&self.source),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LimitExpression {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        /// The visitor for limit expressions.
+        struct LimitVisitor;
+
+        impl Visitor<'_> for LimitVisitor {
+            type Value = LimitExpression;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a number or an expression like \"10s * n / 1000\"")
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                self.visit_str(&v.to_string())
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                LimitExpression::new(v).map_err(|e| {
+                    serde::de::Error::custom(format!("This is not a valid limit: {e}"))
+                })
+            }
+        }
+
+        deserializer.deserialize_any(LimitVisitor)
+    }
+}
+
+impl JsonSchema for LimitExpression {
+    fn schema_name() -> String {
+        "LimitExpression".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(SingleOrVec::Vec(vec![
+                InstanceType::String,
+                InstanceType::Number,
+            ])),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/limits.rs"]
+mod tests;
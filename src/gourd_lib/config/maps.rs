@@ -1,5 +1,4 @@
 use std::collections::BTreeMap;
-use std::collections::HashSet;
 use std::env::current_dir;
 use std::mem::swap;
 use std::path::Path;
@@ -64,13 +63,12 @@ pub fn expand_argument_globs(
     let mut result = BTreeMap::new();
 
     for (original, input) in inputs {
-        let mut globset = HashSet::new();
-        globset.insert(input.clone());
+        let mut globset = vec![input.clone()];
 
         let mut is_glob = false;
 
         for arg_index in 0..input.arguments.len() {
-            let mut next_globset = HashSet::new();
+            let mut next_globset = Vec::new();
 
             for input_instance in &globset {
                 is_glob |=
@@ -101,7 +99,7 @@ fn explode_glob_set(
     input: &UserInput,
     input_name: &str, // only used for warnings.
     arg_index: usize,
-    fill: &mut HashSet<UserInput>,
+    fill: &mut Vec<UserInput>,
     fs: &impl FileOperations,
 ) -> Result<bool> {
     let arg = &input.arguments[arg_index];
@@ -125,7 +123,9 @@ fn explode_glob_set(
             .with_context(ctx!("",;"",))?
             .to_string();
 
-            fill.insert(glob_instance);
+            if !fill.contains(&glob_instance) {
+                fill.push(glob_instance);
+            }
         }
 
         Ok(true)
@@ -144,7 +144,9 @@ fn explode_glob_set(
             "
             );
         }
-        fill.insert(input.clone());
+        if !fill.contains(input) {
+            fill.push(input.clone());
+        }
         Ok(false)
     }
 }
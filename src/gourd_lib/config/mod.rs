@@ -1,21 +1,27 @@
 use std::collections::BTreeMap;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::Context;
 use anyhow::Result;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
+use toml::Value;
 
+use crate::bailc;
 use crate::constants::CMD_STYLE;
 use crate::constants::EMPTY_ARGS;
 use crate::constants::INTERNAL_PREFIX;
 use crate::constants::INTERNAL_SCHEMA_INPUTS;
+use crate::constants::RANGE_STEP_DEFAULT;
 use crate::constants::RERUN_LABEL_BY_DEFAULT;
 use crate::constants::WRAPPER_DEFAULT;
 use crate::error::ctx;
 use crate::file_system::FileOperations;
 use crate::file_system::FileSystemInteractor;
+use crate::measurement::MetricValue;
 
 /// Deserializer for the duration.
 mod duration;
@@ -29,19 +35,66 @@ mod regex;
 /// Deserializer for the paramters (grid search).
 pub mod parameters;
 
+/// Constraints on the combinations of parameter values.
+pub mod constraints;
+
+/// Sampling the combinations of parameter values.
+pub mod sampling;
+
+/// Placeholders for run information in arguments.
+pub mod placeholders;
+
 /// Fetching for resources.
 pub mod fetching;
 
+/// Resolving environment variables in a configuration.
+pub mod interpolation;
+
+/// Merging the files included by a configuration.
+pub mod include;
+
+/// Reading the inputs of an input schema from a TOML or CSV table.
+pub mod input_table;
+
+/// Choosing which inputs the programs run on.
+pub mod selection;
+
+/// Resource limits of inputs, groups and parameter values.
+pub mod limits;
+
+/// Reading values from the output of runs.
+pub mod extract;
+
+/// Matching labels against the outcome of runs.
+pub mod labels;
+
 /// Slurm configuration.
 pub mod slurm;
 
+/// Asking programs to stop before their time limit.
+pub mod termination;
+
 pub use regex::Regex;
 
+use crate::config::constraints::Constraints;
+use crate::config::extract::Extraction;
+use crate::config::include::merge_includes;
+use crate::config::include::INCLUDE_KEY;
+use crate::config::input_table::read_input_table;
+use crate::config::interpolation::interpolate_table;
+use crate::config::interpolation::Variables;
+use crate::config::labels::ExitCodes;
+use crate::config::labels::Threshold;
+use crate::config::limits::InputGroup;
+use crate::config::limits::LimitOverrides;
+use crate::config::sampling::Sampling;
+use crate::config::selection::InputSelector;
+use crate::config::selection::Pairing;
 use crate::config::slurm::ResourceLimits;
 use crate::config::slurm::SlurmConfig;
 
 /// A pair of a path to a binary and cli arguments.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct UserProgram {
     /// A path to the executable.
@@ -73,13 +126,78 @@ pub struct UserProgram {
     #[serde(default)]
     pub resource_limits: Option<ResourceLimits>,
 
+    /// The signal to send to the program before its time limit, for example
+    /// `SIGTERM` or `SIGUSR1`.
+    #[serde(default)]
+    pub termination_signal: Option<String>,
+
+    /// How long before the time limit the `termination_signal` is sent, the
+    /// program is killed once the time limit is reached.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_optional_human_time_duration",
+        serialize_with = "duration::serialize_optional_duration"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub grace_period: Option<Duration>,
+
+    /// How many measured runs to create for every input of this program,
+    /// overrides the experiment-wide `repetitions`.
+    #[serde(default)]
+    pub repetitions: Option<usize>,
+
+    /// The seeds of the measured runs of this program, overrides the
+    /// experiment-wide `seeds` and `repetitions`.
+    #[serde(default)]
+    pub seeds: Option<Seeds>,
+
+    /// How many times to execute the program and discard it before every
+    /// measured run, overrides the experiment-wide `warmup`.
+    #[serde(default)]
+    pub warmup: Option<usize>,
+
+    /// Environment variables to set for the runs of this program, these
+    /// override the experiment-wide `env`.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
     /// The programs to postprocess this one.
     #[serde(default)]
     pub next: Vec<String>,
+
+    /// Only run on the inputs that match one of these names, which can be
+    /// patterns like `graph_*`.
+    #[serde(default)]
+    pub inputs: Option<Vec<String>>,
+
+    /// Never run on the inputs that match one of these names.
+    #[serde(default)]
+    pub exclude_inputs: Vec<String>,
+
+    /// Only run on the inputs of these groups.
+    #[serde(default)]
+    pub groups: Option<Vec<String>>,
+
+    /// Never run on the inputs of these groups.
+    #[serde(default)]
+    pub exclude_groups: Vec<String>,
+}
+
+impl UserProgram {
+    /// The inputs that this program runs on.
+    pub fn selector(&self) -> InputSelector {
+        InputSelector {
+            inputs: self.inputs.clone(),
+            exclude_inputs: self.exclude_inputs.clone(),
+            groups: self.groups.clone(),
+            exclude_groups: self.exclude_groups.clone(),
+        }
+    }
 }
 
 /// An algorithm fetched from a git repository.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct GitProgram {
     /// The URI to the git repository.
@@ -96,7 +214,7 @@ pub struct GitProgram {
 }
 
 /// Fetch a remote resource
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq, JsonSchema)]
 pub struct FetchedResource<const PERMISSIONS: u32> {
     /// The url from which to fetch this resource
     pub url: String,
@@ -118,7 +236,7 @@ pub struct FetchedResource<const PERMISSIONS: u32> {
 /// ```
 ///
 /// Will run `test a b c`
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct UserInput {
     /// Direct path to the input.
@@ -142,25 +260,57 @@ pub struct UserInput {
     /// By default these will be empty.
     #[serde(default = "EMPTY_ARGS")]
     pub arguments: Vec<String>,
+
+    /// Environment variables to set for the runs on this input, these
+    /// override the `env` of the program.
+    ///
+    /// Values can be parameters, like arguments.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
+    /// Values that describe this input, like its size, by which the runs can
+    /// be grouped, filtered and plotted in `gourd analyse`.
+    #[serde(default)]
+    pub metadata: BTreeMap<String, MetricValue>,
+
+    /// The resource limits of the runs on this input, which override those
+    /// of its group and of the program.
+    pub resource_limits: Option<LimitOverrides>,
 }
 
 /// ### TOML struct that can be used to provide inputs.
 /// structure is:
 /// ```toml
 /// [[input]]
-/// input = "/path/to/input"
+/// file = "/path/to/input"
 /// arguments = [ "arg1", "arg2" ]
 ///
 /// [[input]]
-/// input = "/path/to/input2"
+/// name = "second"
+/// file = "/path/to/input2"
 /// arguments = [ "arg1", "arg2" ]
+/// metadata = { n = 100 }
 /// ```
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Hash, Eq)]
+///
+/// The inputs can also be given as a CSV table, see [`input_table`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct InputSchema {
     /// 0 or more `[[input]]` instances
     #[serde(rename = "input")]
-    pub inputs: Vec<UserInput>,
+    pub inputs: Vec<SchemaInput>,
+}
+
+/// An input of an input schema, which can be given a name.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct SchemaInput {
+    /// The name of the input, by default the name of its file or its index.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// The input itself.
+    #[serde(flatten)]
+    pub input: UserInput,
 }
 
 /// A parameter.
@@ -186,7 +336,7 @@ pub struct InputSchema {
 /// `test 1 b`
 /// `test 2 a`
 /// `test 2 b`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash, Eq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Parameter {
     /// Sub-parameters of this parameter.
@@ -198,6 +348,83 @@ pub struct Parameter {
     ///
     /// To be used exclusively without sub (parameter).
     pub values: Option<Vec<String>>,
+
+    /// Numbers from `start` up to, but not including, `stop`.
+    pub range: Option<NumericRange>,
+
+    /// Evenly spaced numbers from `start` to `stop`.
+    pub linspace: Option<Spacing>,
+
+    /// Numbers from `start` to `stop` that are evenly spaced on a log scale.
+    pub logspace: Option<Spacing>,
+
+    /// How the numbers of a `range`, `linspace` or `logspace` are written.
+    pub format: Option<NumberFormat>,
+
+    /// The number of decimals of `float` numbers.
+    pub precision: Option<usize>,
+
+    /// The resource limits of the runs with some of the values of this
+    /// parameter, keyed by the value.
+    pub resource_limits: Option<BTreeMap<String, LimitOverrides>>,
+}
+
+/// The numbers `start`, `start + step`, ... before `stop`.
+///
+/// # Examples
+///
+/// ```toml
+/// [parameter.threads]
+/// range = { start = 1, stop = 9, step = 2 }
+/// ```
+///
+/// Has the values `1`, `3`, `5` and `7`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NumericRange {
+    /// The first number.
+    pub start: f64,
+
+    /// The bound that the numbers stay below, or above for a negative step.
+    pub stop: f64,
+
+    /// The difference between two numbers.
+    #[serde(default = "RANGE_STEP_DEFAULT")]
+    pub step: f64,
+}
+
+/// `num` numbers from `start` to `stop`, both included.
+///
+/// # Examples
+///
+/// ```toml
+/// [parameter.alpha]
+/// logspace = { start = 0.01, stop = 1.0, num = 3 }
+/// ```
+///
+/// Has the values `0.01`, `0.1` and `1`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Spacing {
+    /// The first number.
+    pub start: f64,
+
+    /// The last number.
+    pub stop: f64,
+
+    /// How many numbers there are.
+    pub num: usize,
+}
+
+/// How generated numbers are written in arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NumberFormat {
+    /// Rounded to the nearest integer, like `3`.
+    Integer,
+
+    /// As a decimal number, like `0.25`.
+    Float,
 }
 
 /// A subparameter.
@@ -222,7 +449,7 @@ pub struct Parameter {
 /// `test 1 15`
 /// `test 2 60`
 /// `test 3 30`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash, Eq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SubParameter {
     /// The values of sub parameter.
@@ -234,24 +461,111 @@ pub struct SubParameter {
 
 /// Options for configuring the execution of
 /// `gourd run local`
-#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Hash, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Hash, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct LocalOptions {
     /// The number of threads to use for parallel execution of jobs locally.
     pub num_threads: usize,
 }
 
-/// A label that can be assigned to a job based on the afterscript output.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash, Eq)]
+/// Options for configuring how the wrapper measures runs.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize, Hash, Eq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MeasurementOptions {
+    /// Measure every run in its own cgroup v2, which also accounts for the
+    /// processes that the program spawns. Only available on Linux.
+    #[serde(default)]
+    pub cgroup: bool,
+
+    /// Record the resource usage of the running program at this interval.
+    /// Only available on Linux.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "duration::deserialize_optional_human_time_duration",
+        serialize_with = "duration::serialize_optional_duration"
+    )]
+    #[schemars(with = "Option<String>")]
+    pub sample_interval: Option<Duration>,
+}
+
+/// The seeds of the replicates of a program-input pair.
+///
+/// Either a list of seeds:
+/// ```toml
+/// seeds = [3, 14, 15]
+/// ```
+/// or a number of seeds counting up from a base seed:
+/// ```toml
+/// seeds = { count = 10, base = 42 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash, Eq, JsonSchema)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum Seeds {
+    /// An explicit list of seeds.
+    List(Vec<u64>),
+
+    /// `count` seeds, starting at `base`.
+    Count {
+        /// How many seeds to use.
+        count: usize,
+
+        /// The first seed.
+        #[serde(default)]
+        base: u64,
+    },
+}
+
+impl Seeds {
+    /// The seeds, in the order of the replicates.
+    pub fn values(&self) -> Vec<u64> {
+        match self {
+            Seeds::List(seeds) => seeds.clone(),
+            Seeds::Count { count, base } => {
+                (0..*count as u64).map(|i| base.wrapping_add(i)).collect()
+            }
+        }
+    }
+}
+
+/// A label that is assigned to the runs that match all of its conditions.
+///
+/// # Examples
+///
+/// ```toml
+/// [label.slow_crash]
+/// exit_codes = "1-127"
+/// stderr = "panicked"
+/// metrics = ["wall_time > 0.9 * time_limit"]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash, Eq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Label {
     /// The regex to run over the afterscript output. If there's a match, this
     /// label is assigned.
-    pub regex: Regex,
+    pub regex: Option<Regex>,
+
+    /// A regex that has to match the standard output of the run.
+    pub stdout: Option<Regex>,
+
+    /// A regex that has to match the standard error of the run.
+    pub stderr: Option<Regex>,
+
+    /// The exit codes that the run can have, like `"1-127, 255"`.
+    pub exit_codes: Option<ExitCodes>,
+
+    /// The signals that can have terminated the run, like `"SEGV"`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub signals: Vec<String>,
+
+    /// Comparisons of the metrics of the run that all have to hold, like
+    /// `"max_rss > 1000000"`, where `max_rss` is in KiB as reported in the
+    /// rusage of the run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metrics: Vec<Threshold>,
 
-    /// The priority of the label. Higher numbers mean higher priority, and if
-    /// label is present it will override lower priority labels, even if
-    /// they are also present.
+    /// The priority of the label. Higher numbers mean higher priority, and
+    /// the labels of a run are listed from the highest priority down.
     #[serde(default)]
     pub priority: u64,
 
@@ -268,7 +582,7 @@ pub struct Label {
 // 2. will it break user workflows?
 // 3. update the tests
 // 4. update the user documentation
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Config {
     // Basic settings.
@@ -291,19 +605,76 @@ pub struct Config {
     #[serde(rename = "input")]
     pub inputs: BTreeMap<String, UserInput>,
 
-    /// A path to a TOML file that contains input combinations.
+    /// A path to a TOML or CSV file that contains more inputs.
     pub input_schema: Option<PathBuf>,
 
     /// The list of parameters.
     #[serde(rename = "parameter")]
     pub parameters: Option<BTreeMap<String, Parameter>>,
 
+    /// The combinations of parameter values to leave out.
+    pub constraints: Option<Constraints>,
+
+    /// Run a sample of the combinations of parameter values.
+    ///
+    /// ### Default
+    /// By default every combination is run.
+    pub sampling: Option<Sampling>,
+
+    /// The programs and the inputs that they run on.
+    ///
+    /// ### Default
+    /// By default every program runs on every input that it selects.
+    #[serde(rename = "pairing")]
+    pub pairings: Option<Vec<Pairing>>,
+
+    /// The groups of inputs, with their resource limits.
+    #[serde(rename = "group")]
+    pub groups: Option<BTreeMap<String, InputGroup>>,
+
     /// If running on a SLURM cluster, the job configurations.
     pub slurm: Option<SlurmConfig>,
 
     /// If running on a SLURM cluster, the initial global resource limits.
     pub resource_limits: Option<ResourceLimits>,
 
+    /// How many measured runs to create for every program-input pair.
+    ///
+    /// ### Default
+    /// By default every pair is run once.
+    pub repetitions: Option<usize>,
+
+    /// The seeds of the measured runs of every program-input pair, one run is
+    /// created for every seed.
+    ///
+    /// ### Default
+    /// By default runs have no seed, and `{seed}` is the index of the
+    /// repetition.
+    #[serde(default)]
+    pub seeds: Option<Seeds>,
+
+    /// How many times to execute the program of every run and discard it
+    /// before the run is measured, in the same job.
+    ///
+    /// ### Default
+    /// By default runs are not warmed up.
+    pub warmup: Option<usize>,
+
+    /// Environment variables to set for all runs.
+    pub env: Option<BTreeMap<String, String>>,
+
+    /// Start runs with an empty environment, except for the variables in
+    /// `env_whitelist` and the ones set by `env`.
+    #[serde(default)]
+    pub clean_env: bool,
+
+    /// The variables that are passed on to runs when `clean_env` is enabled.
+    ///
+    /// ### Default
+    /// By default `PATH`, `HOME`, `USER`, `LOGNAME`, `SHELL`, `TMPDIR` and
+    /// `TERM` are kept.
+    pub env_whitelist: Option<Vec<String>>,
+
     //
     // Advanced settings.
     /// The command to execute to get to the wrapper.
@@ -317,7 +688,10 @@ pub struct Config {
     /// `gourd run local`
     pub local: Option<LocalOptions>,
 
-    /// Allow custom labels to be assigned based on the afterscript output.
+    /// Options for configuring how runs are measured.
+    pub measurement: Option<MeasurementOptions>,
+
+    /// Allow custom labels to be assigned based on the outcome of runs.
     ///
     /// syntax is:
     /// ```toml
@@ -330,6 +704,18 @@ pub struct Config {
     /// ```
     #[serde(rename = "label")]
     pub labels: Option<BTreeMap<String, Label>>,
+
+    /// The values that are read from the output of every run with a regex.
+    ///
+    /// ### Default
+    /// By default nothing is read from the output.
+    #[serde(rename = "extract")]
+    pub extractions: Option<BTreeMap<String, Extraction>>,
+
+    /// The environment variables that were resolved in the configuration,
+    /// with their values.
+    #[serde(skip)]
+    pub variables: Variables,
 }
 
 // An implementation that provides a default value of `Config`,
@@ -345,10 +731,23 @@ impl Default for Config {
             inputs: BTreeMap::default(),
             input_schema: None,
             parameters: None,
+            constraints: None,
+            sampling: None,
+            pairings: None,
+            groups: None,
+            extractions: None,
             slurm: None,
             resource_limits: None,
+            repetitions: None,
+            seeds: None,
+            warmup: None,
+            env: None,
+            clean_env: false,
+            env_whitelist: None,
             local: None,
+            measurement: None,
             labels: Some(BTreeMap::new()),
+            variables: Variables::new(),
         }
     }
 }
@@ -358,12 +757,34 @@ impl Config {
     /// Returns a valid `Config` or an explanatory
     /// `GourdError::ConfigLoadError`.
     pub fn from_file(path: &Path, fs: &FileSystemInteractor) -> Result<Config> {
-        let mut initial: Config = fs.try_read_toml(path).with_context(ctx!(
+        let mut table: toml::Table = fs.try_read_toml(path).with_context(ctx!(
+          "Could not parse {path:?}", ;
+          "More help and examples can be found with \
+          {CMD_STYLE}man gourd.toml{CMD_STYLE:#}",
+        ))?;
+        let mut variables = Variables::new();
+
+        let interpolated = interpolate_table(&mut table, &mut variables, path)?;
+
+        // Parsing the file itself keeps the locations in the error messages.
+        let parsed = if table.contains_key(INCLUDE_KEY) {
+            Value::Table(merge_includes(path, table, &mut variables, fs)?)
+                .try_into()
+                .map_err(anyhow::Error::from)
+        } else if interpolated {
+            Value::Table(table).try_into().map_err(anyhow::Error::from)
+        } else {
+            fs.try_read_toml(path)
+        };
+
+        let mut initial: Config = parsed.with_context(ctx!(
           "Could not parse {path:?}", ;
           "More help and examples can be found with \
           {CMD_STYLE}man gourd.toml{CMD_STYLE:#}",
         ))?;
 
+        initial.variables = variables;
+
         if let Some(schema) = &initial.input_schema {
             initial.inputs = Config::parse_schema_inputs(schema.as_path(), initial.inputs, fs)?;
             initial.input_schema = None;
@@ -372,19 +793,45 @@ impl Config {
         Ok(initial)
     }
 
-    /// Parse the additional inputs toml file and add them to the inputs map.
+    /// Parse the additional inputs file and add them to the inputs map.
+    ///
+    /// An input is called by its `name`, or else by the name of its file if no
+    /// other input has a file of that name, or else by its index in the file.
     pub fn parse_schema_inputs(
         path_buf: &Path,
         mut inputs: BTreeMap<String, UserInput>,
         fs: &impl FileOperations,
     ) -> Result<BTreeMap<String, UserInput>> {
-        let hi = fs.try_read_toml::<InputSchema>(path_buf)?;
-
-        for (idx, input) in hi.inputs.iter().enumerate() {
-            inputs.insert(
-                format!("{idx}{INTERNAL_PREFIX}{INTERNAL_SCHEMA_INPUTS}"),
-                input.clone(),
-            );
+        let table = read_input_table(path_buf, fs)?;
+
+        let stems: Vec<Option<String>> = table
+            .iter()
+            .map(|schema_input| {
+                let file = schema_input.input.file.as_ref()?;
+                Some(file.file_stem()?.to_string_lossy().to_string())
+            })
+            .collect();
+
+        for (idx, schema_input) in table.into_iter().enumerate() {
+            // Files with the same name in different folders are told apart
+            // by their index.
+            let stem = stems[idx]
+                .clone()
+                .filter(|stem| stems.iter().flatten().filter(|s| *s == stem).count() == 1);
+
+            let name = schema_input.name.or(stem).unwrap_or(idx.to_string());
+
+            let key = format!("{name}{INTERNAL_PREFIX}{INTERNAL_SCHEMA_INPUTS}");
+
+            if inputs.contains_key(&key) {
+                bailc!(
+                    "Two inputs in {path_buf:?} are called {name}", ;
+                    "Inputs are called by their name, or else by the name of their file or their index", ;
+                    "Give the inputs different names",
+                );
+            }
+
+            inputs.insert(key, schema_input.input);
         }
 
         Ok(inputs)
@@ -4,38 +4,264 @@ use std::collections::BTreeSet;
 use anyhow::anyhow;
 use anyhow::Context;
 use anyhow::Result;
+use log::debug;
 use log::trace;
 
+use super::constraints::Constraints;
+use super::placeholders::fill_some;
+use super::placeholders::placeholders;
+use super::placeholders::PARAMETER_PLACEHOLDER_PREFIX;
+use super::sampling::grid;
+use super::NumberFormat;
+use super::NumericRange;
 use super::Parameter;
+use super::SubParameter;
 use super::UserInput;
+use super::UserProgram;
 use crate::bailc;
 use crate::constants::INTERNAL_PARAMETER;
 use crate::constants::INTERNAL_PREFIX;
 use crate::constants::PARAMETER_ESCAPE;
+use crate::constants::PARAMETER_SIGNIFICANT_DIGITS;
 use crate::constants::SUB_PARAMETER_ESCAPE;
 use crate::ctx;
 
+/// One combination of parameter values: its name, the arguments with the
+/// values filled in and the value of every parameter that was used.
+type Combination = (String, Vec<String>, BTreeMap<String, String>);
+
+/// The slots that a parameter is used in, and the subparameter used in each.
+type Slots = Vec<(usize, Option<String>)>;
+
+/// A variant of a program and the values of its parameters.
+pub type ProgramVariant = (UserProgram, BTreeMap<String, String>);
+
+/// A variant of an input and the values of its parameters.
+pub type InputVariant = (UserInput, BTreeMap<String, String>);
+
+/// The points of the [`ParameterSpace`] of some programs or inputs that are
+/// built, by their name. The others are built with every combination.
+pub type Points = BTreeMap<String, BTreeSet<Vec<usize>>>;
+
 /// Check if the parameters are well-formed.
 pub fn validate_parameters(parameters: &BTreeMap<String, Parameter>) -> Result<()> {
     for (p_name, p) in parameters {
+        let generated = p.range.is_some() || p.linspace.is_some() || p.logspace.is_some();
+
+        let sources = [
+            p.sub.is_some(),
+            p.values.is_some(),
+            p.range.is_some(),
+            p.linspace.is_some(),
+            p.logspace.is_some(),
+        ]
+        .into_iter()
+        .filter(|source| *source)
+        .count();
+
         if p.sub.is_some() && p.values.is_some() {
             bailc!(
               "Parameter specified incorrectly", ;
               "Parameter can have either values or subparameters, not both", ;
               "Parameter name {p_name}",
             );
-        } else if p.sub.is_none() && p.values.is_none() {
+        } else if sources == 0 {
             bailc!(
               "Parameter specified incorrectly", ;
               "Parameter must have either values or subparameters, currently has none", ;
+              "Parameter name {p_name}, it can also have a range, linspace or logspace",
+            );
+        } else if sources > 1 {
+            bailc!(
+              "Parameter specified incorrectly", ;
+              "Parameter can have only one of values, sub, range, linspace and logspace", ;
+              "Parameter name {p_name}",
+            );
+        }
+
+        if !generated && (p.format.is_some() || p.precision.is_some()) {
+            bailc!(
+              "Parameter specified incorrectly", ;
+              "Only the numbers of a range, linspace or logspace have a format and precision", ;
               "Parameter name {p_name}",
             );
         }
+
+        if p.format == Some(NumberFormat::Integer) && p.precision.is_some() {
+            bailc!(
+              "Parameter specified incorrectly", ;
+              "Integers do not have a precision", ;
+              "Parameter name {p_name}, remove the precision or use format = \"float\"",
+            );
+        }
+
+        if let Some(range) = p.range {
+            if ![range.start, range.stop, range.step]
+                .iter()
+                .all(|x| x.is_finite())
+            {
+                bailc!(
+                  "Parameter specified incorrectly", ;
+                  "The start, stop and step of a range must be finite numbers", ;
+                  "Parameter name {p_name}",
+                );
+            }
+
+            if range_length(&range) == 0 {
+                bailc!(
+                  "Parameter specified incorrectly", ;
+                  "The range from {} has no numbers", range.start;
+                  "Parameter name {p_name}, the step must go from the start towards the stop",
+                );
+            }
+        }
+
+        for (kind, spacing) in [("linspace", p.linspace), ("logspace", p.logspace)] {
+            let Some(spacing) = spacing else {
+                continue;
+            };
+
+            if !spacing.start.is_finite() || !spacing.stop.is_finite() {
+                bailc!(
+                  "Parameter specified incorrectly", ;
+                  "The start and stop of a {kind} must be finite numbers", ;
+                  "Parameter name {p_name}",
+                );
+            }
+
+            if spacing.num == 0 {
+                bailc!(
+                  "Parameter specified incorrectly", ;
+                  "The {kind} has no numbers", ;
+                  "Parameter name {p_name}, set `num` to 1 or more",
+                );
+            }
+
+            if kind == "logspace" && (spacing.start <= 0.0 || spacing.stop <= 0.0) {
+                bailc!(
+                  "Parameter specified incorrectly", ;
+                  "The start and stop of a logspace must be positive", ;
+                  "Parameter name {p_name}",
+                );
+            }
+        }
+
+        let values = parameter_values(p).unwrap_or_default();
+
+        for value in p.resource_limits.iter().flat_map(BTreeMap::keys) {
+            if !values.contains(value) {
+                bailc!(
+                  "Parameter specified incorrectly", ;
+                  "Parameter {p_name} has resource limits for {value:?}, which is not one of its values", ;
+                  "Its values are {values:?}",
+                );
+            }
+        }
     }
 
     Ok(())
 }
 
+/// The values of a parameter, either given as a list or generated from a
+/// `range`, `linspace` or `logspace`.
+///
+/// Returns [`None`] if the parameter has subparameters instead.
+pub fn parameter_values(p: &Parameter) -> Option<Vec<String>> {
+    if let Some(values) = &p.values {
+        return Some(values.clone());
+    }
+
+    let (numbers, integral) = if let Some(range) = p.range {
+        let numbers = (0..range_length(&range))
+            .map(|i| range.start + i as f64 * range.step)
+            .collect();
+
+        // A range of whole numbers is written as integers by default.
+        (
+            numbers,
+            range.start.fract() == 0.0 && range.step.fract() == 0.0,
+        )
+    } else if let Some(linspace) = p.linspace {
+        (spaced(linspace.start, linspace.stop, linspace.num), false)
+    } else if let Some(logspace) = p.logspace {
+        let exponents = spaced(logspace.start.ln(), logspace.stop.ln(), logspace.num);
+        let mut numbers: Vec<f64> = exponents.into_iter().map(f64::exp).collect();
+
+        // The ends are exact, even if the logarithms are not.
+        if let Some(first) = numbers.first_mut() {
+            *first = logspace.start;
+        }
+        if let (Some(last), true) = (numbers.last_mut(), logspace.num > 1) {
+            *last = logspace.stop;
+        }
+
+        (numbers, false)
+    } else {
+        return None;
+    };
+
+    let format = p.format.unwrap_or(if integral {
+        NumberFormat::Integer
+    } else {
+        NumberFormat::Float
+    });
+
+    Some(
+        numbers
+            .into_iter()
+            .map(|number| format_number(number, format, p.precision))
+            .collect(),
+    )
+}
+
+/// How many numbers a range has.
+fn range_length(range: &NumericRange) -> usize {
+    let steps = (range.stop - range.start) / range.step;
+
+    if !steps.is_finite() || steps <= 0.0 {
+        return 0;
+    }
+
+    // A stop that is a whole number of steps away is not included, even if
+    // the division is not exact.
+    (steps - 1e-9).ceil().max(0.0) as usize
+}
+
+/// `num` evenly spaced numbers from `start` to `stop`, both included.
+fn spaced(start: f64, stop: f64, num: usize) -> Vec<f64> {
+    if num == 1 {
+        return vec![start];
+    }
+
+    let step = (stop - start) / (num - 1) as f64;
+
+    (0..num)
+        .map(|i| {
+            if i == num - 1 {
+                stop
+            } else {
+                start + i as f64 * step
+            }
+        })
+        .collect()
+}
+
+/// Write a generated number in the format of its parameter.
+fn format_number(number: f64, format: NumberFormat, precision: Option<usize>) -> String {
+    match (format, precision) {
+        (NumberFormat::Integer, _) => format!("{}", number.round() as i64),
+        (NumberFormat::Float, Some(precision)) => format!("{number:.precision$}"),
+        (NumberFormat::Float, None) => {
+            // Drop the rounding errors of the arithmetic, like in
+            // 0.30000000000000004, by keeping a number of significant digits.
+            let digits = PARAMETER_SIGNIFICANT_DIGITS - 1;
+            let rounded: f64 = format!("{number:.digits$e}").parse().unwrap_or(number);
+
+            format!("{rounded}")
+        }
+    }
+}
+
 /// Takes the set of all inputs and all Parameters and expands parameterd
 /// arguments in the inputs with valeus of provided parameters.
 ///
@@ -56,79 +282,413 @@ pub fn validate_parameters(parameters: &BTreeMap<String, Parameter>) -> Result<(
 ///
 /// [inputs.test_input]
 /// arguments = [ "const", "subparam|x.a", "param|y",
-/// "parameter|{parameter_x_b}" ]
+/// "--b={param.x.b}" ]
 /// ```
 ///
 /// Will get expanded to:
 /// ```toml
 /// [inputs.test_input_x_0_y_0]
-/// arguments = [ "const", "1", "a", "15" ]
+/// arguments = [ "const", "1", "a", "--b=15" ]
 ///
 /// [inputs.test_input_x_1_y_0]
-/// arguments = [ "const", "2", "a", "60" ]
+/// arguments = [ "const", "2", "a", "--b=60" ]
 ///
 /// [inputs.test_input_x_0_y_1]
-/// arguments = [ "const", "1", "b", "15" ]
+/// arguments = [ "const", "1", "b", "--b=15" ]
 ///
 /// [inputs.test_input_x_1_y_1]
-/// arguments = [ "const", "2", "b", "60" ]
+/// arguments = [ "const", "2", "b", "--b=60" ]
 /// ```
 pub fn expand_parameters(
     inputs: BTreeMap<String, UserInput>,
     parameters: &BTreeMap<String, Parameter>,
 ) -> Result<BTreeMap<String, UserInput>> {
-    let mut result: BTreeMap<String, UserInput> = BTreeMap::new();
+    Ok(
+        expand_input_parameters(inputs, parameters, &Constraints::default(), &Points::new())?
+            .into_iter()
+            .map(|(name, (input, _))| (name, input))
+            .collect(),
+    )
+}
+
+/// Like [`expand_parameters`], but leaves out the combinations that the
+/// `constraints` exclude and returns the values of the parameters of every
+/// input.
+///
+/// The inputs that have `points` are only built at those points.
+pub fn expand_input_parameters(
+    inputs: BTreeMap<String, UserInput>,
+    parameters: &BTreeMap<String, Parameter>,
+    constraints: &Constraints,
+    points: &Points,
+) -> Result<BTreeMap<String, InputVariant>> {
+    let mut result = BTreeMap::new();
 
     check_sub_parameter_size_is_equal(parameters)?;
 
     for (input_name, input) in inputs.iter() {
+        // The values of environment variables are expanded like arguments
+        // that follow the actual ones.
+        let mut slots = input.arguments.clone();
+        slots.extend(input.env.values().cloned());
+
+        let owner = format!("input {input_name}");
+
+        // If none of parameters was used in this input then there's no need to do
+        // anything.
+        let Some(set) = expand_slots(
+            &owner,
+            input_name,
+            slots,
+            parameters,
+            constraints,
+            points.get(input_name),
+        )?
+        else {
+            result.insert(input_name.clone(), (input.clone(), BTreeMap::new()));
+            continue;
+        };
+
+        for (name, mut x, values) in set {
+            let mut input_copy = input.clone();
+            let env_values = x.split_off(input.arguments.len());
+            input_copy.arguments.clone_from(&x);
+            input_copy.env = input.env.keys().cloned().zip(env_values).collect();
+            result.insert(
+                format!("{name}{INTERNAL_PREFIX}{INTERNAL_PARAMETER}"),
+                (input_copy, values),
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+/// Expands the parameters used in the arguments and environment of programs,
+/// creating a variant of a program for every combination of their values.
+///
+/// The variants are named after the values, like `solver[x=3,y=a]`, and the
+/// `next` programs of every variant are all variants of those programs.
+/// Returns the variants together with the values of their parameters, leaving
+/// out the combinations that the `constraints` exclude. The programs that
+/// have `points` are only built at those points.
+pub fn expand_program_parameters(
+    programs: &BTreeMap<String, UserProgram>,
+    parameters: &BTreeMap<String, Parameter>,
+    constraints: &Constraints,
+    points: &Points,
+) -> Result<BTreeMap<String, ProgramVariant>> {
+    check_sub_parameter_size_is_equal(parameters)?;
+
+    let mut result = BTreeMap::new();
+    let mut variants: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+
+    for (program_name, program) in programs {
+        let mut slots = program.arguments.clone();
+        slots.extend(program.env.values().cloned());
+
+        let owner = format!("program {program_name}");
+
+        let Some(set) = expand_slots(
+            &owner,
+            program_name,
+            slots,
+            parameters,
+            constraints,
+            points.get(program_name),
+        )?
+        else {
+            variants.insert(program_name, vec![program_name.clone()]);
+            result.insert(program_name.clone(), (program.clone(), BTreeMap::new()));
+            continue;
+        };
+
+        // A program whose combinations are all excluded has no variants.
+        variants.entry(program_name).or_default();
+
+        for (_, mut x, values) in set {
+            let name = format!(
+                "{program_name}[{}]",
+                values
+                    .iter()
+                    .map(|(parameter, value)| format!("{parameter}={value}"))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            );
+
+            let mut program_copy = program.clone();
+            let env_values = x.split_off(program.arguments.len());
+            program_copy.arguments = x;
+            program_copy.env = program.env.keys().cloned().zip(env_values).collect();
+
+            variants.entry(program_name).or_default().push(name.clone());
+            result.insert(name, (program_copy, values));
+        }
+    }
+
+    for (program, _) in result.values_mut() {
+        program.next = program
+            .next
+            .iter()
+            // Unknown programs are kept to be reported later.
+            .flat_map(|next| {
+                variants
+                    .get(next.as_str())
+                    .cloned()
+                    .unwrap_or_else(|| vec![next.clone()])
+            })
+            .collect();
+    }
+
+    Ok(result)
+}
+
+/// Fill in the parameters that are used in `slots` with the values at every
+/// point, or at every combination of their values if there are no `points`.
+///
+/// Every combination has a name, `base` followed by the index of the value of
+/// every parameter like `base_x_0_y_1`, the filled in slots and the values of
+/// the parameters. The combinations that the `constraints` exclude are left
+/// out. Returns [`None`] if no parameter is used.
+fn expand_slots(
+    owner: &str,
+    base: &str,
+    slots: Vec<String>,
+    parameters: &BTreeMap<String, Parameter>,
+    constraints: &Constraints,
+    points: Option<&BTreeSet<Vec<usize>>>,
+) -> Result<Option<BTreeSet<Combination>>> {
+    let Some(space) = ParameterSpace::new(owner, &slots, parameters)? else {
+        return Ok(None);
+    };
+
+    let mut set: BTreeSet<Combination> = BTreeSet::new();
+
+    match points {
+        Some(points) => {
+            for point in points {
+                set.insert(space.combination(base, &slots, point)?);
+            }
+        }
+        None => {
+            for point in grid(&space.counts()) {
+                set.insert(space.combination(base, &slots, &point)?);
+            }
+        }
+    }
+
+    let before = set.len();
+    set.retain(|(_, _, values)| !constraints.excludes(values));
+
+    if set.len() < before {
+        debug!(
+            "The constraints excluded {} of the {before} combinations of {owner}",
+            before - set.len()
+        );
+    }
+
+    Ok(Some(set))
+}
+
+/// The parameters that are used in the arguments and environment of a program
+/// or an input, and the values that they can take.
+///
+/// A point of the space is the index of a value of every parameter, in the
+/// order of [`ParameterSpace::counts`].
+#[derive(Debug)]
+pub struct ParameterSpace<'a> {
+    /// Every used parameter, its values and where it is used.
+    used: Vec<(String, Choices<'a>, Slots)>,
+}
+
+impl<'a> ParameterSpace<'a> {
+    /// The space of the parameters that are used in `slots`, or [`None`] if
+    /// no parameter is used.
+    pub fn new(
+        owner: &str,
+        slots: &[String],
+        parameters: &'a BTreeMap<String, Parameter>,
+    ) -> Result<Option<Self>> {
         let mut map = BTreeMap::new();
         let mut expandable_parameters = BTreeSet::new();
 
-        // Find uses of parameters in inputs.
-        get_expandable_parameters(input, &mut map, &mut expandable_parameters)?;
+        // Find uses of parameters in the slots.
+        get_expandable_parameters(slots, &mut map, &mut expandable_parameters)?;
 
-        trace!("Expandable parameters for {input_name} are {expandable_parameters:#?}");
+        trace!("Expandable parameters for {owner} are {expandable_parameters:#?}");
 
-        // If none of parameters was used in this input then there's no need to do
-        // anything.
         if expandable_parameters.is_empty() {
-            result.insert(input_name.clone(), input.clone());
-            continue;
+            return Ok(None);
         }
 
-        let mut set: BTreeSet<(String, Vec<String>)> = BTreeSet::new();
-        set.insert((input_name.clone(), input.arguments.clone()));
+        let mut used = Vec::new();
 
         for parameter_name in expandable_parameters {
             if let Some(param) = parameters.get(&parameter_name) {
-                let indexes = &map[&parameter_name];
+                let indexes = map.remove(&parameter_name).unwrap_or_default();
 
-                if indexes[0].1.is_some() {
-                    set = expand_sub_parameter(&parameter_name, param, set, indexes)?;
-                } else {
-                    set = expand_parameter(&parameter_name, param, set, indexes)?;
-                }
+                let choices = choices(&parameter_name, param, &indexes)?;
+
+                used.push((parameter_name, choices, indexes));
             } else {
                 bailc!(
                     "Invalid parameter specified", ;
-                    "Did not find values for parameter specified in input {}", input_name;
+                    "Did not find values for parameter specified in {}", owner;
                     "For parameter \"{parameter_name}\"",
                 );
             }
         }
 
-        for (name, x) in set {
-            let mut input_copy = input.clone();
-            input_copy.arguments.clone_from(&x);
-            result.insert(
-                format!("{name}{INTERNAL_PREFIX}{INTERNAL_PARAMETER}"),
-                input_copy,
-            );
+        Ok(Some(ParameterSpace { used }))
+    }
+
+    /// The space of the parameters in the arguments and environment of a
+    /// program.
+    pub fn of_program(
+        name: &str,
+        program: &UserProgram,
+        parameters: &'a BTreeMap<String, Parameter>,
+    ) -> Result<Option<Self>> {
+        let mut slots = program.arguments.clone();
+        slots.extend(program.env.values().cloned());
+
+        Self::new(&format!("program {name}"), &slots, parameters)
+    }
+
+    /// The space of the parameters in the arguments and environment of an
+    /// input.
+    pub fn of_input(
+        name: &str,
+        input: &UserInput,
+        parameters: &'a BTreeMap<String, Parameter>,
+    ) -> Result<Option<Self>> {
+        let mut slots = input.arguments.clone();
+        slots.extend(input.env.values().cloned());
+
+        Self::new(&format!("input {name}"), &slots, parameters)
+    }
+
+    /// How many values every parameter has.
+    pub fn counts(&self) -> Vec<usize> {
+        self.used
+            .iter()
+            .map(|(_, choices, _)| choices.len())
+            .collect()
+    }
+
+    /// The indices of the values of the `n`th parameter from the smallest to
+    /// the largest if they are numbers, and in their order otherwise.
+    pub fn order(&self, n: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.used[n].1.len()).collect();
+
+        if let Choices::Values(values) = &self.used[n].1 {
+            let numbers: Option<Vec<f64>> = values.iter().map(|v| v.parse().ok()).collect();
+
+            if let Some(numbers) = numbers {
+                order.sort_by(|a, b| numbers[*a].total_cmp(&numbers[*b]));
+            }
         }
+
+        order
     }
 
-    Ok(result)
+    /// The values of the parameters at `point`, as a variant records them.
+    pub fn values(&self, point: &[usize]) -> BTreeMap<String, String> {
+        let mut values = BTreeMap::new();
+
+        for ((parameter_name, choices, indexes), i) in self.used.iter().zip(point) {
+            match choices {
+                Choices::Values(choices) => {
+                    values.insert(parameter_name.clone(), choices[*i].clone());
+                }
+                Choices::Sub(subparams) => {
+                    for sub in indexes.iter().filter_map(|(_, sub)| sub.as_ref()) {
+                        if let Some(subparam) = subparams.get(sub) {
+                            values.insert(
+                                format!("{parameter_name}.{sub}"),
+                                subparam.values[*i].clone(),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        values
+    }
+
+    /// Fill in the values at `point`.
+    fn combination(&self, base: &str, slots: &[String], point: &[usize]) -> Result<Combination> {
+        let mut combination = (base.to_string(), slots.to_vec(), BTreeMap::new());
+
+        for ((parameter_name, choices, indexes), i) in self.used.iter().zip(point) {
+            match choices {
+                Choices::Values(values) => {
+                    fill_parameter(parameter_name, (*i, &values[*i]), &mut combination, indexes)?
+                }
+                Choices::Sub(subparams) => {
+                    fill_sub_parameter(parameter_name, subparams, &mut combination, indexes, *i)?
+                }
+            }
+        }
+
+        Ok(combination)
+    }
+}
+
+/// The values that a parameter can take where it is used.
+#[derive(Debug)]
+enum Choices<'a> {
+    /// The values of a parameter.
+    Values(Vec<String>),
+
+    /// The subparameters of a parameter, which all have the same number of
+    /// values.
+    Sub(&'a BTreeMap<String, SubParameter>),
+}
+
+impl Choices<'_> {
+    /// How many values there are to choose from.
+    fn len(&self) -> usize {
+        match self {
+            Choices::Values(values) => values.len(),
+            Choices::Sub(subparams) => subparams.values().next().map_or(0, |s| s.values.len()),
+        }
+    }
+}
+
+/// The values of a parameter, or its subparameters if it is used as
+/// `subparam|`.
+fn choices<'a>(
+    parameter_name: &String,
+    param: &'a Parameter,
+    indexes: &[(usize, Option<String>)],
+) -> Result<Choices<'a>> {
+    if indexes[0].1.is_some() {
+        let subparams = param
+            .sub
+            .as_ref()
+            .ok_or(anyhow!("Parameter {parameter_name} used"))
+            .with_context(ctx!(
+                "", ;
+                "You cannot use a parameter in a '{SUB_PARAMETER_ESCAPE}' while no subparameters are specified",
+            ))?;
+
+        if subparams.is_empty() {
+            return Err(anyhow!("Subparameters required for {parameter_name}")).context("");
+        }
+
+        Ok(Choices::Sub(subparams))
+    } else {
+        Ok(Choices::Values(
+            parameter_values(param)
+                .ok_or(anyhow!("Parameter \"{parameter_name}\" used"))
+                .with_context(ctx!(
+                    "", ;
+                    "You cannot use a parameter in a '{PARAMETER_ESCAPE}' while no values are specified",
+                ))?,
+        ))
+    }
 }
 
 /// Checks if all sub parameters of each parameter specified in `parameters`
@@ -159,15 +719,15 @@ fn check_sub_parameter_size_is_equal(parameters: &BTreeMap<String, Parameter>) -
     Ok(())
 }
 
-/// Gets names and positions of parameters in the provided `input`. (Helper
-/// function)
+/// Gets names and positions of parameters in the provided `arguments`.
+/// (Helper function)
 ///
 /// Saves parameter names in `expandable_parameters` Set and
 /// `parameter_names_encountered` Set
 ///
 /// Saves map of Index to Parameter name in `map`
 fn get_expandable_parameters(
-    input: &UserInput,
+    arguments: &[String],
     map: &mut BTreeMap<String, Vec<(usize, Option<String>)>>,
     expandable_parameters: &mut BTreeSet<String>,
 ) -> Result<()> {
@@ -178,7 +738,7 @@ fn get_expandable_parameters(
             .with_context(ctx!("", ; "", ))
     }
 
-    for (pos, arg) in input.arguments.iter().enumerate() {
+    for (pos, arg) in arguments.iter().enumerate() {
         if let Some(param_name) = arg.strip_prefix(PARAMETER_ESCAPE).to_owned() {
             expandable_parameters.insert(param_name.to_string());
 
@@ -205,111 +765,129 @@ fn get_expandable_parameters(
             } else {
                 map.insert(param_name, vec![(pos, Some(subparam_name))]);
             }
+        } else {
+            // Parameters can also be used inside of an argument, as `{param.x}`
+            // or `{param.x.subparam}`.
+            for name in placeholders(arg) {
+                let Some(used) = name.strip_prefix(PARAMETER_PLACEHOLDER_PREFIX) else {
+                    continue;
+                };
+
+                let (param_name, subparam_name) = match used.split_once('.') {
+                    None => (used.to_string(), None),
+                    Some((param, sub)) if !sub.contains('.') => {
+                        (param.to_string(), Some(sub.to_string()))
+                    }
+                    Some(_) => {
+                        bailc!(
+                            "Invalid parameter syntax", ;
+                            "The placeholder {{{name}}} has too many parts", ;
+                            "Use {{{PARAMETER_PLACEHOLDER_PREFIX}x}} or \
+                            {{{PARAMETER_PLACEHOLDER_PREFIX}x.subparam}}",
+                        );
+                    }
+                };
+
+                expandable_parameters.insert(param_name.clone());
+                map.entry(param_name)
+                    .or_default()
+                    .push((pos, subparam_name));
+            }
         }
     }
 
     Ok(())
 }
 
-/// Expands provided parameter (Helper function).
-fn expand_parameter(
-    parameter_name: &String,
-    param: &Parameter,
-    set: BTreeSet<(String, Vec<String>)>,
-    indexes: &Vec<(usize, Option<String>)>,
-) -> Result<BTreeSet<(String, Vec<String>)>> {
-    let param_values = &param
-        .values
-        .clone()
-        .ok_or(anyhow!("Parameter \"{parameter_name}\" used"))
-        .with_context(ctx!(
-            "", ;
-            "You cannot use a parameter in a '{PARAMETER_ESCAPE}' while no values are specified",
-        ))?;
-
-    let mut new_set = BTreeSet::new();
-
-    for (base_name, arguments) in set {
-        // For each value...
-        for (i, value) in param_values.iter().enumerate() {
-            let mut arguments_clone = arguments.clone();
-
-            // Everywhere where this parameter appears we replace it with
-            // its value.
-            for index in indexes {
-                if index.1.is_some() {
-                    bailc!(
-                        "Ivariant failed", ;
-                        "You cannot use subparameters for this parameter anymore", ;
-                        "For parameter \"{parameter_name}\"",
-                    );
-                }
-                arguments_clone[index.0] = value.to_string();
-            }
+/// Put the value of a parameter into an argument, replacing the whole
+/// argument if it is a `param|` or `subparam|` and the placeholders otherwise.
+fn set_parameter(
+    argument: &mut String,
+    parameter_name: &str,
+    subparameter_name: Option<&str>,
+    value: &str,
+) -> Result<()> {
+    if argument.starts_with(PARAMETER_ESCAPE) || argument.starts_with(SUB_PARAMETER_ESCAPE) {
+        *argument = value.to_string();
+    } else {
+        let placeholder = match subparameter_name {
+            Some(sub) => format!("{PARAMETER_PLACEHOLDER_PREFIX}{parameter_name}.{sub}"),
+            None => format!("{PARAMETER_PLACEHOLDER_PREFIX}{parameter_name}"),
+        };
+
+        *argument = fill_some(argument, |name| {
+            Ok((name == placeholder).then(|| value.to_string()))
+        })?;
+    }
+
+    Ok(())
+}
 
-            new_set.insert((format!("{base_name}_{parameter_name}_{i}"), arguments_clone));
+/// Fill in the `i`th value of a parameter (Helper function).
+fn fill_parameter(
+    parameter_name: &String,
+    (i, value): (usize, &String),
+    (base_name, arguments, values): &mut Combination,
+    indexes: &[(usize, Option<String>)],
+) -> Result<()> {
+    // Everywhere where this parameter appears we replace it with its value.
+    for index in indexes {
+        if index.1.is_some() {
+            bailc!(
+                "Ivariant failed", ;
+                "You cannot use subparameters for this parameter anymore", ;
+                "For parameter \"{parameter_name}\"",
+            );
         }
+        set_parameter(&mut arguments[index.0], parameter_name, None, value)?;
     }
 
-    Ok(new_set)
+    values.insert(parameter_name.clone(), value.clone());
+    *base_name = format!("{base_name}_{parameter_name}_{i}");
+
+    Ok(())
 }
 
-/// Expands provided sub parameter (Helper function).
-fn expand_sub_parameter(
+/// Fill in the `i`th value of every subparameter of a parameter (Helper
+/// function).
+fn fill_sub_parameter(
     param_name: &String,
-    param: &Parameter,
-    set: BTreeSet<(String, Vec<String>)>,
-    indexes: &Vec<(usize, Option<String>)>,
-) -> Result<BTreeSet<(String, Vec<String>)>> {
-    let subparams = &param
-        .sub
-        .clone()
-        .ok_or(anyhow!("Parameter {param_name} used"))
-        .with_context(ctx!(
-            "", ;
-            "You cannot use a parameter in a '{SUB_PARAMETER_ESCAPE}' while no subparameters are specified",
-        ))?;
-
-    let size_of_one = subparams
-        .values()
-        .next()
-        .ok_or(anyhow!("Subparameters required for {param_name}"))
-        .context("")?
-        .values
-        .len();
-
-    let mut new_set = BTreeSet::new();
-
-    for (base_name, arguments) in set {
-        for i in 0..size_of_one {
-            let mut arguments_clone = arguments.clone();
-            for sub_index in indexes {
-                let expanding = &sub_index
-                    .1
-                    .clone()
-                    .ok_or(anyhow!("Invariant failed"))
-                    .with_context(ctx!(
-                        "Cannot use a parameter with subparameters as value-based", ;
-                        "'{INTERNAL_PARAMETER}' reqiures a parameter that has the values \
-                        field defined and \"{param_name}\" does not",
-                    ))?;
-
-                arguments_clone[sub_index.0].clone_from(
-                    &subparams
-                        .get(expanding)
-                        .ok_or(anyhow!("Invalid subparameter specified {expanding}"))
-                        .with_context(ctx!(
-                        "For parameter {param_name}", ;
-                        "Ensure that it exists", ))?
-                        .values[i],
-                );
-            }
-
-            new_set.insert((format!("{base_name}_{param_name}_{i}"), arguments_clone));
-        }
+    subparams: &BTreeMap<String, SubParameter>,
+    (base_name, arguments, values): &mut Combination,
+    indexes: &[(usize, Option<String>)],
+    i: usize,
+) -> Result<()> {
+    for sub_index in indexes {
+        let expanding = &sub_index
+            .1
+            .clone()
+            .ok_or(anyhow!("Invariant failed"))
+            .with_context(ctx!(
+                "Cannot use a parameter with subparameters as value-based", ;
+                "'{INTERNAL_PARAMETER}' reqiures a parameter that has the values \
+                field defined and \"{param_name}\" does not",
+            ))?;
+
+        let value = &subparams
+            .get(expanding)
+            .ok_or(anyhow!("Invalid subparameter specified {expanding}"))
+            .with_context(ctx!(
+            "For parameter {param_name}", ;
+            "Ensure that it exists", ))?
+            .values[i];
+
+        set_parameter(
+            &mut arguments[sub_index.0],
+            param_name,
+            Some(expanding),
+            value,
+        )?;
+        values.insert(format!("{param_name}.{expanding}"), value.clone());
     }
 
-    Ok(new_set)
+    *base_name = format!("{base_name}_{param_name}_{i}");
+
+    Ok(())
 }
 
 #[cfg(test)]
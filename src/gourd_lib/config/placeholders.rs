@@ -0,0 +1,153 @@
+use anyhow::Context;
+use anyhow::Result;
+
+use crate::bailc;
+
+/// The path to the input file of the run.
+pub const INPUT_PLACEHOLDER: &str = "input";
+
+/// The name of the input of the run.
+pub const INPUT_NAME_PLACEHOLDER: &str = "input_name";
+
+/// The id of the run.
+pub const RUN_ID_PLACEHOLDER: &str = "run_id";
+
+/// The working directory of the run.
+pub const WORK_DIR_PLACEHOLDER: &str = "work_dir";
+
+/// The seed of the run.
+pub const SEED_PLACEHOLDER: &str = "seed";
+
+/// The prefix of placeholders for parameter values, as in `{param.x}`.
+pub const PARAMETER_PLACEHOLDER_PREFIX: &str = "param.";
+
+/// A part of an argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Piece<'a> {
+    /// Text that is copied as is.
+    Text(&'a str),
+
+    /// An escaped brace, `{{` or `}}`.
+    Brace(char),
+
+    /// The name inside of a `{name}`.
+    Placeholder(&'a str),
+}
+
+/// Whether this can be the name of a placeholder.
+///
+/// Anything else in braces is left alone, so that arguments like `{"a": 1}`
+/// keep working.
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Split an argument into text, escaped braces and placeholders.
+fn pieces(arg: &str) -> Vec<Piece<'_>> {
+    let mut pieces = vec![];
+    let mut rest = arg;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("{{") {
+            pieces.push(Piece::Brace('{'));
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("}}") {
+            pieces.push(Piece::Brace('}'));
+            rest = after;
+        } else if let Some((name, after)) = rest
+            .strip_prefix('{')
+            .and_then(|inner| inner.split_once('}'))
+            .filter(|(name, _)| is_name(name))
+        {
+            pieces.push(Piece::Placeholder(name));
+            rest = after;
+        } else {
+            // Copy everything up to the next brace, but at least one character.
+            let first = rest.chars().next().map_or(0, char::len_utf8);
+            let end = rest[first..]
+                .find(['{', '}'])
+                .map_or(rest.len(), |i| i + first);
+
+            pieces.push(Piece::Text(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+
+    pieces
+}
+
+/// The names of the placeholders used in an argument.
+pub fn placeholders(arg: &str) -> Vec<&str> {
+    pieces(arg)
+        .into_iter()
+        .filter_map(|piece| match piece {
+            Piece::Placeholder(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Escape the braces in a value, so that it is not read as a placeholder.
+pub fn escape(value: &str) -> String {
+    value.replace('{', "{{").replace('}', "}}")
+}
+
+/// Fill in the placeholders that `lookup` knows the value of, and keep the
+/// other placeholders and the escaped braces for a later [`fill`].
+pub fn fill_some(
+    arg: &str,
+    mut lookup: impl FnMut(&str) -> Result<Option<String>>,
+) -> Result<String> {
+    let mut out = String::new();
+
+    for piece in pieces(arg) {
+        match piece {
+            Piece::Text(text) => out.push_str(text),
+            Piece::Brace(brace) => {
+                out.push(brace);
+                out.push(brace);
+            }
+            Piece::Placeholder(name) => match lookup(name)? {
+                Some(value) => out.push_str(&escape(&value)),
+                None => out.push_str(&format!("{{{name}}}")),
+            },
+        }
+    }
+
+    Ok(out)
+}
+
+/// Fill in all placeholders of an argument and unescape its braces.
+///
+/// Fails if `lookup` does not know one of the placeholders.
+pub fn fill(arg: &str, mut lookup: impl FnMut(&str) -> Result<Option<String>>) -> Result<String> {
+    let mut out = String::new();
+
+    for piece in pieces(arg) {
+        match piece {
+            Piece::Text(text) => out.push_str(text),
+            Piece::Brace(brace) => out.push(brace),
+            Piece::Placeholder(name) => match lookup(name)? {
+                Some(value) => out.push_str(&value),
+                None => {
+                    bailc!(
+                        "Unknown placeholder {{{name}}} in the argument {arg:?}", ;
+                        "The placeholders are {{{INPUT_PLACEHOLDER}}}, {{{INPUT_NAME_PLACEHOLDER}}}, \
+                        {{{RUN_ID_PLACEHOLDER}}}, {{{WORK_DIR_PLACEHOLDER}}}, \
+                        {{{SEED_PLACEHOLDER}}} and {{{PARAMETER_PLACEHOLDER_PREFIX}name}}", ;
+                        "Write {{{{ and }}}} for literal braces",
+                    );
+                }
+            },
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+#[path = "tests/placeholders.rs"]
+mod tests;
@@ -3,6 +3,11 @@ use std::hash::Hash;
 use std::ops::Deref;
 use std::ops::DerefMut;
 
+use schemars::gen::SchemaGenerator;
+use schemars::schema::InstanceType;
+use schemars::schema::Schema;
+use schemars::schema::SchemaObject;
+use schemars::JsonSchema;
 use serde::de::Visitor;
 use serde::Deserialize;
 use serde::Deserializer;
@@ -70,6 +75,21 @@ impl<'de> Deserialize<'de> for Regex {
     }
 }
 
+impl JsonSchema for Regex {
+    fn schema_name() -> String {
+        "Regex".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("regex".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 impl Deref for Regex {
     type Target = regex_lite::Regex;
 
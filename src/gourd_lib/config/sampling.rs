@@ -0,0 +1,260 @@
+use std::collections::BTreeSet;
+
+use anyhow::Context;
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::bailc;
+use crate::constants::SAMPLE_ATTEMPTS;
+
+/// Run a sample of the combinations of parameter values instead of all of
+/// them.
+///
+/// # Examples
+///
+/// ```toml
+/// [sampling]
+/// method = "latin_hypercube"
+/// samples = 20
+/// seed = 42
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Sampling {
+    /// How the combinations are chosen.
+    pub method: SamplingMethod,
+
+    /// How many combinations of a program and an input run, in total.
+    pub samples: usize,
+
+    /// The seed of the random choices, the same seed gives the same sample.
+    #[serde(default)]
+    pub seed: u64,
+}
+
+/// The ways to choose a sample of the combinations of parameter values.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplingMethod {
+    /// Combinations chosen uniformly at random, without repeating one.
+    Random,
+
+    /// A Latin hypercube, which spreads the samples evenly over the values of
+    /// every parameter.
+    LatinHypercube,
+}
+
+impl Sampling {
+    /// Check that the sample is not empty.
+    pub fn validate(&self) -> Result<()> {
+        if self.samples == 0 {
+            bailc!(
+                "The sampling has no samples", ;
+                "The experiment runs `samples` combinations of programs and inputs with parameters", ;
+                "Set `samples` in [sampling] to 1 or more",
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Choose a sample of the points of some `blocks`. Every block is a part of
+    /// the space, the values of the parameters of a program and an input that
+    /// run together, given by how many values every parameter has. The
+    /// values of a parameter are in order, so a point is a block and the index
+    /// of a value of every parameter.
+    ///
+    /// Only the points that are `allowed` are chosen, and none of them twice.
+    /// If there are no more points than samples, all of them are chosen.
+    /// Otherwise the indices are drawn from the numbers of values alone, so
+    /// that a sample of a large space takes as long as one of a small space.
+    /// If the allowed points are rare, the sample can be smaller.
+    pub fn choose(
+        &self,
+        blocks: &[Vec<usize>],
+        mut allowed: impl FnMut(usize, &[usize]) -> bool,
+    ) -> Result<BTreeSet<(usize, Vec<usize>)>> {
+        let sizes: Option<Vec<usize>> = blocks
+            .iter()
+            .map(|counts| {
+                counts
+                    .iter()
+                    .try_fold(1usize, |size, c| size.checked_mul(*c))
+            })
+            .collect();
+
+        let Some((sizes, total)) = sizes.and_then(|sizes| {
+            let total = sizes
+                .iter()
+                .try_fold(0usize, |total, s| total.checked_add(*s))?;
+            Some((sizes, total))
+        }) else {
+            bailc!(
+                "The parameters have too many combinations to sample", ;
+                "There are more than {} combinations of programs and inputs", usize::MAX;
+                "Use fewer parameters or fewer values",
+            );
+        };
+
+        let mut chosen = BTreeSet::new();
+
+        if total <= self.samples {
+            for (block, counts) in blocks.iter().enumerate() {
+                for point in grid(counts) {
+                    if allowed(block, &point) {
+                        chosen.insert((block, point));
+                    }
+                }
+            }
+
+            return Ok(chosen);
+        }
+
+        let mut random = SplitMix::new(self.seed);
+
+        // A point from a number below the total, the last index changing
+        // fastest.
+        let point = |mut number: usize| {
+            let mut block = 0;
+            while number >= sizes[block] {
+                number -= sizes[block];
+                block += 1;
+            }
+
+            let mut indices = vec![0; blocks[block].len()];
+            for (index, count) in indices.iter_mut().zip(&blocks[block]).rev() {
+                *index = number % count;
+                number /= count;
+            }
+
+            (block, indices)
+        };
+
+        // The samples take the strata of the choice of the block and of every
+        // parameter in a random order.
+        let strata: Vec<Vec<usize>> = match self.method {
+            SamplingMethod::Random => Vec::new(),
+            SamplingMethod::LatinHypercube => {
+                let width = blocks.iter().map(Vec::len).max().unwrap_or_default();
+
+                (0..=width)
+                    .map(|_| random.permutation(self.samples))
+                    .collect()
+            }
+        };
+
+        for sample in 0..self.samples {
+            for attempt in 0..SAMPLE_ATTEMPTS {
+                let (block, indices) = if strata.is_empty() || attempt >= SAMPLE_ATTEMPTS / 2 {
+                    point(random.below(total))
+                } else {
+                    // A random place in the stratum of every dimension.
+                    let mut place = |stratum: usize, count: usize| {
+                        let position = (stratum as f64 + random.unit()) / self.samples as f64;
+                        ((position * count as f64) as usize).min(count - 1)
+                    };
+
+                    let (block, _) = point(place(strata[0][sample], total));
+                    let indices = blocks[block]
+                        .iter()
+                        .enumerate()
+                        .map(|(parameter, count)| place(strata[parameter + 1][sample], *count))
+                        .collect();
+
+                    (block, indices)
+                };
+
+                if !chosen.contains(&(block, indices.clone())) && allowed(block, &indices) {
+                    chosen.insert((block, indices));
+                    break;
+                }
+            }
+        }
+
+        // In a space that is not much larger than the sample the draws often
+        // repeat, so the rest of the sample is taken from all points.
+        if chosen.len() < self.samples && total / SAMPLE_ATTEMPTS <= self.samples {
+            let mut rest: Vec<(usize, Vec<usize>)> = (0..total)
+                .map(point)
+                .filter(|(block, indices)| {
+                    !chosen.contains(&(*block, indices.clone())) && allowed(*block, indices)
+                })
+                .collect();
+
+            for i in random.permutation(rest.len()) {
+                if chosen.len() == self.samples {
+                    break;
+                }
+
+                chosen.insert(std::mem::take(&mut rest[i]));
+            }
+        }
+
+        Ok(chosen)
+    }
+}
+
+/// Every combination of indices below `counts`, the last one changing fastest.
+pub fn grid(counts: &[usize]) -> Vec<Vec<usize>> {
+    counts.iter().fold(vec![vec![]], |points, count| {
+        points
+            .into_iter()
+            .flat_map(|point| {
+                (0..*count).map(move |i| {
+                    let mut point = point.clone();
+                    point.push(i);
+                    point
+                })
+            })
+            .collect()
+    })
+}
+
+/// A small random number generator, so that a seed gives the same sample in
+/// every version of gourd.
+struct SplitMix(u64);
+
+impl SplitMix {
+    /// A generator that starts at `seed`.
+    fn new(seed: u64) -> Self {
+        SplitMix(seed)
+    }
+
+    /// The next random number.
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+        z ^ (z >> 31)
+    }
+
+    /// A random number from `0` up to `n`.
+    fn below(&mut self, n: usize) -> usize {
+        ((self.next() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// A random number from `0.0` up to `1.0`.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// The numbers from `0` up to `n` in a random order.
+    fn permutation(&mut self, n: usize) -> Vec<usize> {
+        let mut numbers: Vec<usize> = (0..n).collect();
+
+        for i in (1..n).rev() {
+            numbers.swap(i, self.below(i + 1));
+        }
+
+        numbers
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/sampling.rs"]
+mod tests;
@@ -0,0 +1,168 @@
+use anyhow::Context;
+use anyhow::Result;
+use glob::Pattern;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::bailc;
+use crate::constants::INTERNAL_PREFIX;
+use crate::constants::INTERNAL_SCHEMA_INPUTS;
+use crate::experiment::InternalInput;
+use crate::experiment::InternalProgram;
+
+/// The inputs that a program runs on, chosen by name and by group.
+///
+/// Names are patterns like `graph_*`, which match the name of an input and
+/// the name of the input in the configuration that it was made from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, Default, JsonSchema)]
+pub struct InputSelector {
+    /// Only run on the inputs that match one of these names.
+    #[serde(default)]
+    pub inputs: Option<Vec<String>>,
+
+    /// Never run on the inputs that match one of these names.
+    #[serde(default)]
+    pub exclude_inputs: Vec<String>,
+
+    /// Only run on the inputs of these groups.
+    #[serde(default)]
+    pub groups: Option<Vec<String>>,
+
+    /// Never run on the inputs of these groups.
+    #[serde(default)]
+    pub exclude_groups: Vec<String>,
+}
+
+impl InputSelector {
+    /// Whether every input is selected.
+    pub fn is_empty(&self) -> bool {
+        *self == InputSelector::default()
+    }
+
+    /// Whether the input called `name` is selected.
+    pub fn selects(&self, name: &str, input: &InternalInput) -> bool {
+        let group = input.metadata.group.as_deref();
+
+        self.inputs
+            .as_ref()
+            .is_none_or(|inputs| matches_name(inputs, name, input))
+            && !matches_name(&self.exclude_inputs, name, input)
+            && self
+                .groups
+                .as_ref()
+                .is_none_or(|groups| group.is_some_and(|g| groups.iter().any(|x| x == g)))
+            && !group.is_some_and(|g| self.exclude_groups.iter().any(|x| x == g))
+    }
+
+    /// Check that the names are valid patterns.
+    pub fn validate(&self, program: &str) -> Result<()> {
+        let names = self.inputs.iter().flatten().chain(&self.exclude_inputs);
+
+        validate_patterns(names, &format!("program {program}"))
+    }
+}
+
+/// A program and the inputs that it runs on.
+///
+/// # Examples
+///
+/// ```toml
+/// [[pairing]]
+/// program = "exact"
+/// inputs = ["small_*"]
+/// groups = ["easy"]
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Pairing {
+    /// The name of the program, which pairs all of its variants if it has
+    /// parameters.
+    pub program: String,
+
+    /// The names of the inputs, which are patterns like in the `inputs` of a
+    /// program.
+    #[serde(default)]
+    pub inputs: Vec<String>,
+
+    /// The groups of the inputs.
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+impl Pairing {
+    /// Whether the program is paired with the input called `name`.
+    pub fn pairs(&self, program: &InternalProgram, name: &str, input: &InternalInput) -> bool {
+        let group = input.metadata.group.as_deref();
+
+        self.names(program)
+            && (matches_name(&self.inputs, name, input)
+                || group.is_some_and(|g| self.groups.iter().any(|x| x == g)))
+    }
+
+    /// Whether this pairing is about the program, or one of its variants.
+    pub fn names(&self, program: &InternalProgram) -> bool {
+        program.name == self.program
+            || (!program.parameters.is_empty()
+                && program
+                    .name
+                    .strip_prefix(self.program.as_str())
+                    .is_some_and(|rest| rest.starts_with('[')))
+    }
+
+    /// Check that the names of the inputs are valid patterns.
+    pub fn validate(&self) -> Result<()> {
+        if self.inputs.is_empty() && self.groups.is_empty() {
+            bailc!(
+                "The pairing of program {} has no inputs", self.program;
+                "A pairing lists the inputs and groups of inputs that a program runs on", ;
+                "Add `inputs` or `groups` to the pairing",
+            );
+        }
+
+        validate_patterns(
+            self.inputs.iter(),
+            &format!("the pairing of program {}", self.program),
+        )
+    }
+}
+
+/// Whether the input called `name` matches one of the patterns, either by
+/// its name or by the name it has in the configuration.
+///
+/// Inputs of a schema match by the name they have in the schema, without the
+/// internal suffix that tells them apart from the inputs of the configuration.
+fn matches_name(patterns: &[String], name: &str, input: &InternalInput) -> bool {
+    let schema_suffix = format!("{INTERNAL_PREFIX}{INTERNAL_SCHEMA_INPUTS}");
+    let names = [Some(name), input.metadata.origin.as_deref()];
+
+    patterns.iter().any(|pattern| {
+        Pattern::new(pattern).is_ok_and(|pattern| {
+            names.into_iter().flatten().any(|name| {
+                pattern.matches(name)
+                    || name
+                        .strip_suffix(&schema_suffix)
+                        .is_some_and(|name| pattern.matches(name))
+            })
+        })
+    })
+}
+
+/// Check that the names of inputs are valid patterns.
+fn validate_patterns<'a>(names: impl Iterator<Item = &'a String>, owner: &str) -> Result<()> {
+    for name in names {
+        if let Err(error) = Pattern::new(name) {
+            bailc!(
+                "The input name {name:?} of {owner} is not a valid pattern", ;
+                "{}", error.msg;
+                "Names can use `*`, `?` and `[...]` like globs",
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "tests/selection.rs"]
+mod tests;
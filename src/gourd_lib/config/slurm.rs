@@ -3,11 +3,12 @@ use std::fmt::Display;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
 /// The config options when running through Slurm
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SlurmConfig {
     /// The name of the experiment. This is used (parametrically) as the job
@@ -56,7 +57,7 @@ pub struct SlurmConfig {
 }
 
 /// The structure for providing custom slurm arguments
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SBatchArg {
     /// Name of the sbatch argument
@@ -68,7 +69,9 @@ pub struct SBatchArg {
 
 /// The resource limits, a Slurm configuration parameter that can be changed
 /// during an experiment. Contains the CPU, time, and memory bounds per run.
-#[derive(Debug, Clone, Copy, PartialEq, Hash, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Hash, Eq, Serialize, Deserialize, PartialOrd, Ord, JsonSchema,
+)]
 #[serde(deny_unknown_fields)]
 pub struct ResourceLimits {
     /// Maximum time allowed _for each_ job.
@@ -76,6 +79,7 @@ pub struct ResourceLimits {
         deserialize_with = "super::duration::deserialize_human_time_duration",
         serialize_with = "super::duration::serialize_duration"
     )]
+    #[schemars(with = "String")]
     pub time_limit: Duration,
 
     /// CPUs to use per job
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::bailc;
+use crate::constants::WRAPPER_KILL_GRACE_PERIOD;
+
+/// The signals that a program can ask for before its time limit, without the
+/// `SIG` prefix.
+///
+/// `KILL` and `STOP` cannot be handled by a program, so they are not allowed.
+pub const TERMINATION_SIGNALS: [&str; 9] = [
+    "HUP", "INT", "QUIT", "ABRT", "USR1", "USR2", "ALRM", "TERM", "XCPU",
+];
+
+/// The signal that is sent if a program only sets a `grace_period`.
+pub const DEFAULT_TERMINATION_SIGNAL: &str = "TERM";
+
+/// How a program is asked to stop before it reaches its time limit.
+///
+/// The signal is sent `grace_period` before the time limit, and the program is
+/// killed once the grace period has passed after the signal.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GracefulTermination {
+    /// The name of the signal without the `SIG` prefix, for example `TERM`.
+    pub signal: String,
+
+    /// How long the program has between the signal and the time limit.
+    #[serde(
+        deserialize_with = "super::duration::deserialize_human_time_duration",
+        serialize_with = "super::duration::serialize_duration"
+    )]
+    #[schemars(with = "String")]
+    pub grace_period: Duration,
+}
+
+impl GracefulTermination {
+    /// The graceful termination of a program with these `termination_signal`
+    /// and `grace_period` options, if either of them is set.
+    pub fn new(signal: Option<&str>, grace_period: Option<Duration>) -> Result<Option<Self>> {
+        if signal.is_none() && grace_period.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(GracefulTermination {
+            signal: normalize_signal(signal.unwrap_or(DEFAULT_TERMINATION_SIGNAL))?,
+            grace_period: grace_period.unwrap_or(WRAPPER_KILL_GRACE_PERIOD),
+        }))
+    }
+
+    /// How long after the start of a run the signal is sent.
+    pub fn signal_after(&self, time_limit: Duration) -> Duration {
+        time_limit.saturating_sub(self.grace_period)
+    }
+}
+
+/// Turn a signal name like `sigusr1` or `USR1` into `USR1`.
+pub fn normalize_signal(name: &str) -> Result<String> {
+    let upper = name.trim().to_ascii_uppercase();
+    let short = upper.strip_prefix("SIG").unwrap_or(&upper);
+
+    if !TERMINATION_SIGNALS.contains(&short) {
+        bailc!(
+            "Unknown termination signal {name:?}", ;
+            "A program can only be asked to stop with a signal that it can handle", ;
+            "Use one of {}", TERMINATION_SIGNALS.join(", "),
+        );
+    }
+
+    Ok(short.to_string())
+}
+
+#[cfg(test)]
+#[path = "tests/termination.rs"]
+mod tests;
@@ -0,0 +1,110 @@
+use super::*;
+
+/// Parameter values from pairs of names and values.
+fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_evaluate_conditions() {
+    let evaluate = |source: &str, pairs| Condition::new(source).unwrap().evaluate(&values(pairs));
+
+    assert_eq!(
+        Some(false),
+        evaluate("threads > size", &[("threads", "8"), ("size", "10")])
+    );
+    assert_eq!(Some(true), evaluate("threads<=10", &[("threads", "8")]));
+
+    // numbers are compared as numbers, "10" < "9" as text
+    assert_eq!(Some(true), evaluate("x > 9", &[("x", "10")]));
+    assert_eq!(Some(true), evaluate("x == 0", &[("x", "0.0")]));
+    assert_eq!(Some(true), evaluate("x == 'exact'", &[("x", "exact")]));
+    assert_eq!(Some(false), evaluate("x != \"exact\"", &[("x", "exact")]));
+
+    // && binds stronger than ||
+    let pairs = &[("a", "1"), ("b", "2")];
+    assert_eq!(Some(true), evaluate("a == 1 && b == 3 || b == 2", pairs));
+    assert_eq!(Some(false), evaluate("a == 2 || a == 1 && b == 3", pairs));
+
+    // a subparameter is named with a dot
+    assert_eq!(Some(true), evaluate("x.a < 2", &[("x.a", "1")]));
+
+    // a condition on a parameter without a value does not apply
+    assert_eq!(None, evaluate("a == 1 && c == 1", pairs));
+}
+
+#[test]
+fn test_invalid_conditions() {
+    for source in [
+        "",
+        "threads",
+        "threads >",
+        "threads = 8",
+        "a == 1 &&",
+        "a == 1 b == 2",
+        "a == 'open",
+        "a == $b",
+    ] {
+        assert!(Condition::new(source).is_err(), "{source:?}");
+    }
+
+    let error = toml::from_str::<Constraints>("exclude = [\"x => 1\"]").unwrap_err();
+    assert!(error.to_string().contains("This is not a valid condition"));
+}
+
+#[test]
+fn test_constraints_exclude() {
+    let constraints: Constraints = toml::from_str(
+        r#"
+        exclude = ["algorithm == 'exact' && level != 0"]
+        require = ["threads <= size"]
+        "#,
+    )
+    .unwrap();
+
+    assert!(constraints.excludes(&values(&[("algorithm", "exact"), ("level", "2")])));
+    assert!(!constraints.excludes(&values(&[("algorithm", "exact"), ("level", "0")])));
+    assert!(constraints.excludes(&values(&[("threads", "8"), ("size", "4")])));
+    assert!(!constraints.excludes(&values(&[("threads", "8")])));
+    assert!(!Constraints::default().excludes(&values(&[("threads", "8")])));
+
+    // the condition is kept as it was written
+    assert_eq!(
+        "exclude = [\"algorithm == 'exact' && level != 0\"]\nrequire = [\"threads <= size\"]\n",
+        toml::to_string(&constraints).unwrap()
+    );
+}
+
+#[test]
+fn test_validate_constraints() {
+    let parameters: BTreeMap<String, Parameter> = toml::from_str(
+        r#"
+        threads.values = ["1", "2"]
+        x.sub.a.values = ["1"]
+        "#,
+    )
+    .unwrap();
+    let constraints = |source: &str| Constraints {
+        exclude: vec![Condition::new(source).unwrap()],
+        require: vec![],
+    };
+
+    assert!(constraints("threads > x.a")
+        .validate(&Some(parameters.clone()))
+        .is_ok());
+
+    for source in ["threads > cores", "x > 1", "threads.a > 1", "x.b > 1"] {
+        let error = constraints(source)
+            .validate(&Some(parameters.clone()))
+            .unwrap_err();
+        assert!(
+            format!("{error:#}").contains("Unknown parameter"),
+            "{source}"
+        );
+    }
+
+    assert!(constraints("threads > 1").validate(&None).is_err());
+}
@@ -0,0 +1,85 @@
+use super::*;
+
+/// An extraction from the standard output.
+fn extraction(regex: &str, value_type: ValueType, aggregate: Aggregation) -> Extraction {
+    Extraction {
+        regex: regex_lite::Regex::new(regex).unwrap().into(),
+        source: OutputSource::Stdout,
+        value_type,
+        aggregate,
+    }
+}
+
+const OUTPUT: &str = "explored 12 nodes in 0.5s\n\
+                      explored 340 nodes in 1m 2s\n\
+                      explored many nodes in 2s\n\
+                      explored 7 nodes in 250ms\n";
+
+#[test]
+fn test_aggregations() {
+    let nodes = |aggregate| {
+        extraction(r"explored (?<value>\d+) nodes", ValueType::Int, aggregate).extract(OUTPUT)
+    };
+
+    assert_eq!(Some(MetricValue::Int(12)), nodes(Aggregation::First));
+    assert_eq!(Some(MetricValue::Int(7)), nodes(Aggregation::Last));
+    assert_eq!(Some(MetricValue::Int(340)), nodes(Aggregation::Max));
+    assert_eq!(Some(MetricValue::Int(7)), nodes(Aggregation::Min));
+}
+
+#[test]
+fn test_value_types() {
+    // a single named group is the value, whatever its name
+    let time = extraction(r"in (?<time>[^\n]+)", ValueType::Duration, Aggregation::Max);
+    assert_eq!(Some(MetricValue::Float(62.0)), time.extract(OUTPUT));
+
+    let time = extraction(r"in (?<time>[^\n]+)", ValueType::Duration, Aggregation::Min);
+    assert_eq!(Some(MetricValue::Float(0.25)), time.extract(OUTPUT));
+
+    // matches that are not of the type are skipped
+    let count = extraction(
+        r"(?<verb>\w+) (?<value>\w+) nodes",
+        ValueType::Float,
+        Aggregation::First,
+    );
+    assert_eq!(Some(MetricValue::Float(12.0)), count.extract(OUTPUT));
+
+    let count = extraction(
+        r"(?<verb>\w+) (?<value>\w+) nodes",
+        ValueType::String,
+        Aggregation::Max,
+    );
+    assert_eq!(
+        Some(MetricValue::Text("many".to_string())),
+        count.extract(OUTPUT)
+    );
+
+    let missing = extraction(r"found (?<value>\d+)", ValueType::Int, Aggregation::Last);
+    assert_eq!(None, missing.extract(OUTPUT));
+}
+
+#[test]
+fn test_capture_group_is_required() {
+    let unnamed = extraction(r"explored (\d+)", ValueType::Int, Aggregation::Last);
+    let ambiguous = extraction(r"(?<a>\d+) (?<b>\d+)", ValueType::Int, Aggregation::Last);
+
+    assert!(unnamed.validate("nodes").is_err());
+    assert!(ambiguous.validate("nodes").is_err());
+    assert!(
+        extraction(r"(?<value>\d+)", ValueType::Int, Aggregation::Last)
+            .validate("nodes")
+            .is_ok()
+    );
+
+    let parsed: Extraction = toml::from_str(
+        r#"
+        regex = "objective (?<value>[0-9.]+)"
+        source = "afterscript"
+        type = "float"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(OutputSource::Afterscript, parsed.source);
+    assert_eq!(Aggregation::Last, parsed.aggregate);
+    assert!(toml::from_str::<Extraction>("regex = \"x\"\naggregate = \"sum\"").is_err());
+}
@@ -0,0 +1,139 @@
+use std::fs;
+
+use tempdir::TempDir;
+
+use super::*;
+use crate::config::Config;
+use crate::test_utils::REAL_FS;
+
+/// Write files into a new temporary folder.
+fn write_files(files: &[(&str, &str)]) -> TempDir {
+    let dir = TempDir::new("include").unwrap();
+
+    for (name, contents) in files {
+        let path = dir.path().join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    dir
+}
+
+#[test]
+fn test_includes_are_merged() {
+    let dir = write_files(&[
+        (
+            "common/slurm.toml",
+            r#"
+            [slurm]
+            experiment_name = "common"
+            output_folder = "./slurm"
+            partition = "compute"
+            account = "research"
+
+            [resource_limits]
+            time_limit = "1m"
+            cpus = 1
+            mem_per_cpu = 512
+
+            [program.a.env]
+            X = "1"
+            "#,
+        ),
+        (
+            "common/inputs.toml",
+            r#"
+            [input.small]
+            arguments = ["1"]
+            "#,
+        ),
+        (
+            "project/gourd.toml",
+            r#"
+            include = ["../common/slurm.toml", "../common/inputs.toml"]
+            output_path = "out"
+            metrics_path = "metrics"
+            experiments_folder = "experiments"
+
+            [slurm]
+            partition = "gpu"
+
+            [program.a]
+            binary = "/bin/sleep"
+
+            [input.large]
+            arguments = ["100"]
+            "#,
+        ),
+    ]);
+
+    let config = Config::from_file(&dir.path().join("project/gourd.toml"), &REAL_FS).unwrap();
+
+    let slurm = config.slurm.unwrap();
+    assert_eq!("gpu", slurm.partition);
+    assert_eq!("research", slurm.account);
+    assert_eq!(Some(512), config.resource_limits.map(|l| l.mem_per_cpu));
+    assert_eq!(
+        Some(PathBuf::from("/bin/sleep")),
+        config.programs["a"].binary
+    );
+    assert_eq!("1", config.programs["a"].env["X"]);
+    assert_eq!(
+        vec!["large", "small"],
+        config.inputs.keys().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_conflicting_includes_name_their_files() {
+    let dir = write_files(&[
+        ("a.toml", "[slurm]\npartition = \"one\"\n"),
+        ("b.toml", "[slurm]\npartition = \"two\"\n"),
+        ("c.toml", "[slurm]\npartition = \"one\"\n"),
+        ("gourd.toml", "include = [\"a.toml\", \"b.toml\"]\n"),
+        ("same.toml", "include = [\"a.toml\", \"c.toml\"]\n"),
+    ]);
+
+    let path = dir.path().join("gourd.toml");
+    let err = format!(
+        "{:#}",
+        merge_includes(
+            &path,
+            REAL_FS.try_read_toml(&path).unwrap(),
+            &mut Variables::new(),
+            &REAL_FS
+        )
+        .unwrap_err()
+    );
+
+    assert!(err.contains("slurm.partition"));
+    assert!(err.contains("a.toml"));
+    assert!(err.contains("b.toml"));
+
+    // the same value in two files is not a conflict
+    let path = dir.path().join("same.toml");
+    assert!(merge_includes(
+        &path,
+        REAL_FS.try_read_toml(&path).unwrap(),
+        &mut Variables::new(),
+        &REAL_FS
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_include_cycle() {
+    let dir = write_files(&[
+        ("a.toml", "include = [\"b.toml\"]\n"),
+        ("b.toml", "include = [\"a.toml\"]\n"),
+    ]);
+
+    let path = dir.path().join("a.toml");
+    assert!(merge_includes(
+        &path,
+        REAL_FS.try_read_toml(&path).unwrap(),
+        &mut Variables::new(),
+        &REAL_FS
+    )
+    .is_err());
+}
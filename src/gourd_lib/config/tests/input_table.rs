@@ -0,0 +1,122 @@
+use std::fs;
+
+use tempdir::TempDir;
+
+use super::*;
+use crate::config::Config;
+use crate::constants::INTERNAL_PREFIX;
+use crate::constants::INTERNAL_SCHEMA_INPUTS;
+use crate::test_utils::REAL_FS;
+
+#[test]
+fn test_read_csv_table() {
+    let inputs = read_csv_table(
+        Path::new("inputs.csv"),
+        "file, n, density, family, arguments, group, solved\n\
+         a.graph, 10, 0.5, random, -v --fast, small, true\n\
+         b.graph, 2000, 1, grid, , large, false\n\
+         c.graph, , 0.25, 7, , large, maybe\n",
+    )
+    .unwrap();
+
+    assert_eq!(3, inputs.len());
+
+    let a = &inputs[0].input;
+    assert_eq!(None, inputs[0].name);
+    assert_eq!(Some(PathBuf::from("a.graph")), a.file);
+    assert_eq!(vec!["-v", "--fast"], a.arguments);
+    assert_eq!(Some("small".to_string()), a.group);
+
+    // a column has the type of all of its values
+    assert_eq!(
+        BTreeMap::from([
+            ("n".to_string(), MetricValue::Int(10)),
+            ("density".to_string(), MetricValue::Float(0.5)),
+            (
+                "family".to_string(),
+                MetricValue::Text("random".to_string())
+            ),
+            ("solved".to_string(), MetricValue::Text("true".to_string())),
+        ]),
+        a.metadata
+    );
+    assert_eq!(
+        Some(&MetricValue::Float(1.0)),
+        inputs[1].input.metadata.get("density")
+    );
+    assert_eq!(
+        Some(&MetricValue::Text("7".to_string())),
+        inputs[2].input.metadata.get("family")
+    );
+
+    // empty cells are left out
+    assert!(inputs[1].input.arguments.is_empty());
+    assert!(!inputs[2].input.metadata.contains_key("n"));
+}
+
+#[test]
+fn test_invalid_csv_table() {
+    let error = read_csv_table(Path::new("inputs.csv"), "file, n\na.graph, 1, 2\n").unwrap_err();
+
+    assert!(format!("{error:#}").contains("Could not read row 1"));
+}
+
+#[test]
+fn test_schema_inputs_are_named() {
+    let dir = TempDir::new("input_table").unwrap();
+    let csv = dir.path().join("inputs.csv");
+    let toml = dir.path().join("inputs.toml");
+
+    fs::write(&csv, "name,file,n\n,a.graph,1\nlarge,b.graph,2\n,,3\n").unwrap();
+    fs::write(
+        &toml,
+        "[[input]]\nfile = \"c.graph\"\nmetadata = { n = 4 }\n\n\
+         [[input]]\nname = \"named\"\narguments = [\"x\"]\n",
+    )
+    .unwrap();
+
+    let name = |name: &str| format!("{name}{INTERNAL_PREFIX}{INTERNAL_SCHEMA_INPUTS}");
+
+    let inputs = Config::parse_schema_inputs(&csv, BTreeMap::new(), &REAL_FS).unwrap();
+    assert_eq!(
+        vec![name("2"), name("a"), name("large")],
+        inputs.keys().cloned().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some(&MetricValue::Int(2)),
+        inputs[&name("large")].metadata.get("n")
+    );
+
+    let inputs = Config::parse_schema_inputs(&toml, BTreeMap::new(), &REAL_FS).unwrap();
+    assert_eq!(
+        vec![name("c"), name("named")],
+        inputs.keys().cloned().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some(&MetricValue::Int(4)),
+        inputs[&name("c")].metadata.get("n")
+    );
+
+    // files with the same name in different folders are called by their index
+    fs::write(&csv, "file\na/x.graph\nb/x.graph\nc/y.graph\n").unwrap();
+    let inputs = Config::parse_schema_inputs(&csv, BTreeMap::new(), &REAL_FS).unwrap();
+    assert_eq!(
+        vec![name("0"), name("1"), name("y")],
+        inputs.keys().cloned().collect::<Vec<_>>()
+    );
+
+    fs::write(
+        &toml,
+        "[[input]]\nfile = \"a/inst.txt\"\n\n[[input]]\nfile = \"b/inst.txt\"\n",
+    )
+    .unwrap();
+    let inputs = Config::parse_schema_inputs(&toml, BTreeMap::new(), &REAL_FS).unwrap();
+    assert_eq!(
+        vec![name("0"), name("1")],
+        inputs.keys().cloned().collect::<Vec<_>>()
+    );
+
+    fs::write(&csv, "name,file\nx,a.graph\nx,b.graph\n").unwrap();
+    let error = Config::parse_schema_inputs(&csv, BTreeMap::new(), &REAL_FS).unwrap_err();
+    assert!(format!("{error:#}").contains("are called x"));
+}
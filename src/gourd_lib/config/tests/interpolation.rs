@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::PathBuf;
+
+use tempdir::TempDir;
+
+use super::*;
+use crate::config::Config;
+use crate::test_utils::REAL_FS;
+
+/// Resolve the variables in a string, with the values that were used.
+fn resolve(text: &str) -> Result<(String, Variables)> {
+    let mut variables = Variables::new();
+    let resolved = interpolate(text, "key", &mut variables, Path::new("gourd.toml"))?;
+
+    Ok((resolved, variables))
+}
+
+#[test]
+fn test_interpolate_variables() {
+    env::set_var("GOURD_TEST_SCRATCH", "/scratch/alice");
+    env::set_var("GOURD_TEST_EMPTY", "");
+    env::remove_var("GOURD_TEST_UNSET");
+
+    let (resolved, variables) = resolve("${GOURD_TEST_SCRATCH}/instances").unwrap();
+    assert_eq!("/scratch/alice/instances", resolved);
+    assert_eq!(
+        Variables::from([(
+            "GOURD_TEST_SCRATCH".to_string(),
+            "/scratch/alice".to_string()
+        )]),
+        variables
+    );
+
+    // defaults are used for unset and empty variables, and recorded
+    let (resolved, variables) =
+        resolve("${GOURD_TEST_UNSET:-/tmp}/${GOURD_TEST_EMPTY:-x}").unwrap();
+    assert_eq!("/tmp/x", resolved);
+    assert_eq!("/tmp", variables["GOURD_TEST_UNSET"]);
+
+    // only the braced form is a variable
+    assert_eq!("a$b ^ok$ {input}", resolve("a$b ^ok$ {input}").unwrap().0);
+    assert_eq!(
+        "${GOURD_TEST_SCRATCH}",
+        resolve("$${GOURD_TEST_SCRATCH}").unwrap().0
+    );
+}
+
+#[test]
+fn test_interpolate_home() {
+    let home = shellexpand::tilde("~").to_string();
+
+    let (resolved, variables) = resolve("~/instances").unwrap();
+    assert_eq!(format!("{home}/instances"), resolved);
+    assert_eq!(home, variables["~"]);
+
+    assert_eq!("a~/b", resolve("a~/b").unwrap().0);
+    assert_eq!("~user", resolve("~user").unwrap().0);
+}
+
+#[test]
+fn test_interpolation_errors() {
+    env::remove_var("GOURD_TEST_MISSING");
+
+    let error = resolve("${GOURD_TEST_MISSING}/x").unwrap_err();
+    assert!(format!("{error:#}").contains("The environment variable GOURD_TEST_MISSING is not set"));
+    assert!(format!("{error:?}").contains("It is used in `key` of \"gourd.toml\""));
+
+    assert!(resolve("${GOURD_TEST_MISSING").is_err());
+    assert!(resolve("${}").is_err());
+    assert!(resolve("${A B}").is_err());
+}
+
+#[test]
+fn test_config_is_interpolated() {
+    env::set_var("GOURD_TEST_CONFIG_DIR", "/scratch/bob");
+    env::remove_var("GOURD_TEST_CONFIG_THREADS");
+
+    let dir = TempDir::new("interpolation").unwrap();
+
+    fs::write(
+        dir.path().join("common.toml"),
+        r#"
+        [program.a]
+        binary = "${GOURD_TEST_CONFIG_DIR}/solver"
+        arguments = ["-t", "${GOURD_TEST_CONFIG_THREADS:-4}"]
+        "#,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("gourd.toml"),
+        r#"
+        include = ["common.toml"]
+        output_path = "${GOURD_TEST_CONFIG_DIR}/output"
+        metrics_path = "~/metrics"
+        experiments_folder = "experiments"
+
+        [input.x]
+        file = "${GOURD_TEST_CONFIG_DIR}/instances/x"
+        "#,
+    )
+    .unwrap();
+
+    let conf = Config::from_file(&dir.path().join("gourd.toml"), &REAL_FS).unwrap();
+
+    assert_eq!(PathBuf::from("/scratch/bob/output"), conf.output_path);
+    assert_eq!(
+        PathBuf::from(shellexpand::tilde("~/metrics").to_string()),
+        conf.metrics_path
+    );
+    assert_eq!(
+        Some(PathBuf::from("/scratch/bob/solver")),
+        conf.programs["a"].binary
+    );
+    assert_eq!(vec!["-t", "4"], conf.programs["a"].arguments);
+    assert_eq!(
+        Some(PathBuf::from("/scratch/bob/instances/x")),
+        conf.inputs["x"].file
+    );
+    assert_eq!(
+        vec!["GOURD_TEST_CONFIG_DIR", "GOURD_TEST_CONFIG_THREADS", "~"],
+        conf.variables.keys().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_undefined_variable_in_config() {
+    env::remove_var("GOURD_TEST_UNDEFINED");
+
+    let dir = TempDir::new("interpolation").unwrap();
+    fs::write(
+        dir.path().join("gourd.toml"),
+        r#"
+        output_path = "${GOURD_TEST_UNDEFINED}/output"
+        metrics_path = "metrics"
+        experiments_folder = "experiments"
+        "#,
+    )
+    .unwrap();
+
+    let error = Config::from_file(&dir.path().join("gourd.toml"), &REAL_FS).unwrap_err();
+
+    assert!(format!("{error:?}").contains("It is used in `output_path`"));
+}
@@ -0,0 +1,104 @@
+use super::*;
+
+/// A label read from TOML.
+fn label(toml: &str) -> Label {
+    toml::from_str(toml).unwrap()
+}
+
+#[test]
+fn test_exit_codes() {
+    let codes = ExitCodes::new("1-127, 255").unwrap();
+
+    assert!(codes.contains(1));
+    assert!(codes.contains(127));
+    assert!(codes.contains(255));
+    assert!(!codes.contains(0));
+    assert!(!codes.contains(128));
+    assert!(ExitCodes::new("-1").unwrap().contains(-1));
+
+    for source in ["", "1-", "a", "5-2", "1..3"] {
+        assert!(ExitCodes::new(source).is_err(), "{source}");
+    }
+
+    // a single code stays a number
+    let codes = label("exit_codes = 3").exit_codes.unwrap();
+    assert!(codes.contains(3));
+    assert!(toml::to_string(&label("exit_codes = 3"))
+        .unwrap()
+        .contains("exit_codes = 3\n"));
+}
+
+#[test]
+fn test_thresholds() {
+    let variables = BTreeMap::from([
+        ("wall_time".to_string(), 95.0),
+        ("time_limit".to_string(), 100.0),
+        ("max_rss".to_string(), 2048.0),
+    ]);
+    let holds = |source: &str| Threshold::new(source).unwrap().holds(&variables);
+
+    assert!(holds("wall_time > 0.9 * time_limit"));
+    assert!(holds("wall_time >= 1m"));
+    assert!(holds("max_rss == 2048"));
+    assert!(holds("max_rss != 0"));
+    assert!(!holds("max_rss < 1000"));
+    assert!(!holds("wall_time <= time_limit / 2"));
+
+    // a metric that the run does not have never holds
+    assert!(!holds("nodes > 0"));
+    assert!(!holds("nodes <= 0"));
+
+    for source in ["wall_time", "> 1", "wall_time > ", "1 = 1", "a > b > c"] {
+        assert!(Threshold::new(source).is_err(), "{source}");
+    }
+}
+
+#[test]
+fn test_label_matches_all_conditions() {
+    let crash = label(
+        r#"
+        exit_codes = "1-255"
+        stderr = "panicked"
+        metrics = ["wall_time > 10"]
+        "#,
+    );
+    let outcome = RunOutcome {
+        exit_code: 101,
+        stderr: Some("thread 'main' panicked at src/main.rs"),
+        variables: BTreeMap::from([("wall_time".to_string(), 12.0)]),
+        ..Default::default()
+    };
+
+    assert!(crash.validate("crash").is_ok());
+    assert!(crash.matches(&outcome));
+    assert!(!crash.matches(&RunOutcome {
+        exit_code: 0,
+        ..outcome.clone()
+    }));
+    assert!(!crash.matches(&RunOutcome {
+        stderr: None,
+        ..outcome.clone()
+    }));
+    assert!(!crash.matches(&RunOutcome {
+        variables: BTreeMap::new(),
+        ..outcome
+    }));
+}
+
+#[test]
+fn test_label_signals() {
+    let segfault = label("signals = [\"sigsegv\", \"ABRT\"]");
+    let signalled = |signal| RunOutcome {
+        signal,
+        ..Default::default()
+    };
+
+    assert!(segfault.validate("segfault").is_ok());
+    assert!(segfault.matches(&signalled(signal_number("SEGV"))));
+    assert!(segfault.matches(&signalled(signal_number("6"))));
+    assert!(!segfault.matches(&signalled(signal_number("TERM"))));
+    assert!(!segfault.matches(&signalled(None)));
+
+    assert!(label("signals = [\"NOPE\"]").validate("x").is_err());
+    assert!(label("priority = 2").validate("empty").is_err());
+}
@@ -0,0 +1,109 @@
+use super::*;
+
+/// The variables `n` and `threads`.
+fn variables() -> BTreeMap<String, f64> {
+    BTreeMap::from([("n".to_string(), 20000.0), ("threads".to_string(), 4.0)])
+}
+
+#[test]
+fn test_evaluate_expressions() {
+    let value = |source: &str| LimitExpression::new(source).unwrap().evaluate(&variables());
+
+    assert_eq!(200.0, value("10s * n / 1000").unwrap());
+    assert_eq!(8.0, value("2 * threads").unwrap());
+    assert_eq!(3.0, value("1 + 2 * 3 - 4").unwrap());
+    assert_eq!(5.0, value("(1 + 4) * (3 - 2)").unwrap());
+    assert_eq!(90.0, value("1min + 30s").unwrap());
+    assert_eq!(1.0, value("-1 + 2").unwrap());
+    assert_eq!(0.5, value("500ms").unwrap());
+    assert_eq!(1000.0, value("1e3").unwrap());
+    assert_eq!(250.0, value("2.5E2s").unwrap());
+    assert_eq!(0.02, value("2e-2 * 1").unwrap());
+
+    // a limit must be a positive number
+    assert!(format!("{:#}", value("threads - 4").unwrap_err()).contains("not positive"));
+    assert!(value("n / 0 - n / 0").is_err());
+    assert!(format!("{:#}", value("size * 2").unwrap_err()).contains("Unknown variable size"));
+}
+
+#[test]
+fn test_invalid_expressions() {
+    for source in [
+        "",
+        "10 *",
+        "(n + 1",
+        "n 2",
+        "10 parsecs",
+        "n % 2",
+        "1..2",
+        "1e",
+    ] {
+        assert!(LimitExpression::new(source).is_err(), "{source}");
+    }
+}
+
+#[test]
+fn test_apply_overrides() {
+    let overrides: LimitOverrides = toml::from_str(
+        r#"
+        time_limit = "2h 30m"
+        cpus = "threads / 3"
+        "#,
+    )
+    .unwrap();
+
+    let limits = overrides
+        .apply(ResourceLimits::default(), &variables())
+        .unwrap();
+
+    assert_eq!(Duration::from_secs(9000), limits.time_limit);
+    assert_eq!(2, limits.cpus);
+    assert_eq!(ResourceLimits::default().mem_per_cpu, limits.mem_per_cpu);
+
+    // a limit can be a float
+    let float: LimitOverrides = toml::from_str("time_limit = 1.5").unwrap();
+    assert_eq!(
+        Duration::from_millis(1500),
+        float
+            .apply(ResourceLimits::default(), &variables())
+            .unwrap()
+            .time_limit
+    );
+
+    let general: LimitOverrides = toml::from_str("cpus = 8\nmem_per_cpu = 512").unwrap();
+    let merged = overrides.or(&general);
+
+    assert_eq!(overrides.cpus, merged.cpus);
+    assert_eq!(general.mem_per_cpu, merged.mem_per_cpu);
+    assert!(LimitOverrides::default().is_empty());
+
+    // numbers stay numbers
+    assert_eq!(
+        "cpus = 8\nmem_per_cpu = 512\n",
+        toml::to_string(&general).unwrap()
+    );
+    assert!(toml::from_str::<LimitOverrides>("cpus = \"2 +\"").is_err());
+}
+
+#[test]
+fn test_limit_variables() {
+    let metadata = BTreeMap::from([
+        ("n".to_string(), MetricValue::Int(10)),
+        ("density".to_string(), MetricValue::Float(0.5)),
+        ("family".to_string(), MetricValue::Text("grid".to_string())),
+        ("solved".to_string(), MetricValue::Bool(true)),
+    ]);
+    let parameters = [
+        ("threads".to_string(), "8".to_string()),
+        ("algorithm".to_string(), "exact".to_string()),
+    ];
+
+    assert_eq!(
+        BTreeMap::from([
+            ("density".to_string(), 0.5),
+            ("n".to_string(), 10.0),
+            ("threads".to_string(), 8.0),
+        ]),
+        limit_variables(&metadata, parameters.iter().map(|(k, v)| (k, v)))
+    );
+}
@@ -12,6 +12,7 @@ use std::path::PathBuf;
 use tempdir::TempDir;
 
 use crate::config::Config;
+use crate::config::Seeds;
 use crate::config::UserInput;
 use crate::config::UserProgram;
 use crate::constants::WRAPPER_DEFAULT;
@@ -31,12 +32,25 @@ fn breaking_changes_config_struct() {
         wrapper: "".to_string(),
         inputs: BTreeMap::default(),
         parameters: None,
+        constraints: None,
+        sampling: None,
+        pairings: None,
+        groups: None,
+        extractions: None,
         programs: BTreeMap::default(),
         input_schema: None,
         slurm: None,
         resource_limits: None,
         local: None,
         labels: Some(BTreeMap::new()),
+        variables: BTreeMap::new(),
+        repetitions: None,
+        seeds: None,
+        warmup: None,
+        measurement: None,
+        env: None,
+        clean_env: false,
+        env_whitelist: None,
     };
 }
 
@@ -70,12 +84,25 @@ fn breaking_changes_config_file_all_values() {
             wrapper: "gourd_wrapper".to_string(),
             inputs: BTreeMap::default(),
             parameters: None,
+            constraints: None,
+            sampling: None,
+            pairings: None,
+            groups: None,
+            extractions: None,
             programs: BTreeMap::default(),
             input_schema: None,
             slurm: None,
             resource_limits: None,
             local: None,
             labels: None,
+            variables: BTreeMap::new(),
+            repetitions: None,
+            seeds: None,
+            warmup: None,
+            measurement: None,
+            env: None,
+            clean_env: false,
+            env_whitelist: None,
         },
         Config::from_file(file_pathbuf.as_path(), &REAL_FS).expect("Unexpected config read error.")
     );
@@ -107,12 +134,25 @@ fn breaking_changes_config_file_required_values() {
             wrapper: "gourd_wrapper".to_string(),
             inputs: BTreeMap::default(),
             parameters: None,
+            constraints: None,
+            sampling: None,
+            pairings: None,
+            groups: None,
+            extractions: None,
             programs: BTreeMap::default(),
             input_schema: None,
             slurm: None,
             resource_limits: None,
             local: None,
             labels: None,
+            variables: BTreeMap::new(),
+            repetitions: None,
+            seeds: None,
+            warmup: None,
+            measurement: None,
+            env: None,
+            clean_env: false,
+            env_whitelist: None,
         },
         Config::from_file(file_pb.as_path(), &REAL_FS).expect("Unexpected config read error.")
     );
@@ -210,11 +250,15 @@ fn test_regex_that_do_match() {
     );
     let config =
         Config::from_file(file_pb.as_path(), &REAL_FS).expect("Unexpected config read error.");
-    assert!(
-        regex_lite::Regex::new(config.labels.unwrap().get("stefan").unwrap().regex.as_str())
+    assert!(regex_lite::Regex::new(
+        config.labels.unwrap()["stefan"]
+            .regex
+            .as_ref()
             .unwrap()
-            .is_match("18C loves stefan")
-    );
+            .as_str()
+    )
+    .unwrap()
+    .is_match("18C loves stefan"));
 }
 
 #[test]
@@ -282,6 +326,11 @@ fn parse_valid_escape_hatch_file() {
         metrics_path: dir.path().join("43"),
         experiments_folder: dir.path().join("44"),
         parameters: None,
+        constraints: None,
+        sampling: None,
+        pairings: None,
+        groups: None,
+        extractions: None,
         local: None,
         programs: vec![(
             "x".to_string(),
@@ -293,6 +342,16 @@ fn parse_valid_escape_hatch_file() {
                 afterscript: None,
                 resource_limits: None,
                 next: vec![],
+                repetitions: None,
+                seeds: None,
+                termination_signal: None,
+                grace_period: None,
+                warmup: None,
+                env: BTreeMap::new(),
+                inputs: None,
+                exclude_inputs: vec![],
+                groups: None,
+                exclude_groups: vec![],
             },
         )]
         .into_iter()
@@ -310,6 +369,9 @@ fn parse_valid_escape_hatch_file() {
                     fetch: None,
                     group: None,
                     arguments: vec!["hello".to_string()],
+                    env: BTreeMap::new(),
+                    metadata: BTreeMap::new(),
+                    resource_limits: None,
                 },
             ),
             (
@@ -324,6 +386,9 @@ fn parse_valid_escape_hatch_file() {
                     fetch: None,
                     group: None,
                     arguments: vec!["hi".to_string()],
+                    env: BTreeMap::new(),
+                    metadata: BTreeMap::new(),
+                    resource_limits: None,
                 },
             ),
         ]
@@ -334,6 +399,57 @@ fn parse_valid_escape_hatch_file() {
         resource_limits: None,
         wrapper: WRAPPER_DEFAULT(),
         labels: None,
+        variables: BTreeMap::new(),
+        repetitions: None,
+        seeds: None,
+        warmup: None,
+        measurement: None,
+        env: None,
+        clean_env: false,
+        env_whitelist: None,
     };
     assert_eq!(c1, c2);
 }
+
+#[test]
+fn test_seeds_parse() {
+    let (file_pb, dir) = create_sample_toml(
+        r#"
+        output_path = ""
+        metrics_path = ""
+        experiments_folder = ""
+        seeds = { count = 3, base = 40 }
+
+        [input]
+
+        [program.a]
+        binary = "/bin/sleep"
+        seeds = [5, 1]
+    "#,
+    );
+
+    let conf = Config::from_file(file_pb.as_path(), &REAL_FS).unwrap();
+
+    assert_eq!(Some(vec![40, 41, 42]), conf.seeds.map(|s| s.values()));
+    assert_eq!(
+        Some(vec![5, 1]),
+        conf.programs["a"].seeds.as_ref().map(Seeds::values)
+    );
+    dir.close().unwrap();
+
+    let (file_pb, dir) = create_sample_toml(
+        r#"
+        output_path = ""
+        metrics_path = ""
+        experiments_folder = ""
+        seeds = { count = 3, bsae = 40 }
+
+        [input]
+
+        [program]
+    "#,
+    );
+
+    assert!(Config::from_file(file_pb.as_path(), &REAL_FS).is_err());
+    dir.close().unwrap();
+}
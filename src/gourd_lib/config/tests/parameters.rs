@@ -1,5 +1,6 @@
 use super::*;
 use crate::config::SubParameter;
+use crate::config::UserProgram;
 
 #[test]
 fn test_expand_parameters_ok_no_expandable() {
@@ -12,6 +13,9 @@ fn test_expand_parameters_ok_no_expandable() {
             fetch: None,
             group: None,
             arguments: vec!["nice".to_string()],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
 
@@ -31,6 +35,9 @@ fn test_expand_parameters_ok_parameter() {
             fetch: None,
             group: None,
             arguments: vec!["-e".to_string(), "param|x".to_string()],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     let mut parameters = BTreeMap::new();
@@ -39,6 +46,12 @@ fn test_expand_parameters_ok_parameter() {
         Parameter {
             sub: None,
             values: Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            range: None,
+            linspace: None,
+            logspace: None,
+            format: None,
+            precision: None,
+            resource_limits: None,
         },
     );
     let expanded = expand_parameters(inputs.clone(), &parameters).unwrap();
@@ -51,6 +64,9 @@ fn test_expand_parameters_ok_parameter() {
             fetch: None,
             group: None,
             arguments: vec!["-e".to_string(), "a".to_string()],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -61,6 +77,9 @@ fn test_expand_parameters_ok_parameter() {
             fetch: None,
             group: None,
             arguments: vec!["-e".to_string(), "b".to_string()],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -71,6 +90,9 @@ fn test_expand_parameters_ok_parameter() {
             fetch: None,
             group: None,
             arguments: vec!["-e".to_string(), "c".to_string()],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
 
@@ -93,6 +115,9 @@ fn test_expand_parameters_ok_parameter_doubled() {
                 "-f".to_string(),
                 "param|x".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     let mut parameters = BTreeMap::new();
@@ -101,6 +126,12 @@ fn test_expand_parameters_ok_parameter_doubled() {
         Parameter {
             sub: None,
             values: Some(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            range: None,
+            linspace: None,
+            logspace: None,
+            format: None,
+            precision: None,
+            resource_limits: None,
         },
     );
     let expanded = expand_parameters(inputs.clone(), &parameters).unwrap();
@@ -118,6 +149,9 @@ fn test_expand_parameters_ok_parameter_doubled() {
                 "-f".to_string(),
                 "a".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -133,6 +167,9 @@ fn test_expand_parameters_ok_parameter_doubled() {
                 "-f".to_string(),
                 "b".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -148,6 +185,9 @@ fn test_expand_parameters_ok_parameter_doubled() {
                 "-f".to_string(),
                 "c".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     assert_eq!(expanded, expected);
@@ -169,6 +209,9 @@ fn test_expand_parameters_ok_subparameter() {
                 "-x".to_string(),
                 "subparam|x.2".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     let mut sub_parameters = BTreeMap::new();
@@ -190,6 +233,12 @@ fn test_expand_parameters_ok_subparameter() {
         Parameter {
             sub: Some(sub_parameters),
             values: None,
+            range: None,
+            linspace: None,
+            logspace: None,
+            format: None,
+            precision: None,
+            resource_limits: None,
         },
     );
     let expanded = expand_parameters(inputs, &parameters).unwrap();
@@ -207,6 +256,9 @@ fn test_expand_parameters_ok_subparameter() {
                 "-x".to_string(),
                 "10".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -222,6 +274,9 @@ fn test_expand_parameters_ok_subparameter() {
                 "-x".to_string(),
                 "20".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -237,6 +292,9 @@ fn test_expand_parameters_ok_subparameter() {
                 "-x".to_string(),
                 "30".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     assert_eq!(expanded, expected);
@@ -260,6 +318,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "subparam|x.2".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     let mut sub_parameters = BTreeMap::new();
@@ -282,6 +343,12 @@ fn test_expand_parameters_ok_both() {
         Parameter {
             sub: Some(sub_parameters),
             values: None,
+            range: None,
+            linspace: None,
+            logspace: None,
+            format: None,
+            precision: None,
+            resource_limits: None,
         },
     );
     parameters.insert(
@@ -293,6 +360,12 @@ fn test_expand_parameters_ok_both() {
                 "yyy".to_string(),
                 "zzz".to_string(),
             ]),
+            range: None,
+            linspace: None,
+            logspace: None,
+            format: None,
+            precision: None,
+            resource_limits: None,
         },
     );
 
@@ -313,6 +386,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "10".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -330,6 +406,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "20".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -347,6 +426,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "30".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -364,6 +446,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "10".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -381,6 +466,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "20".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -398,6 +486,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "30".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -415,6 +506,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "10".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -432,6 +526,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "20".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     expected.insert(
@@ -449,6 +546,9 @@ fn test_expand_parameters_ok_both() {
                 "-x".to_string(),
                 "30".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     assert_eq!(expanded, expected);
@@ -465,6 +565,9 @@ fn test_expand_parameters_fail_subparameter_not_declared() {
             fetch: None,
             group: None,
             arguments: vec!["-e".to_string(), "param|x".to_string()],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     let parameters = BTreeMap::new();
@@ -487,6 +590,9 @@ fn test_expand_parameters_fail_subparameter_size_not_match() {
                 "-x".to_string(),
                 "param|x_2".to_string(),
             ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
         },
     );
     let mut sub_parameters = BTreeMap::new();
@@ -508,7 +614,415 @@ fn test_expand_parameters_fail_subparameter_size_not_match() {
         Parameter {
             sub: Some(sub_parameters),
             values: None,
+            range: None,
+            linspace: None,
+            logspace: None,
+            format: None,
+            precision: None,
+            resource_limits: None,
         },
     );
     assert!(expand_parameters(inputs, &parameters).is_err());
 }
+
+#[test]
+fn test_expand_parameters_in_env() {
+    let mut inputs = BTreeMap::new();
+    inputs.insert(
+        "first".to_string(),
+        UserInput {
+            file: None,
+            glob: None,
+            fetch: None,
+            group: None,
+            arguments: vec!["param|x".to_string()],
+            env: BTreeMap::from([
+                ("OMP_NUM_THREADS".to_string(), "param|x".to_string()),
+                ("RUST_LOG".to_string(), "info".to_string()),
+            ]),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
+        },
+    );
+    let mut parameters = BTreeMap::new();
+    parameters.insert(
+        "x".to_string(),
+        Parameter {
+            sub: None,
+            values: Some(vec!["1".to_string(), "2".to_string()]),
+            range: None,
+            linspace: None,
+            logspace: None,
+            format: None,
+            precision: None,
+            resource_limits: None,
+        },
+    );
+
+    let expanded = expand_parameters(inputs, &parameters).unwrap();
+
+    for (i, value) in ["1", "2"].iter().enumerate() {
+        let input = &expanded[&format!("first_x_{i}{INTERNAL_PREFIX}{INTERNAL_PARAMETER}")];
+        assert_eq!(vec![value.to_string()], input.arguments);
+        assert_eq!(
+            BTreeMap::from([
+                ("OMP_NUM_THREADS".to_string(), value.to_string()),
+                ("RUST_LOG".to_string(), "info".to_string()),
+            ]),
+            input.env
+        );
+    }
+}
+
+#[test]
+fn test_expand_parameter_placeholders() {
+    let mut inputs = BTreeMap::new();
+    inputs.insert(
+        "first".to_string(),
+        UserInput {
+            file: None,
+            glob: None,
+            fetch: None,
+            group: None,
+            arguments: vec![
+                "--alpha={param.x}".to_string(),
+                "--out={work_dir}/{param.y.a}-{param.y.b}.txt".to_string(),
+            ],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
+        },
+    );
+    let mut parameters = BTreeMap::new();
+    parameters.insert(
+        "x".to_string(),
+        Parameter {
+            sub: None,
+            values: Some(vec!["0.1".to_string(), "{0.2}".to_string()]),
+            range: None,
+            linspace: None,
+            logspace: None,
+            format: None,
+            precision: None,
+            resource_limits: None,
+        },
+    );
+    parameters.insert(
+        "y".to_string(),
+        Parameter {
+            sub: Some(BTreeMap::from([
+                (
+                    "a".to_string(),
+                    SubParameter {
+                        values: vec!["1".to_string()],
+                    },
+                ),
+                (
+                    "b".to_string(),
+                    SubParameter {
+                        values: vec!["2".to_string()],
+                    },
+                ),
+            ])),
+            values: None,
+            range: None,
+            linspace: None,
+            logspace: None,
+            format: None,
+            precision: None,
+            resource_limits: None,
+        },
+    );
+
+    let expanded = expand_parameters(inputs, &parameters).unwrap();
+
+    assert_eq!(
+        vec!["--alpha=0.1", "--out={work_dir}/1-2.txt"],
+        expanded[&format!("first_x_0_y_0{INTERNAL_PREFIX}{INTERNAL_PARAMETER}")].arguments
+    );
+    // braces in values are escaped for the placeholders filled in later
+    assert_eq!(
+        vec!["--alpha={{0.2}}", "--out={work_dir}/1-2.txt"],
+        expanded[&format!("first_x_1_y_0{INTERNAL_PREFIX}{INTERNAL_PARAMETER}")].arguments
+    );
+}
+
+/// Parse a parameter from toml.
+fn parameter(toml: &str) -> Parameter {
+    toml::from_str(toml).unwrap()
+}
+
+#[test]
+fn test_parameter_range_values() {
+    let values = |toml| parameter_values(&parameter(toml)).unwrap();
+
+    assert_eq!(
+        vec!["1", "3", "5", "7"],
+        values("range = { start = 1, stop = 9, step = 2 }")
+    );
+    assert_eq!(
+        vec!["0", "1", "2"],
+        values("range = { start = 0, stop = 3 }")
+    );
+    assert_eq!(
+        vec!["3", "2", "1"],
+        values("range = { start = 3, stop = 0, step = -1 }")
+    );
+    assert_eq!(
+        vec!["0", "0.1", "0.2"],
+        values("range = { start = 0, stop = 0.3, step = 0.1 }")
+    );
+    assert_eq!(
+        vec!["0.00", "0.50"],
+        values("range = { start = 0, stop = 1, step = 0.5 }\nformat = \"float\"\nprecision = 2")
+    );
+}
+
+#[test]
+fn test_parameter_spaced_values() {
+    let values = |toml| parameter_values(&parameter(toml)).unwrap();
+
+    assert_eq!(
+        vec!["0", "0.25", "0.5", "0.75", "1"],
+        values("linspace = { start = 0, stop = 1, num = 5 }")
+    );
+    assert_eq!(
+        vec!["0.01", "0.1", "1"],
+        values("logspace = { start = 0.01, stop = 1.0, num = 3 }")
+    );
+    assert_eq!(
+        vec!["1", "10", "100", "1000"],
+        values("logspace = { start = 1, stop = 1000, num = 4 }\nformat = \"integer\"")
+    );
+    assert_eq!(
+        vec!["2.000"],
+        values("linspace = { start = 2, stop = 5, num = 1 }\nprecision = 3")
+    );
+}
+
+#[test]
+fn test_validate_generated_parameters() {
+    let validate = |toml| {
+        validate_parameters(&BTreeMap::from([("x".to_string(), parameter(toml))]))
+            .map_err(|e| format!("{e:#}"))
+    };
+
+    assert!(validate("range = { start = 1, stop = 9, step = 2 }").is_ok());
+    assert!(validate("logspace = { start = 0.01, stop = 1, num = 20 }").is_ok());
+
+    assert!(validate("range = { start = 1, stop = 9, step = 0 }")
+        .unwrap_err()
+        .contains("has no numbers"));
+    assert!(validate("range = { start = 9, stop = 1 }")
+        .unwrap_err()
+        .contains("has no numbers"));
+    assert!(
+        validate("values = [\"1\"]\nrange = { start = 1, stop = 9 }")
+            .unwrap_err()
+            .contains("only one of")
+    );
+    assert!(validate("logspace = { start = 0, stop = 1, num = 3 }")
+        .unwrap_err()
+        .contains("must be positive"));
+    assert!(validate("linspace = { start = 0, stop = 1, num = 0 }")
+        .unwrap_err()
+        .contains("has no numbers"));
+    assert!(validate("values = [\"1\"]\nprecision = 2")
+        .unwrap_err()
+        .contains("format and precision"));
+    assert!(
+        validate("range = { start = 1, stop = 9 }\nformat = \"integer\"\nprecision = 2")
+            .unwrap_err()
+            .contains("do not have a precision")
+    );
+}
+
+#[test]
+fn test_expand_parameters_range() {
+    let inputs = BTreeMap::from([(
+        "first".to_string(),
+        UserInput {
+            file: None,
+            glob: None,
+            fetch: None,
+            group: None,
+            arguments: vec!["--alpha={param.alpha}".to_string()],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
+        },
+    )]);
+    let parameters = BTreeMap::from([(
+        "alpha".to_string(),
+        parameter("linspace = { start = 0.5, stop = 1.5, num = 3 }"),
+    )]);
+
+    let expanded = expand_parameters(inputs, &parameters).unwrap();
+
+    assert_eq!(3, expanded.len());
+    assert_eq!(
+        vec!["--alpha=1.5"],
+        expanded[&format!("first_alpha_2{INTERNAL_PREFIX}{INTERNAL_PARAMETER}")].arguments
+    );
+}
+
+#[test]
+fn test_expand_program_parameters() {
+    let program = |arguments: Vec<&str>, next: Vec<&str>| UserProgram {
+        binary: None,
+        fetch: None,
+        git: None,
+        arguments: arguments.into_iter().map(String::from).collect(),
+        afterscript: None,
+        next: next.into_iter().map(String::from).collect(),
+        resource_limits: None,
+        repetitions: None,
+        seeds: None,
+        termination_signal: None,
+        grace_period: None,
+        warmup: None,
+        env: BTreeMap::new(),
+        inputs: None,
+        exclude_inputs: vec![],
+        groups: None,
+        exclude_groups: vec![],
+    };
+
+    let programs = BTreeMap::from([
+        ("pre".to_string(), program(vec![], vec!["solver"])),
+        (
+            "solver".to_string(),
+            program(vec!["-x", "param|x"], vec!["post"]),
+        ),
+        ("post".to_string(), program(vec!["subparam|y.a"], vec![])),
+    ]);
+    let parameters = BTreeMap::from([
+        ("x".to_string(), parameter("values = [\"3\", \"5\"]")),
+        (
+            "y".to_string(),
+            parameter("sub.a.values = [\"1\"]\nsub.b.values = [\"2\"]"),
+        ),
+    ]);
+
+    let expanded = expand_program_parameters(
+        &programs,
+        &parameters,
+        &Constraints::default(),
+        &Points::new(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        vec!["post[y.a=1]", "pre", "solver[x=3]", "solver[x=5]"],
+        expanded.keys().collect::<Vec<_>>()
+    );
+
+    let (solver, values) = &expanded["solver[x=5]"];
+    assert_eq!(vec!["-x", "5"], solver.arguments);
+    assert_eq!(
+        BTreeMap::from([("x".to_string(), "5".to_string())]),
+        *values
+    );
+    assert_eq!(vec!["post[y.a=1]"], solver.next);
+
+    // a program runs before every variant of the next program
+    assert_eq!(vec!["solver[x=3]", "solver[x=5]"], expanded["pre"].0.next);
+    assert!(expanded["pre"].1.is_empty());
+}
+
+#[test]
+fn test_expand_parameters_with_constraints() {
+    let inputs = BTreeMap::from([(
+        "first".to_string(),
+        UserInput {
+            file: None,
+            glob: None,
+            fetch: None,
+            group: None,
+            arguments: vec!["param|algorithm".to_string(), "param|level".to_string()],
+            env: BTreeMap::new(),
+            metadata: BTreeMap::new(),
+            resource_limits: None,
+        },
+    )]);
+    let parameters = BTreeMap::from([
+        (
+            "algorithm".to_string(),
+            parameter("values = [\"exact\", \"greedy\"]"),
+        ),
+        (
+            "level".to_string(),
+            parameter("range = { start = 0, stop = 3 }"),
+        ),
+    ]);
+    let constraints: Constraints =
+        toml::from_str("exclude = [\"algorithm == 'exact' && level != 0\"]").unwrap();
+
+    let expanded =
+        expand_input_parameters(inputs, &parameters, &constraints, &Points::new()).unwrap();
+
+    assert_eq!(4, expanded.len());
+    assert_eq!(
+        vec!["exact", "0"],
+        expanded[&format!("first_algorithm_0_level_0{INTERNAL_PREFIX}{INTERNAL_PARAMETER}")]
+            .0
+            .arguments
+    );
+    assert!(!expanded.contains_key(&format!(
+        "first_algorithm_0_level_1{INTERNAL_PREFIX}{INTERNAL_PARAMETER}"
+    )));
+    assert_eq!(
+        BTreeMap::from([
+            ("algorithm".to_string(), "greedy".to_string()),
+            ("level".to_string(), "2".to_string())
+        ]),
+        expanded[&format!("first_algorithm_1_level_2{INTERNAL_PREFIX}{INTERNAL_PARAMETER}")].1
+    );
+}
+
+#[test]
+fn test_expand_program_parameters_with_constraints() {
+    let program = |arguments: Vec<&str>, next: Vec<&str>| UserProgram {
+        binary: None,
+        fetch: None,
+        git: None,
+        arguments: arguments.into_iter().map(String::from).collect(),
+        afterscript: None,
+        next: next.into_iter().map(String::from).collect(),
+        resource_limits: None,
+        repetitions: None,
+        seeds: None,
+        termination_signal: None,
+        grace_period: None,
+        warmup: None,
+        env: BTreeMap::new(),
+        inputs: None,
+        exclude_inputs: vec![],
+        groups: None,
+        exclude_groups: vec![],
+    };
+
+    let programs = BTreeMap::from([
+        ("pre".to_string(), program(vec![], vec!["solver"])),
+        ("solver".to_string(), program(vec!["param|x"], vec![])),
+    ]);
+    let parameters = BTreeMap::from([("x".to_string(), parameter("values = [\"3\", \"5\"]"))]);
+    let constraints: Constraints = toml::from_str("require = [\"x < 5\"]").unwrap();
+
+    let expanded =
+        expand_program_parameters(&programs, &parameters, &constraints, &Points::new()).unwrap();
+
+    assert_eq!(
+        vec!["pre", "solver[x=3]"],
+        expanded.keys().collect::<Vec<_>>()
+    );
+    assert_eq!(vec!["solver[x=3]"], expanded["pre"].0.next);
+
+    // a program without any variant left is not run after another one
+    let constraints: Constraints = toml::from_str("require = [\"x > 5\"]").unwrap();
+    let expanded =
+        expand_program_parameters(&programs, &parameters, &constraints, &Points::new()).unwrap();
+
+    assert_eq!(vec!["pre"], expanded.keys().collect::<Vec<_>>());
+    assert!(expanded["pre"].0.next.is_empty());
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+/// A lookup that knows `a` and `b`.
+fn lookup(name: &str) -> Result<Option<String>> {
+    Ok(match name {
+        "a" => Some("1".to_string()),
+        "b" => Some("{x}".to_string()),
+        _ => None,
+    })
+}
+
+#[test]
+fn test_placeholders_are_found() {
+    assert_eq!(
+        vec!["input", "param.x", "seed"],
+        placeholders("--in={input} {param.x}{seed}")
+    );
+    assert!(placeholders("{{input}} {\"a\": 1} {1} {}").is_empty());
+}
+
+#[test]
+fn test_fill() {
+    assert_eq!("--a=1", fill("--a={a}", lookup).unwrap());
+    assert_eq!("{a} {x}", fill("{{a}} {b}", lookup).unwrap());
+    assert_eq!("{\"k\": 1}", fill("{\"k\": 1}", lookup).unwrap());
+    assert_eq!("ü{1}", fill("ü{1}", lookup).unwrap());
+    assert!(fill("{c}", lookup).is_err());
+}
+
+#[test]
+fn test_fill_some_keeps_the_rest() {
+    let partial = fill_some("{a}-{c}-{{a}}-{b}", lookup).unwrap();
+
+    assert_eq!("1-{c}-{{a}}-{{x}}", partial);
+    assert_eq!(
+        "1-3-{a}-{x}",
+        fill(&partial, |name| Ok((name == "c").then(|| "3".to_string()))).unwrap()
+    );
+}
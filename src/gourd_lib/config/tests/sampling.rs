@@ -0,0 +1,145 @@
+use super::*;
+
+/// A sampling with this method and number of samples.
+fn sampling(method: SamplingMethod, samples: usize) -> Sampling {
+    Sampling {
+        method,
+        samples,
+        seed: 42,
+    }
+}
+
+#[test]
+fn test_grid() {
+    assert_eq!(
+        vec![
+            vec![0, 0],
+            vec![0, 1],
+            vec![1, 0],
+            vec![1, 1],
+            vec![2, 0],
+            vec![2, 1]
+        ],
+        grid(&[3, 2])
+    );
+    assert_eq!(vec![Vec::<usize>::new()], grid(&[]));
+    assert!(grid(&[3, 0]).is_empty());
+}
+
+/// Every point is allowed.
+fn all(_: usize, _: &[usize]) -> bool {
+    true
+}
+
+/// The points of a sample of one block, in increasing order.
+fn points(chosen: BTreeSet<(usize, Vec<usize>)>) -> Vec<Vec<usize>> {
+    assert!(chosen.iter().all(|(block, _)| *block == 0));
+
+    chosen.into_iter().map(|(_, point)| point).collect()
+}
+
+#[test]
+fn test_small_spaces_are_not_sampled() {
+    for method in [SamplingMethod::Random, SamplingMethod::LatinHypercube] {
+        let chosen = sampling(method, 6).choose(&[vec![2, 3]], all).unwrap();
+
+        assert_eq!(grid(&[2, 3]), points(chosen));
+    }
+}
+
+#[test]
+fn test_random_sampling() {
+    let random = sampling(SamplingMethod::Random, 50);
+    let blocks = [vec![10, 10, 10]];
+    let chosen = random.choose(&blocks, all).unwrap();
+
+    assert_eq!(50, chosen.len());
+    assert!(chosen
+        .iter()
+        .all(|(_, point)| point.len() == 3 && point.iter().all(|i| *i < 10)));
+
+    // the same seed gives the same sample
+    assert_eq!(chosen, random.choose(&blocks, all).unwrap());
+    assert_ne!(
+        chosen,
+        Sampling { seed: 7, ..random }.choose(&blocks, all).unwrap()
+    );
+}
+
+#[test]
+fn test_latin_hypercube_sampling() {
+    let chosen = points(
+        sampling(SamplingMethod::LatinHypercube, 10)
+            .choose(&[vec![10, 20]], all)
+            .unwrap(),
+    );
+
+    assert_eq!(10, chosen.len());
+
+    // every value of the first parameter is used exactly once
+    let mut first: Vec<usize> = chosen.iter().map(|point| point[0]).collect();
+    first.sort();
+    assert_eq!((0..10).collect::<Vec<_>>(), first);
+
+    // the second parameter has one value in every pair of its values
+    let mut second: Vec<usize> = chosen.iter().map(|point| point[1] / 2).collect();
+    second.sort();
+    assert_eq!((0..10).collect::<Vec<_>>(), second);
+}
+
+#[test]
+fn test_latin_hypercube_draws_no_combination_twice() {
+    // there are fewer values than samples, so the strata share values
+    let chosen = sampling(SamplingMethod::LatinHypercube, 8)
+        .choose(&[vec![3, 3]], all)
+        .unwrap();
+
+    assert_eq!(8, chosen.len());
+}
+
+#[test]
+fn test_sampling_leaves_out_points_that_are_not_allowed() {
+    for method in [SamplingMethod::Random, SamplingMethod::LatinHypercube] {
+        let chosen = sampling(method, 20)
+            .choose(&[vec![10, 10]], |_, point| point[0] != point[1])
+            .unwrap();
+
+        assert_eq!(20, chosen.len());
+        assert!(chosen.iter().all(|(_, point)| point[0] != point[1]));
+    }
+}
+
+#[test]
+fn test_sampling_large_spaces() {
+    // a space far too large to list
+    let blocks = [vec![2], vec![1000; 6]];
+
+    for method in [SamplingMethod::Random, SamplingMethod::LatinHypercube] {
+        let chosen = sampling(method, 100).choose(&blocks, all).unwrap();
+
+        assert_eq!(100, chosen.len());
+        assert!(chosen
+            .iter()
+            .all(|(block, point)| point.len() == blocks[*block].len()));
+    }
+
+    assert!(sampling(SamplingMethod::Random, 100)
+        .choose(&[vec![1000; 8]], all)
+        .is_err());
+}
+
+#[test]
+fn test_sampling_needs_samples() {
+    assert!(sampling(SamplingMethod::Random, 0).validate().is_err());
+    assert!(sampling(SamplingMethod::Random, 1).validate().is_ok());
+
+    let parsed: Sampling = toml::from_str("method = \"latin_hypercube\"\nsamples = 3").unwrap();
+    assert_eq!(
+        Sampling {
+            method: SamplingMethod::LatinHypercube,
+            samples: 3,
+            seed: 0
+        },
+        parsed
+    );
+}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use super::*;
+use crate::experiment::Metadata;
+
+/// An input of a group, made from the input `origin` of the configuration.
+fn input(group: Option<&str>, origin: &str) -> InternalInput {
+    InternalInput {
+        input: None,
+        arguments: vec![],
+        env: BTreeMap::new(),
+        metadata: Metadata {
+            glob_from: None,
+            is_fetched: false,
+            group: group.map(String::from),
+            parameters: BTreeMap::new(),
+            values: BTreeMap::new(),
+            origin: Some(origin.to_string()),
+        },
+        limit_overrides: Default::default(),
+    }
+}
+
+/// A list of names.
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn test_input_selector() {
+    let small = input(Some("easy"), "graphs");
+    let large = input(Some("hard"), "large_i_schema");
+    let plain = input(None, "plain");
+
+    assert!(InputSelector::default().is_empty());
+    assert!(InputSelector::default().selects("anything", &plain));
+
+    // names match the input and the input it was made from
+    let selector = InputSelector {
+        inputs: Some(names(&["graphs", "large*"])),
+        ..Default::default()
+    };
+    assert!(selector.selects("graphs_i_a", &small));
+    assert!(selector.selects("large_i_schema", &large));
+    assert!(!selector.selects("plain", &plain));
+
+    let selector = InputSelector {
+        exclude_inputs: names(&["*_i_a"]),
+        exclude_groups: names(&["hard"]),
+        ..Default::default()
+    };
+    assert!(!selector.selects("graphs_i_a", &small));
+    assert!(selector.selects("graphs_i_b", &small));
+    assert!(!selector.selects("large_i_schema", &large));
+    assert!(selector.selects("plain", &plain));
+
+    // an input without a group is not in any group
+    let selector = InputSelector {
+        groups: Some(names(&["easy"])),
+        ..Default::default()
+    };
+    assert!(selector.selects("graphs_i_a", &small));
+    assert!(!selector.selects("plain", &plain));
+
+    let selector = InputSelector {
+        inputs: Some(names(&["[oops"])),
+        ..Default::default()
+    };
+    assert!(selector.validate("solver").is_err());
+}
+
+#[test]
+fn test_schema_input_names() {
+    let instance = input(None, "instance_i_schema");
+
+    // inputs of a schema are selected by their name in the schema
+    let selector = InputSelector {
+        inputs: Some(names(&["instance"])),
+        ..Default::default()
+    };
+    assert!(selector.selects("instance_i_schema", &instance));
+
+    let selector = InputSelector {
+        exclude_inputs: names(&["inst*"]),
+        ..Default::default()
+    };
+    assert!(!selector.selects("instance_i_schema", &instance));
+
+    let selector = InputSelector {
+        inputs: Some(names(&["instance_i"])),
+        ..Default::default()
+    };
+    assert!(!selector.selects("instance_i_schema", &instance));
+
+    // and so are the inputs made from them
+    let selector = InputSelector {
+        inputs: Some(names(&["instance"])),
+        ..Default::default()
+    };
+    assert!(selector.selects("instance_i_schema_x_0_i_parameter", &instance));
+}
+
+#[test]
+fn test_pairing() {
+    let pairing = Pairing {
+        program: "exact".to_string(),
+        inputs: names(&["small*"]),
+        groups: names(&["easy"]),
+    };
+    let program = |name: &str, parameters: &[(&str, &str)]| InternalProgram {
+        name: name.to_string(),
+        parameters: parameters
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+
+    assert!(pairing.names(&program("exact", &[])));
+    assert!(pairing.names(&program("exact[k=1]", &[("k", "1")])));
+    assert!(!pairing.names(&program("exact_fast", &[])));
+    assert!(!pairing.names(&program("heuristic", &[])));
+
+    let exact = program("exact", &[]);
+    assert!(pairing.pairs(&exact, "small_1", &input(None, "small_1")));
+    assert!(pairing.pairs(&exact, "x", &input(Some("easy"), "x")));
+    assert!(!pairing.pairs(&exact, "x", &input(Some("hard"), "x")));
+    assert!(!pairing.pairs(
+        &program("heuristic", &[]),
+        "small_1",
+        &input(None, "small_1")
+    ));
+
+    assert!(pairing.validate().is_ok());
+    assert!(Pairing {
+        inputs: vec![],
+        groups: vec![],
+        ..pairing
+    }
+    .validate()
+    .is_err());
+}
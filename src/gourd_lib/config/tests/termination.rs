@@ -0,0 +1,48 @@
+use super::*;
+
+#[test]
+fn test_normalize_signal() {
+    assert_eq!("USR1", normalize_signal("SIGUSR1").unwrap());
+    assert_eq!("USR1", normalize_signal("usr1").unwrap());
+    assert_eq!("TERM", normalize_signal(" sigterm ").unwrap());
+    assert!(normalize_signal("KILL").is_err());
+    assert!(normalize_signal("SIGFOO").is_err());
+}
+
+#[test]
+fn test_graceful_termination_defaults() {
+    assert_eq!(None, GracefulTermination::new(None, None).unwrap());
+
+    assert_eq!(
+        Some(GracefulTermination {
+            signal: "TERM".to_string(),
+            grace_period: Duration::from_secs(10),
+        }),
+        GracefulTermination::new(None, Some(Duration::from_secs(10))).unwrap()
+    );
+
+    assert_eq!(
+        Some(GracefulTermination {
+            signal: "USR1".to_string(),
+            grace_period: WRAPPER_KILL_GRACE_PERIOD,
+        }),
+        GracefulTermination::new(Some("SIGUSR1"), None).unwrap()
+    );
+}
+
+#[test]
+fn test_signal_after() {
+    let termination = GracefulTermination {
+        signal: "TERM".to_string(),
+        grace_period: Duration::from_secs(10),
+    };
+
+    assert_eq!(
+        Duration::from_secs(50),
+        termination.signal_after(Duration::from_secs(60))
+    );
+    assert_eq!(
+        Duration::ZERO,
+        termination.signal_after(Duration::from_secs(5))
+    );
+}
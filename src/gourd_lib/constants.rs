@@ -33,6 +33,23 @@ pub const PROGRAM_RESOURCES_DEFAULT: fn() -> Option<ResourceLimits> = || None;
 /// The default value of warning on label overlaps.
 pub const LABEL_OVERLAP_DEFAULT: fn() -> bool = || false;
 
+/// The default number of measured runs per program-input pair.
+pub const REPETITIONS_DEFAULT: fn() -> usize = || 1;
+
+/// The default difference between the numbers of a parameter `range`.
+pub const RANGE_STEP_DEFAULT: fn() -> f64 = || 1.0;
+
+/// The number of significant digits that generated parameter values keep
+/// when they are written without a `precision`.
+pub const PARAMETER_SIGNIFICANT_DIGITS: usize = 12;
+
+/// The environment variables kept for runs when `clean_env` is enabled.
+pub const ENV_WHITELIST_DEFAULT: fn() -> Vec<String> = || {
+    ["PATH", "HOME", "USER", "LOGNAME", "SHELL", "TMPDIR", "TERM"]
+        .map(String::from)
+        .to_vec()
+};
+
 /// The default arguments for an input.
 pub const EMPTY_ARGS: fn() -> Vec<String> = Vec::new;
 
@@ -66,6 +83,24 @@ pub const INTERNAL_PREFIX: &str = "_i_";
 /// The amount between refreshes of the status screen, in ms.
 pub const STATUS_REFRESH_PERIOD: Duration = Duration::from_millis(500);
 
+/// The environment variable that holds the path where programs can write
+/// their own metrics.
+pub const CUSTOM_METRICS_ENV: &str = "GOURD_METRICS_FILE";
+
+/// The environment variable that holds the seed of a run.
+pub const SEED_ENV: &str = "GOURD_SEED";
+
+/// The exit code of the wrapper when it failed, but recorded the failure in
+/// the metrics file of the run.
+pub const WRAPPER_FAILED_EXIT_CODE: i32 = 3;
+
+/// How often the wrapper checks a running program against its resource limits.
+pub const WRAPPER_POLL_PERIOD: Duration = Duration::from_millis(50);
+
+/// How long the wrapper waits after sending `SIGTERM` to a program that
+/// exceeded its limits before killing it with `SIGKILL`.
+pub const WRAPPER_KILL_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
 /// Create a style with a defined foreground color.
 pub const fn style_from_fg(color: AnsiColor) -> Style {
     Style::new().fg_color(Some(Color::Ansi(color)))
@@ -157,3 +192,7 @@ pub const SCHEDULE_BAR_WIDTH: usize = 50;
 
 /// The size of the analysis output plots, in pixels.
 pub const PLOT_SIZE: (u32, u32) = (1920, 1080);
+
+/// How many times a sample is drawn again if it was drawn before or its
+/// combination is excluded.
+pub const SAMPLE_ATTEMPTS: usize = 64;
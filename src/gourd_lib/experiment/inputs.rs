@@ -5,10 +5,12 @@ use anyhow::Context;
 use anyhow::Result;
 
 use crate::bailc;
+use crate::config::constraints::Constraints;
 use crate::config::maps::canon_path;
 use crate::config::maps::expand_argument_globs;
-use crate::config::parameters::expand_parameters;
+use crate::config::parameters::expand_input_parameters;
 use crate::config::parameters::validate_parameters;
+use crate::config::parameters::Points;
 use crate::config::Parameter;
 use crate::config::UserInput;
 use crate::experiment::FieldRef;
@@ -18,25 +20,58 @@ use crate::file_system::FileOperations;
 
 /// Convert a [`UserInput`] to a list of [`InternalInput`]s, expanding globs and
 /// fetching remote resources.
+///
+/// The combinations of parameter values that the `constraints` exclude are
+/// left out, and the inputs that have `points` are only built at those.
+/// Every input remembers the name of the [`UserInput`] it was made from.
 pub fn expand_inputs(
     inp: &BTreeMap<String, UserInput>,
     parameters: &Option<BTreeMap<String, Parameter>>,
+    constraints: &Constraints,
+    points: &Points,
+    fs: &impl FileOperations,
+) -> Result<BTreeMap<FieldRef, InternalInput>> {
+    let mut out = BTreeMap::new();
+
+    for (origin, user) in inp {
+        let single = BTreeMap::from([(origin.clone(), user.clone())]);
+
+        for (name, mut input) in expand_user_inputs(&single, parameters, constraints, points, fs)? {
+            input.metadata.origin = Some(origin.clone());
+            out.insert(name, input);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Expand the globs, parameters and remote resources of user inputs.
+fn expand_user_inputs(
+    inp: &BTreeMap<String, UserInput>,
+    parameters: &Option<BTreeMap<String, Parameter>>,
+    constraints: &Constraints,
+    points: &Points,
     fs: &impl FileOperations,
 ) -> Result<BTreeMap<FieldRef, InternalInput>> {
-    let mut initial = inp.clone();
     let mut out = BTreeMap::new();
 
     // Expand globs in arguments.
-    initial = expand_argument_globs(&initial, fs)?;
+    let initial = expand_argument_globs(inp, fs)?;
 
     // Expand parameters.
-    if let Some(params) = parameters {
-        validate_parameters(params)?;
-        initial = expand_parameters(initial, params)?;
-    }
+    let variants = match parameters {
+        Some(params) => {
+            validate_parameters(params)?;
+            expand_input_parameters(initial, params, constraints, points)?
+        }
+        None => initial
+            .into_iter()
+            .map(|(name, user)| (name, (user, BTreeMap::new())))
+            .collect(),
+    };
 
     // Expand file input
-    for (name, user) in initial {
+    for (name, (user, parameters)) in variants {
         match (user.file, user.glob, user.fetch) {
             (Some(f), None, None) => {
                 out.insert(
@@ -48,7 +83,12 @@ pub fn expand_inputs(
                             glob_from: None,
                             is_fetched: false,
                             group: user.group,
+                            parameters,
+                            values: user.metadata,
+                            origin: None,
                         },
+                        env: user.env.clone(),
+                        limit_overrides: user.resource_limits.clone().unwrap_or_default(),
                     },
                 );
             }
@@ -67,7 +107,12 @@ pub fn expand_inputs(
                                     glob_from: Some(name.clone()),
                                     is_fetched: false,
                                     group: user.group.clone(),
+                                    parameters: parameters.clone(),
+                                    values: user.metadata.clone(),
+                                    origin: None,
                                 },
+                                env: user.env.clone(),
+                                limit_overrides: user.resource_limits.clone().unwrap_or_default(),
                             },
                         );
                     }
@@ -86,7 +131,12 @@ pub fn expand_inputs(
                             glob_from: None,
                             is_fetched: true,
                             group: user.group,
+                            parameters,
+                            values: user.metadata,
+                            origin: None,
                         },
+                        env: user.env.clone(),
+                        limit_overrides: user.resource_limits.clone().unwrap_or_default(),
                     },
                 );
             }
@@ -105,7 +155,12 @@ pub fn expand_inputs(
                             glob_from: None,
                             is_fetched: false,
                             group: user.group,
+                            parameters,
+                            values: user.metadata,
+                            origin: None,
                         },
+                        env: user.env.clone(),
+                        limit_overrides: user.resource_limits.clone().unwrap_or_default(),
                     },
                 );
             }
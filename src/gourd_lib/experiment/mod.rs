@@ -7,14 +7,34 @@ use anyhow::Context;
 use anyhow::Result;
 use chrono::DateTime;
 use chrono::Local;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::config::constraints::Constraints;
+use crate::config::extract::Extraction;
+use crate::config::limits::limit_variables;
+use crate::config::limits::EnforcedLimits;
+use crate::config::limits::LimitOverrides;
+use crate::config::placeholders::fill;
+use crate::config::placeholders::placeholders;
+use crate::config::placeholders::INPUT_NAME_PLACEHOLDER;
+use crate::config::placeholders::INPUT_PLACEHOLDER;
+use crate::config::placeholders::RUN_ID_PLACEHOLDER;
+use crate::config::placeholders::SEED_PLACEHOLDER;
+use crate::config::placeholders::WORK_DIR_PLACEHOLDER;
+use crate::config::sampling::Sampling;
+use crate::config::selection::InputSelector;
+use crate::config::selection::Pairing;
 use crate::config::slurm::ResourceLimits;
 use crate::config::slurm::SlurmConfig;
+use crate::config::termination::GracefulTermination;
 use crate::config::Label;
+use crate::config::MeasurementOptions;
+use crate::constants::REPETITIONS_DEFAULT;
 use crate::ctx;
 use crate::file_system::FileOperations;
+use crate::measurement::MetricValue;
 
 /// Dealing with [`crate::config::UserInput`]s and [`InternalInput`]s
 pub mod inputs;
@@ -27,7 +47,7 @@ pub mod programs;
 pub type FieldRef = String;
 
 /// The internal representation of a [`crate::config::UserInput`]
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InternalInput {
     /// A file to pass the contents into `stdin`
     pub input: Option<PathBuf>,
@@ -35,13 +55,21 @@ pub struct InternalInput {
     /// Command line arguments to be passed to the executable
     pub arguments: Vec<String>,
 
+    /// Environment variables to set for the runs on this input.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
     /// Additional data for this input
     pub metadata: Metadata,
+
+    /// The resource limits of the runs on this input.
+    #[serde(default)]
+    pub limit_overrides: LimitOverrides,
 }
 
 /// Internally used metadata for inputs/programs
 #[allow(dead_code)]
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Metadata {
     /// Which input this was generated from.
     pub glob_from: Option<String>,
@@ -51,10 +79,22 @@ pub struct Metadata {
 
     /// The group this item belongs to.
     pub group: Option<String>,
+
+    /// The values of the parameters used by this input.
+    #[serde(default)]
+    pub parameters: BTreeMap<String, String>,
+
+    /// The metadata that the user gave this input.
+    #[serde(default)]
+    pub values: BTreeMap<String, MetricValue>,
+
+    /// The name of the input in the configuration that this was made from.
+    #[serde(default)]
+    pub origin: Option<String>,
 }
 
 /// The internal representation of a [`crate::config::UserProgram`]
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default, JsonSchema)]
 pub struct InternalProgram {
     /// The name given to this program by the user.
     pub name: String,
@@ -68,10 +108,46 @@ pub struct InternalProgram {
     /// The limits to be applied on executions of this program
     pub limits: ResourceLimits,
 
+    /// The limits of this program that the user set, rather than leaving
+    /// the defaults.
+    #[serde(default)]
+    pub enforced_limits: EnforcedLimits,
+
+    /// How the program is asked to stop before its time limit, if at all.
+    #[serde(default)]
+    pub termination: Option<GracefulTermination>,
+
+    /// How many measured runs to create for every input of this program.
+    #[serde(default = "REPETITIONS_DEFAULT")]
+    pub repetitions: usize,
+
+    /// The seeds of the measured runs, one for every repetition, if the
+    /// program has seeds.
+    #[serde(default)]
+    pub seeds: Option<Vec<u64>>,
+
+    /// How many times the wrapper executes this program and discards it
+    /// before every measured run.
+    #[serde(default)]
+    pub warmup: usize,
+
+    /// Environment variables to set for the runs of this program.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+
     /// The command line arguments to be passed to all executions of this
     /// program
     pub arguments: Vec<String>,
 
+    /// The values of the parameters in the arguments, if this program is a
+    /// variant of a program with parameters.
+    #[serde(default)]
+    pub parameters: BTreeMap<String, String>,
+
+    /// The inputs that this program runs on.
+    #[serde(default)]
+    pub selector: InputSelector,
+
     /// This program runs on the output of our program,
     /// a reference to the other program's name.
     pub next: Vec<usize>,
@@ -85,7 +161,7 @@ pub struct InternalProgram {
 ///
 /// `args`: [`Vec`]<[`String`]> - Command line arguments for this binary
 /// execution.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct RunInput {
     /// A file whose contents to be passed into the program's `stdin`
     pub file: Option<PathBuf>,
@@ -98,7 +174,7 @@ pub struct RunInput {
 }
 
 /// Describes a matching between an algorithm and an input.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Run {
     /// The unique name of the program to run.
@@ -128,6 +204,11 @@ pub struct Run {
     /// Resource limits applied to this run
     pub limits: ResourceLimits,
 
+    /// The limits that the wrapper stops the run for exceeding, which are
+    /// only the limits that the user set.
+    #[serde(default)]
+    pub enforced_limits: EnforcedLimits,
+
     /// If this job has been rerun, a reference to the new one.
     pub rerun: Option<usize>,
 
@@ -139,10 +220,47 @@ pub struct Run {
 
     /// The group this run belongs to.
     pub group: Option<String>,
+
+    /// The index of this run among the repetitions of the same program on the
+    /// same input.
+    #[serde(default)]
+    pub replicate: usize,
+
+    /// The seed of this run, if its program has seeds.
+    #[serde(default)]
+    pub seed: Option<u64>,
+
+    /// The environment variables set for this run, on top of the inherited
+    /// ones.
+    ///
+    /// If the experiment has a clean environment, these are all the variables
+    /// of the run, including the whitelisted ones as they were when the run
+    /// was created.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+impl Run {
+    /// The path to the time series of resource usage, next to the metrics.
+    pub fn samples_path(&self) -> PathBuf {
+        self.metrics_path.with_file_name("samples.csv")
+    }
+
+    /// The path where the program can write its own metrics, next to the
+    /// metrics.
+    pub fn custom_metrics_path(&self) -> PathBuf {
+        self.metrics_path.with_file_name("custom_metrics")
+    }
+
+    /// The seed that is passed to the program, which is the index of the
+    /// repetition if the program has no seeds.
+    pub fn seed_or_replicate(&self) -> u64 {
+        self.seed.unwrap_or(self.replicate as u64)
+    }
 }
 
 /// An enum to distinguish the run context.
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub enum Environment {
     /// Local execution.
@@ -153,7 +271,7 @@ pub enum Environment {
 }
 
 /// Describes one experiment.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Experiment {
     /// The ID of this experiment.
@@ -189,9 +307,56 @@ pub struct Experiment {
     /// How many threads to use for local execution
     pub num_threads: usize,
 
+    /// How the wrapper measures the runs.
+    #[serde(default)]
+    pub measurement: MeasurementOptions,
+
+    /// Environment variables set for all runs.
+    #[serde(default)]
+    pub env_vars: BTreeMap<String, String>,
+
+    /// Whether runs start with only the whitelisted environment variables.
+    #[serde(default)]
+    pub clean_env: bool,
+
+    /// The environment variables passed on to runs when `clean_env` is set.
+    #[serde(default)]
+    pub env_whitelist: Vec<String>,
+
     /// Labels used in this experiment.
     pub labels: BTreeMap<String, Label>,
 
+    /// The values that are read from the output of every run, by name.
+    #[serde(default)]
+    pub extractions: BTreeMap<String, Extraction>,
+
+    /// The combinations of parameter values that were left out.
+    #[serde(default)]
+    pub constraints: Constraints,
+
+    /// How the combinations of parameter values were sampled, the sampled
+    /// values are kept with every program and input.
+    #[serde(default)]
+    pub sampling: Option<Sampling>,
+
+    /// The programs and the inputs that they run on, if they are listed.
+    #[serde(default)]
+    pub pairings: Vec<Pairing>,
+
+    /// The resource limits of the runs on the inputs of a group, by group.
+    #[serde(default)]
+    pub group_limits: BTreeMap<String, LimitOverrides>,
+
+    /// The resource limits of the runs with a value of a parameter, by
+    /// parameter and value.
+    #[serde(default)]
+    pub parameter_limits: BTreeMap<String, BTreeMap<String, LimitOverrides>>,
+
+    /// The environment variables that were resolved in the configuration,
+    /// with the values they had when the experiment was created.
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+
     /// If running on a SLURM cluster, the job configurations.
     pub slurm: Option<SlurmConfig>,
 
@@ -262,6 +427,145 @@ impl Experiment {
             .with_context(ctx!("",;"",))
     }
 
+    /// The arguments that a run is executed with: those of its program
+    /// followed by those of its input, with the placeholders filled in.
+    pub fn run_arguments(&self, run_id: usize) -> Result<Vec<String>> {
+        let run = &self.runs[run_id];
+        let program = self.get_program(run)?;
+
+        program
+            .arguments
+            .iter()
+            .chain(&run.input.args)
+            .map(|arg| {
+                fill(arg, |name| self.placeholder_value(run_id, name)).with_context(ctx!(
+                    "For run {run_id} of program {:?}", program.name;
+                    "",
+                ))
+            })
+            .collect()
+    }
+
+    /// Whether the input file of a run is passed as an argument through
+    /// `{input}`, instead of on `stdin`.
+    pub fn input_in_arguments(&self, run_id: usize) -> Result<bool> {
+        let run = &self.runs[run_id];
+
+        Ok(self
+            .get_program(run)?
+            .arguments
+            .iter()
+            .chain(&run.input.args)
+            .any(|arg| placeholders(arg).contains(&INPUT_PLACEHOLDER)))
+    }
+
+    /// Whether a program runs on the input called `name`, as chosen by its
+    /// selector and the pairings.
+    pub fn pairs(&self, program: &InternalProgram, name: &str, input: &InternalInput) -> bool {
+        program.selector.selects(name, input)
+            && (self.pairings.is_empty()
+                || self
+                    .pairings
+                    .iter()
+                    .any(|pairing| pairing.pairs(program, name, input)))
+    }
+
+    /// The resource limits of a run of `program` on the input called `input`,
+    /// which replace the `limits` of the program, and the limits among them
+    /// that are set for the input.
+    ///
+    /// The limits of the parameter values override those of the input, which
+    /// override those of its group. A run without an input keeps `limits`.
+    pub fn run_limits(
+        &self,
+        program: usize,
+        input: Option<&FieldRef>,
+        limits: ResourceLimits,
+    ) -> Result<(ResourceLimits, EnforcedLimits)> {
+        let (Some(program), Some(input)) = (
+            self.programs.get(program),
+            input.and_then(|name| self.inputs.get(name)),
+        ) else {
+            return Ok((limits, EnforcedLimits::default()));
+        };
+
+        let parameters = || program.parameters.iter().chain(&input.metadata.parameters);
+
+        let mut overrides = LimitOverrides::default();
+
+        for (name, value) in parameters() {
+            if let Some(limits) = self
+                .parameter_limits
+                .get(name)
+                .and_then(|values| values.get(value))
+            {
+                overrides = overrides.or(limits);
+            }
+        }
+
+        overrides = overrides.or(&input.limit_overrides);
+
+        if let Some(limits) = input
+            .metadata
+            .group
+            .as_ref()
+            .and_then(|group| self.group_limits.get(group))
+        {
+            overrides = overrides.or(limits);
+        }
+
+        let limits = overrides.apply(
+            limits,
+            &limit_variables(&input.metadata.values, parameters()),
+        )?;
+
+        Ok((limits, overrides.enforced()))
+    }
+
+    /// The name of the input of a run, postprocessing runs have the input of
+    /// the run they follow.
+    pub fn input_name(&self, run_id: usize) -> Option<&FieldRef> {
+        let mut origin = &self.runs[run_id];
+
+        while let (None, Some(parent)) = (&origin.generated_from_input, origin.parent) {
+            origin = &self.runs[parent];
+        }
+
+        origin.generated_from_input.as_ref()
+    }
+
+    /// The metadata of the input of a run, if it has an input.
+    pub fn input_metadata(&self, run_id: usize) -> Option<&BTreeMap<String, MetricValue>> {
+        self.inputs
+            .get(self.input_name(run_id)?)
+            .map(|input| &input.metadata.values)
+    }
+
+    /// The value of a placeholder in the arguments of a run, if it is one
+    /// that belongs to the run.
+    fn placeholder_value(&self, run_id: usize, name: &str) -> Result<Option<String>> {
+        let run = &self.runs[run_id];
+
+        Ok(match name {
+            INPUT_PLACEHOLDER => {
+                let file = run.input.file.as_ref().with_context(ctx!(
+                    "The run has no input file for {{{INPUT_PLACEHOLDER}}}", ;
+                    "Give the input a `file`, `glob` or `fetch`",
+                ))?;
+
+                Some(file.to_string_lossy().to_string())
+            }
+            INPUT_NAME_PLACEHOLDER => Some(self.input_name(run_id).cloned().with_context(ctx!(
+                "The run has no input for {{{INPUT_NAME_PLACEHOLDER}}}", ;
+                "",
+            ))?),
+            RUN_ID_PLACEHOLDER => Some(run_id.to_string()),
+            WORK_DIR_PLACEHOLDER => Some(run.work_dir.to_string_lossy().to_string()),
+            SEED_PLACEHOLDER => Some(run.seed_or_replicate().to_string()),
+            _ => None,
+        })
+    }
+
     /// Get the slurm stdout file path for a given run.
     pub fn slurm_out(&self, slurm_id: &str) -> Option<PathBuf> {
         self.slurm
@@ -5,23 +5,75 @@ use anyhow::Result;
 
 use crate::bailc;
 use crate::config::fetching::fetch_git;
+use crate::config::limits::EnforcedLimits;
 use crate::config::maps::canon_path;
+use crate::config::parameters::expand_program_parameters;
+use crate::config::parameters::validate_parameters;
+use crate::config::parameters::Points;
+use crate::config::termination::GracefulTermination;
 use crate::config::Config;
+use crate::config::Seeds;
 use crate::config::UserProgram;
+use crate::constants::REPETITIONS_DEFAULT;
+use crate::ctx;
 use crate::experiment::InternalProgram;
 use crate::file_system::FileOperations;
 
 /// Convert a [`UserProgram`] to a list of [`InternalProgram`]s,
 /// expanding globs and fetching remote resources.
+///
+/// The programs that have `points` are only built at those points of their
+/// parameters.
 pub fn expand_programs(
     prog: &BTreeMap<String, UserProgram>,
     conf: &Config,
+    points: &Points,
     fs: &impl FileOperations,
 ) -> Result<Vec<InternalProgram>> {
     let mut out = Vec::new();
     let mut mapper = BTreeMap::new();
 
-    for (name, user) in prog {
+    if conf.seeds.is_some() && conf.repetitions.is_some() {
+        bailc!(
+            "The experiment has both `seeds` and `repetitions`", ;
+            "A run is created for every seed", ;
+            "Remove `repetitions`, the number of seeds is the number of repetitions",
+        );
+    }
+
+    let constraints = conf.constraints.clone().unwrap_or_default();
+    constraints.validate(&conf.parameters)?;
+
+    if let Some(sampling) = &conf.sampling {
+        sampling.validate()?;
+    }
+
+    for pairing in conf.pairings.iter().flatten() {
+        pairing.validate()?;
+
+        if !conf.programs.contains_key(&pairing.program) {
+            bailc!(
+                "Unknown program {} in a pairing", pairing.program;
+                "A pairing lists the inputs that a program runs on", ;
+                "Check the spelling of the program",
+            );
+        }
+    }
+
+    // Every combination of the parameter values in the arguments of a program
+    // is a variant of it.
+    let variants = match &conf.parameters {
+        Some(parameters) => {
+            validate_parameters(parameters)?;
+            expand_program_parameters(prog, parameters, &constraints, points)?
+        }
+        None => prog
+            .iter()
+            .map(|(name, user)| (name.clone(), (user.clone(), BTreeMap::new())))
+            .collect(),
+    };
+
+    for (name, (user, parameters)) in &variants {
         let file = canon_path(
             &match (&user.binary, &user.fetch, &user.git) {
                 (Some(f), None, None) => f.clone(),
@@ -41,9 +93,68 @@ pub fn expand_programs(
         let limits = user
             .resource_limits
             .unwrap_or(conf.resource_limits.unwrap_or_default());
+        // A program sets all of its limits at once.
+        let enforced_limits = if user.resource_limits.is_some() || conf.resource_limits.is_some() {
+            EnforcedLimits::ALL
+        } else {
+            EnforcedLimits::default()
+        };
+
+        let termination =
+            GracefulTermination::new(user.termination_signal.as_deref(), user.grace_period)
+                .with_context(ctx!(
+                    "Program {name} has an invalid `termination_signal`", ;
+                    "",
+                ))?;
+
+        if let Some(termination) = &termination {
+            if termination.grace_period >= limits.time_limit {
+                bailc!(
+                    "The grace period of program {name} is too long", ;
+                    "The signal would be sent before the program starts, \
+                    the time limit is {:?}", limits.time_limit;
+                    "Make the `grace_period` shorter than the `time_limit`",
+                );
+            }
+        }
+
+        if user.seeds.is_some() && user.repetitions.is_some() {
+            bailc!(
+                "Program {name} has both `seeds` and `repetitions`", ;
+                "A run is created for every seed", ;
+                "Remove `repetitions`, the number of seeds is the number of repetitions",
+            );
+        }
+
+        // The most specific setting is used, seeds and repetitions of a program
+        // override both experiment-wide settings.
+        let seeds = match (&user.seeds, user.repetitions) {
+            (Some(seeds), _) => Some(seeds.values()),
+            (None, Some(_)) => None,
+            (None, None) => conf.seeds.as_ref().map(Seeds::values),
+        };
+
+        let repetitions = match &seeds {
+            Some(seeds) => seeds.len(),
+            None => user
+                .repetitions
+                .or(conf.repetitions)
+                .unwrap_or(REPETITIONS_DEFAULT()),
+        };
+        let warmup = user.warmup.or(conf.warmup).unwrap_or_default();
+
+        user.selector().validate(name)?;
+
+        if repetitions == 0 {
+            bailc!(
+                "Program {name} has no repetitions", ;
+                "Every input of a program has to be run at least once",;
+                "Set `repetitions` to 1 or more, or give at least one seed",
+            );
+        }
 
         for child in &user.next {
-            if !prog.contains_key(child) {
+            if !variants.contains_key(child) {
                 bailc!(
                     "Incorrect program dependency: {}", child;
                     "Program {child} runs on {name}, but there's no program called {child}!",;
@@ -58,7 +169,6 @@ pub fn expand_programs(
             use std::os::unix::fs::PermissionsExt;
 
             use crate::constants::CMD_DOC_STYLE;
-            use crate::ctx;
             if let Some(executable) = user.afterscript.as_ref() {
                 if executable
                     .metadata()
@@ -89,13 +199,21 @@ pub fn expand_programs(
                 .map(|a| canon_path(a, fs))
                 .transpose()?,
             limits,
+            enforced_limits,
+            termination,
+            repetitions,
+            seeds,
+            warmup,
             arguments: user.arguments.clone(),
+            parameters: parameters.clone(),
+            selector: user.selector(),
             next: Vec::new(),
+            env: user.env.clone(),
         });
     }
 
     for out_prog in out.iter_mut() {
-        for next_norm in &prog[&out_prog.name].next {
+        for next_norm in &variants[&out_prog.name].0.next {
             out_prog.next.push(mapper[next_norm]);
         }
     }
@@ -11,6 +11,9 @@ pub mod measurement;
 /// The setup of an experiment.
 pub mod experiment;
 
+/// JSON Schemas of the configuration and the experiment lockfiles.
+pub mod schema;
+
 /// Common file operations
 pub mod file_system;
 
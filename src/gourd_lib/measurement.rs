@@ -2,44 +2,346 @@
 // Licensed under MIT.
 // It exists because we have to modify the behaviour of it.
 
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::time::Duration;
 
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::constants::NAME_STYLE;
 
 /// The metrics of running a program.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(tag = "type")]
+#[allow(clippy::large_enum_variant)]
 pub enum Metrics {
     /// The metrics have not been calculated yet.
     NotCompleted,
 
     /// The measurement has been finished.
     Done(Measurement),
+
+    /// The wrapper failed before it could finish the measurement, for
+    /// example because the input of the run was missing.
+    WrapperFailed {
+        /// The error of the wrapper.
+        reason: String,
+    },
 }
 
 /// This structure contains the measurements for one run of the binary.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 pub struct Measurement {
     /// Interval of wall time.
     pub wall_micros: Duration,
     /// The exit code of the invoked program.
+    ///
+    /// If the program was terminated by a signal this is `128` plus the
+    /// signal number, as reported by shells.
     pub exit_code: i32,
     /// The rusage of the invoked program.
     pub rusage: Option<RUsage>,
+    /// Why the invoked program stopped running.
+    #[serde(default)]
+    pub termination: Termination,
+    /// The resource usage of the cgroup of the run, if it was measured in one.
+    #[serde(default)]
+    pub cgroup: Option<CgroupMetrics>,
+    /// The metrics that the program reported itself, through the file in
+    /// `GOURD_METRICS_FILE`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, MetricValue>,
+    /// The machine that the program ran on.
+    #[serde(default)]
+    pub host: Option<HostInfo>,
+    /// The values extracted from the outputs of the run, stored by gourd the
+    /// first time it read them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extracted: Option<BTreeMap<String, MetricValue>>,
+    /// The labels of the run, stored by gourd together with the extracted
+    /// values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+}
+
+/// The value of a metric reported by a program, or of the metadata of an
+/// input.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum MetricValue {
+    /// `true` or `false`.
+    Bool(bool),
+    /// A whole number.
+    Int(i64),
+    /// A floating point number.
+    Float(f64),
+    /// Any other value.
+    Text(String),
+}
+
+impl MetricValue {
+    /// Parse a value as written by a program, quotes make it text.
+    pub fn parse(value: &str) -> MetricValue {
+        let value = value.trim();
+
+        if let Some(text) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            MetricValue::Text(text.to_string())
+        } else if let Ok(b) = value.parse() {
+            MetricValue::Bool(b)
+        } else if let Ok(i) = value.parse() {
+            MetricValue::Int(i)
+        } else if let Ok(f) = value.parse() {
+            MetricValue::Float(f)
+        } else {
+            MetricValue::Text(value.to_string())
+        }
+    }
+
+    /// The value as a number, if it is one.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            MetricValue::Int(i) => Some(*i as f64),
+            MetricValue::Float(f) => Some(*f),
+            MetricValue::Bool(_) | MetricValue::Text(_) => None,
+        }
+    }
+}
+
+impl Display for MetricValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricValue::Bool(b) => write!(f, "{b}"),
+            MetricValue::Int(i) => write!(f, "{i}"),
+            MetricValue::Float(x) => write!(f, "{x}"),
+            MetricValue::Text(t) => write!(f, "{t}"),
+        }
+    }
+}
+
+/// Parse the `key = value` lines that a program wrote to its metrics file.
+///
+/// Empty lines and lines starting with `#` are skipped, and a key that is
+/// written again overrides the earlier value.
+/// Returns the metrics and the lines that could not be parsed.
+pub fn parse_custom_metrics(text: &str) -> (BTreeMap<String, MetricValue>, Vec<String>) {
+    let mut metrics = BTreeMap::new();
+    let mut invalid = vec![];
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match line.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                metrics.insert(key.trim().to_string(), MetricValue::parse(value));
+            }
+            _ => invalid.push(line.to_string()),
+        }
+    }
+
+    (metrics, invalid)
+}
+
+/// The cause of a program ending.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(tag = "cause")]
+pub enum Termination {
+    /// The program exited by itself.
+    #[default]
+    Exited,
+
+    /// The program was terminated by a signal that the wrapper did not send.
+    Signalled {
+        /// The number of the signal.
+        signal: i32,
+        /// Whether the program produced a core dump.
+        core_dumped: bool,
+    },
+
+    /// The wrapper sent the `termination_signal` of the program before its
+    /// time limit, and the program stopped within the grace period.
+    ///
+    /// The exit code shows whether the program handled the signal.
+    Interrupted {
+        /// The number of the signal.
+        signal: i32,
+    },
+
+    /// The wrapper stopped the program because it exceeded a resource limit.
+    Killed {
+        /// The limit that was exceeded.
+        limit: LimitKind,
+    },
+}
+
+impl Termination {
+    /// Whether the program stopped by itself, or when it was asked to by the
+    /// `termination_signal`.
+    pub fn is_orderly(&self) -> bool {
+        matches!(self, Termination::Exited | Termination::Interrupted { .. })
+    }
+
+    /// The signal that terminated the program, if any.
+    pub fn signal(&self) -> Option<i32> {
+        match self {
+            Termination::Signalled { signal, .. } => Some(*signal),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Termination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Termination::Exited => write!(f, "exited"),
+            Termination::Signalled {
+                signal,
+                core_dumped: false,
+            } => write!(f, "terminated by signal {signal}"),
+            Termination::Signalled {
+                signal,
+                core_dumped: true,
+            } => write!(f, "terminated by signal {signal} (core dumped)"),
+            Termination::Interrupted { signal } => {
+                write!(f, "stopped after signal {signal} before the time limit")
+            }
+            Termination::Killed { limit } => write!(f, "{limit} exceeded"),
+        }
+    }
+}
+
+/// A resource limit that the wrapper enforces on a running program.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum LimitKind {
+    /// The program ran for longer than the `time_limit`.
+    WallTime,
+    /// The program used more CPU time than `time_limit` times `cpus`.
+    CpuTime,
+    /// The program used more memory than `mem_per_cpu` times `cpus`.
+    Memory,
+}
+
+impl Display for LimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitKind::WallTime => write!(f, "time limit"),
+            LimitKind::CpuTime => write!(f, "cpu time limit"),
+            LimitKind::Memory => write!(f, "memory limit"),
+        }
+    }
+}
+
+/// Resource usage of all processes in the cgroup of a run.
+///
+/// Unlike [`RUsage`] this includes processes that the program did not wait
+/// for. Values whose cgroup controller was not available are missing.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default, JsonSchema)]
+pub struct CgroupMetrics {
+    /// The peak memory usage in bytes, from `memory.peak`.
+    pub memory_peak: Option<u64>,
+    /// Total CPU time, from `cpu.stat`.
+    pub cpu_usage: Option<Duration>,
+    /// User CPU time, from `cpu.stat`.
+    pub cpu_user: Option<Duration>,
+    /// System CPU time, from `cpu.stat`.
+    pub cpu_system: Option<Duration>,
+    /// Bytes read from block devices, from `io.stat`.
+    pub io_read_bytes: Option<u64>,
+    /// Bytes written to block devices, from `io.stat`.
+    pub io_write_bytes: Option<u64>,
+}
+
+impl Display for CgroupMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(peak) = self.memory_peak {
+            writeln!(f, "  {NAME_STYLE}peak memory{NAME_STYLE:#}: {peak} bytes")?;
+        }
+        if let Some(usage) = self.cpu_usage {
+            writeln!(
+                f,
+                "  {NAME_STYLE}total cpu time{NAME_STYLE:#}: {}",
+                humantime::Duration::from(usage)
+            )?;
+        }
+        if let (Some(read), Some(write)) = (self.io_read_bytes, self.io_write_bytes) {
+            writeln!(
+                f,
+                "  {NAME_STYLE}io{NAME_STYLE:#}: {read} bytes read, {write} bytes written"
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Information about the machine that a run was executed on.
+///
+/// Values that could not be read on the machine are missing.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+pub struct HostInfo {
+    /// The hostname of the machine.
+    pub hostname: Option<String>,
+    /// The model name of the CPU.
+    pub cpu_model: Option<String>,
+    /// The CPU frequency scaling governor.
+    pub cpu_governor: Option<String>,
+    /// The number of cores available to the run.
+    pub cores: Option<usize>,
+    /// The release of the kernel.
+    pub kernel: Option<String>,
+    /// The 1, 5 and 15 minute load averages when the run started.
+    pub load_average: Option<[f64; 3]>,
+    /// The name of the Slurm node, if the run was scheduled by Slurm.
+    pub slurm_node: Option<String>,
+}
+
+impl HostInfo {
+    /// Whether two runs can be compared, that is they ran on the same CPU
+    /// model with the same number of cores and frequency governor.
+    pub fn same_hardware(&self, other: &HostInfo) -> bool {
+        self.cpu_model == other.cpu_model
+            && self.cores == other.cores
+            && self.cpu_governor == other.cpu_governor
+    }
+}
+
+impl Display for HostInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields = [
+            ("hostname", self.hostname.clone()),
+            ("slurm node", self.slurm_node.clone()),
+            ("cpu", self.cpu_model.clone()),
+            ("governor", self.cpu_governor.clone()),
+            ("cores", self.cores.map(|c| c.to_string())),
+            ("kernel", self.kernel.clone()),
+            (
+                "load average",
+                self.load_average
+                    .map(|[one, five, fifteen]| format!("{one:.2} {five:.2} {fifteen:.2}")),
+            ),
+        ];
+
+        for (name, value) in fields {
+            if let Some(value) = value {
+                writeln!(f, "  {NAME_STYLE}{name}{NAME_STYLE:#}: {value}")?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Resource usage statistics for a process.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
 pub struct RUsage {
     /// User CPU time used.
     pub utime: Duration,
     /// System CPU time used.
     pub stime: Duration,
-    /// Maximum resident set size.
+    /// Maximum resident set size, in KiB.
     pub maxrss: usize,
     /// Integral shared memory size.
     pub ixrss: usize,
@@ -100,3 +402,26 @@ impl Display for RUsage {
         Ok(())
     }
 }
+
+/// The resource usage of the process tree of a run at one point in time.
+///
+/// These are written as rows of the samples file of a run when
+/// `sample_interval` is set.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+pub struct Sample {
+    /// Milliseconds since the program was started.
+    pub time_ms: u64,
+    /// The total resident memory of the processes in bytes.
+    pub rss_bytes: u64,
+    /// The CPU utilisation since the previous sample, where `1.0` is one
+    /// core being busy for the whole interval.
+    pub cpu: f64,
+    /// The total number of threads of the processes.
+    pub threads: u64,
+    /// The number of processes in the tree.
+    pub processes: u64,
+}
+
+#[cfg(test)]
+#[path = "tests/measurement.rs"]
+mod tests;
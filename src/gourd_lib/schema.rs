@@ -0,0 +1,54 @@
+use anyhow::Result;
+use schemars::gen::SchemaSettings;
+use schemars::schema::RootSchema;
+use schemars::schema_for;
+use schemars::JsonSchema;
+
+use crate::config::include::INCLUDE_KEY;
+use crate::config::Config;
+use crate::experiment::Experiment;
+
+/// The JSON Schema of `gourd.toml`, with the doc comments as descriptions.
+pub fn config_schema() -> Result<String> {
+    let mut schema = schema_of::<Config>();
+
+    // The included files are merged before the configuration is parsed, so
+    // `include` is not a field of `Config`.
+    let mut include = schema_for!(Vec<String>).schema;
+    include.metadata().title = None;
+    include.metadata().description = Some(
+        "Files whose settings are merged into this configuration, \
+        relative to this file."
+            .to_string(),
+    );
+
+    schema
+        .schema
+        .object()
+        .properties
+        .insert(INCLUDE_KEY.to_string(), include.into());
+
+    to_json(&schema)
+}
+
+/// The JSON Schema of the experiment lockfiles, with the doc comments as
+/// descriptions.
+pub fn experiment_schema() -> Result<String> {
+    to_json(&schema_of::<Experiment>())
+}
+
+/// The schema of a type, following draft 7 which most editors support.
+fn schema_of<T: JsonSchema>() -> RootSchema {
+    SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<T>()
+}
+
+/// Write a schema as pretty JSON that ends with a newline.
+fn to_json(schema: &RootSchema) -> Result<String> {
+    Ok(format!("{}\n", serde_json::to_string_pretty(schema)?))
+}
+
+#[cfg(test)]
+#[path = "tests/schema.rs"]
+mod tests;
@@ -0,0 +1,117 @@
+use super::*;
+
+#[test]
+fn test_metric_value_parse() {
+    assert_eq!(MetricValue::Int(42), MetricValue::parse(" 42 "));
+    assert_eq!(MetricValue::Float(-1.5), MetricValue::parse("-1.5"));
+    assert_eq!(MetricValue::Bool(true), MetricValue::parse("true"));
+    assert_eq!(
+        MetricValue::Text("42".to_string()),
+        MetricValue::parse("\"42\"")
+    );
+    assert_eq!(
+        MetricValue::Text("optimal".to_string()),
+        MetricValue::parse("optimal")
+    );
+}
+
+#[test]
+fn test_parse_custom_metrics() {
+    let (metrics, invalid) = parse_custom_metrics(
+        "# progress\n\
+         iterations = 10\n\
+         iterations = 20\n\
+         \n\
+         objective=3.25\n\
+         not a metric\n\
+         = 5\n",
+    );
+
+    assert_eq!(
+        BTreeMap::from([
+            ("iterations".to_string(), MetricValue::Int(20)),
+            ("objective".to_string(), MetricValue::Float(3.25)),
+        ]),
+        metrics
+    );
+    assert_eq!(vec!["not a metric", "= 5"], invalid);
+}
+
+#[test]
+fn test_custom_metrics_roundtrip() {
+    let metrics = Metrics::Done(Measurement {
+        wall_micros: Duration::from_secs(1),
+        exit_code: 0,
+        rusage: None,
+        termination: Termination::Exited,
+        cgroup: Some(CgroupMetrics::default()),
+        custom: BTreeMap::from([
+            ("nodes".to_string(), MetricValue::Int(7)),
+            ("gap".to_string(), MetricValue::Float(0.5)),
+            ("optimal".to_string(), MetricValue::Bool(false)),
+            ("solver".to_string(), MetricValue::Text("dfs".to_string())),
+        ]),
+        host: None,
+        extracted: None,
+        labels: None,
+    });
+
+    let text = toml::to_string(&metrics).unwrap();
+
+    assert_eq!(metrics, toml::from_str(&text).unwrap());
+}
+
+#[test]
+fn test_host_info_roundtrip() {
+    let metrics = Metrics::Done(Measurement {
+        wall_micros: Duration::from_secs(1),
+        exit_code: 0,
+        rusage: None,
+        termination: Termination::Exited,
+        cgroup: None,
+        custom: BTreeMap::new(),
+        host: Some(HostInfo {
+            hostname: Some("node7".to_string()),
+            cpu_model: Some("AMD EPYC 7H12 64-Core Processor".to_string()),
+            cpu_governor: None,
+            cores: Some(128),
+            kernel: Some("6.1.0".to_string()),
+            load_average: Some([0.5, 1.25, 2.0]),
+            slurm_node: Some("node7".to_string()),
+        }),
+        extracted: Some(BTreeMap::from([(
+            "score".to_string(),
+            MetricValue::Float(0.5),
+        )])),
+        labels: Some(vec!["fast".to_string()]),
+    });
+
+    let text = toml::to_string(&metrics).unwrap();
+
+    assert_eq!(metrics, toml::from_str(&text).unwrap());
+}
+
+#[test]
+fn test_same_hardware() {
+    let a = HostInfo {
+        hostname: Some("node1".to_string()),
+        cpu_model: Some("Intel Xeon".to_string()),
+        cores: Some(16),
+        load_average: Some([0.0, 0.0, 0.0]),
+        ..Default::default()
+    };
+
+    let b = HostInfo {
+        hostname: Some("node2".to_string()),
+        load_average: Some([3.0, 2.0, 1.0]),
+        ..a.clone()
+    };
+
+    let c = HostInfo {
+        cores: Some(32),
+        ..a.clone()
+    };
+
+    assert!(a.same_hardware(&b));
+    assert!(!a.same_hardware(&c));
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn test_config_schema_is_up_to_date() {
+    assert_eq!(
+        include_str!("../../../docs/schema/gourd.toml.schema.json"),
+        config_schema().unwrap(),
+        "Update the schema with: gourd schema config > docs/schema/gourd.toml.schema.json"
+    );
+}
+
+#[test]
+fn test_experiment_schema_is_up_to_date() {
+    assert_eq!(
+        include_str!("../../../docs/schema/experiment.schema.json"),
+        experiment_schema().unwrap(),
+        "Update the schema with: gourd schema experiment > docs/schema/experiment.schema.json"
+    );
+}
+
+#[test]
+fn test_config_schema_describes_fields() {
+    let schema: serde_json::Value = serde_json::from_str(&config_schema().unwrap()).unwrap();
+    let properties = &schema["properties"];
+
+    assert_eq!(false, schema["additionalProperties"]);
+    assert_eq!(
+        "The path to a folder where the experiment output will be stored.",
+        properties["output_path"]["description"]
+    );
+    assert_eq!("array", properties["include"]["type"]);
+    assert!(properties.get("variables").is_none());
+
+    // durations are written as text, like "1min 30s"
+    assert_eq!(
+        "string",
+        schema["definitions"]["ResourceLimits"]["properties"]["time_limit"]["type"]
+    );
+}
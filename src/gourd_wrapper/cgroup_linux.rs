@@ -0,0 +1,298 @@
+#![cfg(target_os = "linux")]
+
+use std::ffi::CString;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::process::CommandExt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use anyhow::Context;
+use anyhow::Result;
+use gourd_lib::constants::WRAPPER_KILL_GRACE_PERIOD;
+use gourd_lib::constants::WRAPPER_POLL_PERIOD;
+use gourd_lib::measurement::CgroupMetrics;
+
+/// The controllers that the metrics are read from.
+const CONTROLLERS: [&str; 3] = ["memory", "cpu", "io"];
+
+/// A cgroup v2 created for a single run.
+///
+/// The controllers of a cgroup can only be enabled for its children if it has
+/// no processes itself, so the wrapper creates a cgroup with two leaves: one
+/// that it moves itself into, and one for the run.
+///
+/// The controllers that are enabled for the cgroup of the wrapper stay
+/// enabled, since other wrappers below it may use them.
+#[derive(Debug)]
+pub struct Cgroup {
+    /// The directory of the leaf cgroup of the run.
+    path: PathBuf,
+
+    /// The directory of the cgroup that holds the leaves.
+    container: PathBuf,
+
+    /// The directory of the cgroup that the wrapper started in.
+    parent: PathBuf,
+}
+
+impl Cgroup {
+    /// Create a cgroup for a run, below the cgroup of the wrapper.
+    ///
+    /// Fails if cgroup v2 is not mounted or the cgroup of the wrapper has not
+    /// been delegated to the user.
+    pub fn create() -> Result<Cgroup> {
+        let parent = own_cgroup()?;
+
+        remove_stale(&parent);
+
+        let container = parent.join(format!("gourd-{}", std::process::id()));
+
+        fs::create_dir(&container)
+            .with_context(|| format!("Could not create the cgroup {container:?}"))?;
+
+        let cgroup = Cgroup {
+            path: container.join("run"),
+            container,
+            parent,
+        };
+
+        if let Err(e) = cgroup.populate() {
+            cgroup.remove();
+            return Err(e);
+        }
+
+        Ok(cgroup)
+    }
+
+    /// Move the wrapper into its own leaf, enable the controllers and create
+    /// the leaf of the run.
+    fn populate(&self) -> Result<()> {
+        // Moving a process requires write access to both cgroups.
+        for procs in [
+            self.parent.join("cgroup.procs"),
+            self.container.join("cgroup.procs"),
+        ] {
+            if !writable(&procs) {
+                return Err(anyhow!("Cannot move processes through {procs:?}"));
+            }
+        }
+
+        let wrapper = self.container.join("wrapper");
+        fs::create_dir(&wrapper)
+            .with_context(|| format!("Could not create the cgroup {wrapper:?}"))?;
+        fs::write(wrapper.join("cgroup.procs"), "0")
+            .with_context(|| format!("Could not move the wrapper to the cgroup {wrapper:?}"))?;
+
+        // Best effort, the parent may still have other processes in it.
+        let available = fs::read_to_string(self.container.join("cgroup.controllers"))?;
+        for controller in CONTROLLERS {
+            if !available.split_whitespace().any(|c| c == controller) {
+                let _ = fs::write(
+                    self.parent.join("cgroup.subtree_control"),
+                    format!("+{controller}"),
+                );
+            }
+        }
+
+        let available = fs::read_to_string(self.container.join("cgroup.controllers"))?;
+        for controller in available.split_whitespace() {
+            let _ = fs::write(
+                self.container.join("cgroup.subtree_control"),
+                format!("+{controller}"),
+            );
+        }
+
+        fs::create_dir(&self.path)
+            .with_context(|| format!("Could not create the cgroup {:?}", self.path))?;
+
+        Ok(())
+    }
+
+    /// Make the command start in this cgroup.
+    pub fn attach(&self, cmd: &mut Command) -> Result<()> {
+        let procs = CString::new(self.path.join("cgroup.procs").as_os_str().as_bytes())
+            .context("The cgroup path contains a null byte")?;
+
+        // SAFETY: The closure runs in the forked child before `exec`, where only
+        // async-signal-safe functions may be called. `open`, `write` and `close`
+        // are, and the path was allocated before forking.
+        unsafe {
+            cmd.pre_exec(move || {
+                let fd = libc::open(procs.as_ptr(), libc::O_WRONLY);
+                if fd < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+
+                // Writing 0 moves the writing process.
+                let written = libc::write(fd, b"0".as_ptr().cast(), 1);
+                libc::close(fd);
+
+                if written != 1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+
+                Ok(())
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Kill the processes left in the cgroup, read its metrics and remove it.
+    ///
+    /// This has to be called once the program itself has exited.
+    pub fn finish(self) -> CgroupMetrics {
+        // The cgroup can only be removed once its processes are gone.
+        let deadline = Instant::now() + WRAPPER_KILL_GRACE_PERIOD;
+
+        while self.populated() && Instant::now() < deadline {
+            self.kill();
+            thread::sleep(WRAPPER_POLL_PERIOD);
+        }
+
+        let cpu = fs::read_to_string(self.path.join("cpu.stat")).unwrap_or_default();
+        let io = fs::read_to_string(self.path.join("io.stat")).ok();
+
+        let metrics = CgroupMetrics {
+            memory_peak: fs::read_to_string(self.path.join("memory.peak"))
+                .ok()
+                .and_then(|peak| peak.trim().parse().ok()),
+            cpu_usage: stat_field(&cpu, "usage_usec").map(Duration::from_micros),
+            cpu_user: stat_field(&cpu, "user_usec").map(Duration::from_micros),
+            cpu_system: stat_field(&cpu, "system_usec").map(Duration::from_micros),
+            io_read_bytes: io.as_deref().map(|io| io_total(io, "rbytes")),
+            io_write_bytes: io.as_deref().map(|io| io_total(io, "wbytes")),
+        };
+
+        self.remove();
+
+        metrics
+    }
+
+    /// Check if there are any processes left in the cgroup.
+    fn populated(&self) -> bool {
+        fs::read_to_string(self.path.join("cgroup.events"))
+            .is_ok_and(|events| events.lines().any(|l| l == "populated 1"))
+    }
+
+    /// Kill all processes in the cgroup.
+    fn kill(&self) {
+        // `cgroup.kill` is only available since Linux 5.14.
+        if fs::write(self.path.join("cgroup.kill"), "1").is_ok() {
+            return;
+        }
+
+        let procs = fs::read_to_string(self.path.join("cgroup.procs")).unwrap_or_default();
+
+        for pid in procs.lines().filter_map(|p| p.parse::<libc::pid_t>().ok()) {
+            // SAFETY: The processes are in the cgroup of this run, they belong
+            // to the program.
+            unsafe {
+                libc::kill(pid, libc::SIGKILL);
+            }
+        }
+    }
+
+    /// Remove the cgroup of the run, and move the wrapper back to where it
+    /// started and remove its cgroups if it can.
+    ///
+    /// A process cannot move into a cgroup that has controllers enabled for
+    /// its children, then the wrapper stays in its own leaf, and the cgroups
+    /// are removed by a later wrapper once this one has exited.
+    fn remove(&self) {
+        let _ = fs::remove_dir(&self.path);
+
+        if fs::write(self.parent.join("cgroup.procs"), "0").is_ok() {
+            let _ = fs::remove_dir(self.container.join("wrapper"));
+            let _ = fs::remove_dir(&self.container);
+        }
+    }
+}
+
+/// Find the directory of the cgroup v2 that the wrapper is in.
+fn own_cgroup() -> Result<PathBuf> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo")?;
+
+    // The mount point is the fifth field, the filesystem type follows the
+    // separator.
+    let mount = mountinfo
+        .lines()
+        .find_map(|line| {
+            let (fields, fs_type) = line.split_once(" - ")?;
+            fs_type
+                .starts_with("cgroup2 ")
+                .then(|| fields.split_whitespace().nth(4))
+                .flatten()
+        })
+        .ok_or(anyhow!("cgroup v2 is not mounted"))?;
+
+    let cgroups = fs::read_to_string("/proc/self/cgroup")?;
+
+    let own = cgroups
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .ok_or(anyhow!("The wrapper is not in a cgroup v2"))?;
+
+    Ok(Path::new(mount).join(own.trim_start_matches('/')))
+}
+
+/// Remove the cgroups below `parent` of wrappers that have exited without
+/// being able to remove them.
+fn remove_stale(parent: &Path) {
+    let Ok(entries) = fs::read_dir(parent) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_prefix("gourd-"))
+            .and_then(|pid| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        if Path::new("/proc").join(pid.to_string()).exists() {
+            continue;
+        }
+
+        // Removing fails for cgroups that still have processes, which are
+        // then left alone.
+        let container = entry.path();
+        let _ = fs::remove_dir(container.join("run"));
+        let _ = fs::remove_dir(container.join("wrapper"));
+        let _ = fs::remove_dir(&container);
+    }
+}
+
+/// Check if the current user may write to a file.
+fn writable(path: &Path) -> bool {
+    CString::new(path.as_os_str().as_bytes()).is_ok_and(|path| {
+        // SAFETY: The path is a valid null terminated string.
+        unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 }
+    })
+}
+
+/// Get a field of a flat keyed file such as `cpu.stat`.
+fn stat_field(stat: &str, key: &str) -> Option<u64> {
+    stat.lines().find_map(|line| {
+        let (k, v) = line.split_once(' ')?;
+        (k == key).then(|| v.trim().parse().ok()).flatten()
+    })
+}
+
+/// Sum a field of the nested keyed `io.stat` over all devices.
+fn io_total(stat: &str, key: &str) -> u64 {
+    stat.split_whitespace()
+        .filter_map(|entry| entry.split_once('='))
+        .filter(|(k, _)| *k == key)
+        .filter_map(|(_, v)| v.parse::<u64>().ok())
+        .sum()
+}
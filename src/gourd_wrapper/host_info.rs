@@ -0,0 +1,85 @@
+use std::env;
+use std::fs;
+use std::thread;
+
+use gourd_lib::measurement::HostInfo;
+
+/// Collect information about the machine, this is done before the program
+/// starts so that the load average is not influenced by it.
+pub fn collect() -> HostInfo {
+    #[cfg(unix)]
+    let (hostname, kernel) = uname();
+    #[cfg(not(unix))]
+    let (hostname, kernel) = (env::var("COMPUTERNAME").ok(), None);
+
+    HostInfo {
+        hostname,
+        cpu_model: cpu_model(),
+        cpu_governor: read_trimmed("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+        cores: thread::available_parallelism().map(usize::from).ok(),
+        kernel,
+        load_average: load_average(),
+        slurm_node: env::var("SLURMD_NODENAME").ok(),
+    }
+}
+
+/// Read a file and remove the surrounding whitespace.
+fn read_trimmed(path: &str) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+/// Find the name of the CPU in `/proc/cpuinfo`.
+fn cpu_model() -> Option<String> {
+    fs::read_to_string("/proc/cpuinfo")
+        .ok()?
+        .lines()
+        .find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            (key.trim() == "model name").then(|| value.trim().to_string())
+        })
+}
+
+/// The hostname and kernel release.
+#[cfg(unix)]
+fn uname() -> (Option<String>, Option<String>) {
+    use std::ffi::CStr;
+    use std::mem::MaybeUninit;
+
+    let mut name = MaybeUninit::<libc::utsname>::uninit();
+
+    // SAFETY: `uname` only writes to the struct that it is given.
+    if unsafe { libc::uname(name.as_mut_ptr()) } != 0 {
+        return (None, None);
+    }
+
+    // SAFETY: `uname` succeeded, so the struct is initialised and its fields
+    // are null terminated.
+    let name = unsafe { name.assume_init() };
+    let field = |chars: &[libc::c_char]| {
+        // SAFETY: see above.
+        unsafe { CStr::from_ptr(chars.as_ptr()) }
+            .to_str()
+            .ok()
+            .map(str::to_string)
+    };
+
+    (field(&name.nodename), field(&name.release))
+}
+
+/// The 1, 5 and 15 minute load averages.
+fn load_average() -> Option<[f64; 3]> {
+    #[cfg(unix)]
+    {
+        let mut loads = [0.0; 3];
+
+        // SAFETY: the array has room for the three requested values.
+        if unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) } == 3 {
+            return Some(loads);
+        }
+    }
+
+    None
+}
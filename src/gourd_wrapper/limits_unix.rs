@@ -0,0 +1,389 @@
+#![cfg(unix)]
+
+use std::os::unix::process::CommandExt;
+use std::process::Child;
+use std::process::Command;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::anyhow;
+use anyhow::Context;
+use anyhow::Result;
+use gourd_lib::config::limits::EnforcedLimits;
+use gourd_lib::config::slurm::ResourceLimits;
+use gourd_lib::config::termination::GracefulTermination;
+use gourd_lib::constants::WRAPPER_KILL_GRACE_PERIOD;
+use gourd_lib::constants::WRAPPER_POLL_PERIOD;
+use gourd_lib::measurement::LimitKind;
+
+/// The total amount of memory in bytes that a run may use.
+fn memory_limit_bytes(limits: &ResourceLimits) -> u64 {
+    (limits.mem_per_cpu * limits.cpus) as u64 * 1024 * 1024
+}
+
+/// The total amount of CPU time in seconds that a run may use.
+fn cpu_limit_secs(limits: &ResourceLimits) -> u64 {
+    (limits.time_limit.as_secs_f64() * limits.cpus as f64).ceil() as u64
+}
+
+/// Prepare a command so that the kernel enforces the limits it can.
+///
+/// The program is put in its own process group, so that the [`Watchdog`]
+/// can terminate everything it has spawned.
+/// The CPU time limit is applied with `RLIMIT_CPU`, the kernel sends `SIGXCPU`
+/// once it is reached.
+/// On systems without `/proc` the memory limit is applied with `RLIMIT_AS`.
+/// Only the `enforced` limits are applied.
+pub fn apply_limits(cmd: &mut Command, limits: &ResourceLimits, enforced: EnforcedLimits) {
+    cmd.process_group(0);
+
+    if !enforced.any() {
+        return;
+    }
+
+    // One second of leeway, so that a program that is busy for its entire time
+    // limit is reported as exceeding the wall clock time rather than the CPU time.
+    let cpu_soft = (cpu_limit_secs(limits) + 1) as libc::rlim_t;
+    let cpu_hard = cpu_soft + WRAPPER_KILL_GRACE_PERIOD.as_secs() as libc::rlim_t;
+
+    #[cfg(not(target_os = "linux"))]
+    let memory = memory_limit_bytes(limits) as libc::rlim_t;
+
+    // SAFETY: The closure runs in the forked child before `exec`, where only
+    // async-signal-safe functions may be called. `setrlimit` is one of them,
+    // and the closure does not allocate.
+    unsafe {
+        cmd.pre_exec(move || {
+            let cpu = libc::rlimit {
+                rlim_cur: cpu_soft,
+                rlim_max: cpu_hard,
+            };
+
+            if enforced.time_limit && libc::setrlimit(libc::RLIMIT_CPU, &cpu) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+
+            #[cfg(not(target_os = "linux"))]
+            if enforced.mem_per_cpu {
+                let mem = libc::rlimit {
+                    rlim_cur: memory,
+                    rlim_max: memory,
+                };
+
+                if libc::setrlimit(libc::RLIMIT_AS, &mem) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+
+            Ok(())
+        });
+    }
+}
+
+/// Wait for a program to exit without reaping it.
+pub fn wait_for_exit(child: &Child) -> Result<()> {
+    // SAFETY: `siginfo_t` is a plain C struct for which all zeroes is valid.
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+
+    // SAFETY: `info` is a valid pointer for the duration of the call.
+    let result = unsafe {
+        libc::waitid(
+            libc::P_PID,
+            child.id() as libc::id_t,
+            &mut info,
+            libc::WEXITED | libc::WNOWAIT,
+        )
+    };
+
+    if result != 0 {
+        return Err(std::io::Error::last_os_error())
+            .context("Could not wait for the program to exit");
+    }
+
+    Ok(())
+}
+
+/// Check whether a program was stopped by the kernel for using too much CPU
+/// time, given the signal it was terminated by.
+pub fn cpu_limit_exceeded(signal: i32) -> bool {
+    signal == libc::SIGXCPU
+}
+
+/// Set when the wrapper itself receives the termination signal, for example
+/// from Slurm shortly before the job reaches its time limit.
+static TERMINATION_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// The signal handler of the wrapper for the termination signal.
+extern "C" fn request_termination(_: libc::c_int) {
+    TERMINATION_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// The number of a signal, given its name without the `SIG` prefix.
+fn signal_number(name: &str) -> Option<libc::c_int> {
+    Some(match name {
+        "HUP" => libc::SIGHUP,
+        "INT" => libc::SIGINT,
+        "QUIT" => libc::SIGQUIT,
+        "ABRT" => libc::SIGABRT,
+        "USR1" => libc::SIGUSR1,
+        "USR2" => libc::SIGUSR2,
+        "ALRM" => libc::SIGALRM,
+        "TERM" => libc::SIGTERM,
+        "XCPU" => libc::SIGXCPU,
+        _ => return None,
+    })
+}
+
+/// When and how a program is asked to stop before its time limit.
+#[derive(Debug, Clone, Copy)]
+pub struct TerminationSignal {
+    /// The signal to send.
+    signal: libc::c_int,
+
+    /// How long after the start of the program the signal is sent, if its
+    /// time limit is enforced.
+    after: Option<Duration>,
+
+    /// How long the program has after the signal before it is killed.
+    grace_period: Duration,
+}
+
+impl TerminationSignal {
+    /// Prepare the graceful termination of a program with this time limit,
+    /// if the user set one.
+    ///
+    /// If the wrapper itself receives the signal, it is passed on to the
+    /// program right away, this is how `#SBATCH --signal` reaches it.
+    pub fn install(
+        termination: &GracefulTermination,
+        time_limit: Option<Duration>,
+    ) -> Result<Self> {
+        let signal = signal_number(&termination.signal)
+            .ok_or(anyhow!("Unknown termination signal {}", termination.signal))?;
+
+        // SAFETY: `sigaction` is a plain C struct for which all zeroes is valid.
+        let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+        action.sa_sigaction = request_termination as extern "C" fn(libc::c_int) as usize;
+        // Waiting for the program should not be interrupted by the signal.
+        action.sa_flags = libc::SA_RESTART;
+
+        // SAFETY: The handler only stores to an atomic, which is
+        // async-signal-safe. Handled signals are reset to their default
+        // action when the program is executed.
+        if unsafe { libc::sigaction(signal, &action, std::ptr::null_mut()) } != 0 {
+            return Err(std::io::Error::last_os_error())
+                .context("Could not handle the termination signal");
+        }
+
+        Ok(TerminationSignal {
+            signal,
+            after: time_limit.map(|limit| termination.signal_after(limit)),
+            grace_period: termination.grace_period,
+        })
+    }
+}
+
+/// What the [`Watchdog`] did to a program.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Intervention {
+    /// The limit that the program exceeded, if any.
+    pub exceeded: Option<LimitKind>,
+
+    /// The termination signal, if it was sent.
+    pub signalled: Option<i32>,
+}
+
+/// A thread that watches a running program and stops it once it exceeds the
+/// wall clock time or memory limit, if they are enforced.
+#[derive(Debug)]
+pub struct Watchdog {
+    /// Dropping or sending on this tells the thread that the program exited.
+    stop: Sender<()>,
+
+    /// The thread, returns what it did to the program.
+    handle: JoinHandle<Intervention>,
+}
+
+impl Watchdog {
+    /// Start watching the process with this `pid`.
+    pub fn start(
+        pid: u32,
+        limits: ResourceLimits,
+        enforced: EnforcedLimits,
+        termination: Option<TerminationSignal>,
+    ) -> Watchdog {
+        let (stop, receiver) = channel();
+
+        let handle = thread::spawn(move || {
+            watch(pid as libc::pid_t, limits, enforced, termination, receiver)
+        });
+
+        Watchdog { stop, handle }
+    }
+
+    /// Stop watching, this has to be called before the program is reaped so
+    /// that its process id cannot be reused while it is being signalled.
+    ///
+    /// Returns what the watchdog did to the program.
+    pub fn stop(self) -> Intervention {
+        // The thread may have already returned and dropped the receiver.
+        let _ = self.stop.send(());
+
+        self.handle.join().unwrap_or_default()
+    }
+}
+
+/// The body of the [`Watchdog`] thread.
+fn watch(
+    pid: libc::pid_t,
+    limits: ResourceLimits,
+    enforced: EnforcedLimits,
+    termination: Option<TerminationSignal>,
+    stop: Receiver<()>,
+) -> Intervention {
+    let start = Instant::now();
+    let mut intervention = Intervention::default();
+    let mut signalled_at = None;
+
+    loop {
+        match stop.recv_timeout(WRAPPER_POLL_PERIOD) {
+            Err(RecvTimeoutError::Timeout) => {}
+            _ => return intervention,
+        }
+
+        if let Some(termination) = termination.filter(|_| intervention.signalled.is_none()) {
+            if termination
+                .after
+                .is_some_and(|after| start.elapsed() >= after)
+                || TERMINATION_REQUESTED.load(Ordering::SeqCst)
+            {
+                // SAFETY: The process has not been reaped yet, so the process
+                // group id still belongs to the program.
+                unsafe {
+                    libc::kill(-pid, termination.signal);
+                }
+
+                intervention.signalled = Some(termination.signal);
+                signalled_at = Some(Instant::now());
+            }
+        }
+
+        let exceeded = if enforced.time_limit && start.elapsed() >= limits.time_limit {
+            Some(LimitKind::WallTime)
+        } else if enforced.mem_per_cpu
+            && group_memory(pid).is_some_and(|rss| rss > memory_limit_bytes(&limits))
+        {
+            Some(LimitKind::Memory)
+        } else {
+            None
+        };
+
+        match exceeded {
+            // The grace period has already passed.
+            Some(LimitKind::WallTime) if intervention.signalled.is_some() => {
+                // SAFETY: As above.
+                unsafe {
+                    libc::kill(-pid, libc::SIGKILL);
+                }
+            }
+            Some(_) => terminate(pid, &stop),
+            None => {
+                // A program that was asked to stop is killed once its grace
+                // period has passed, also if it has no time limit.
+                if let (Some(at), Some(termination)) = (signalled_at, termination) {
+                    if at.elapsed() >= termination.grace_period {
+                        // SAFETY: As above.
+                        unsafe {
+                            libc::kill(-pid, libc::SIGKILL);
+                        }
+
+                        return intervention;
+                    }
+                }
+
+                continue;
+            }
+        }
+
+        intervention.exceeded = exceeded;
+        return intervention;
+    }
+}
+
+/// Send `SIGTERM` to the process group, and `SIGKILL` if it is still alive
+/// after the grace period.
+fn terminate(pid: libc::pid_t, stop: &Receiver<()>) {
+    // SAFETY: The process has not been reaped yet, so the process group id
+    // still belongs to the program.
+    unsafe {
+        libc::kill(-pid, libc::SIGTERM);
+    }
+
+    if let Err(RecvTimeoutError::Timeout) = stop.recv_timeout(WRAPPER_KILL_GRACE_PERIOD) {
+        // SAFETY: As above, the watchdog is stopped before the program is reaped.
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+}
+
+/// The resident set size in bytes of all processes in the process group
+/// `pgid`, so that a program cannot escape its limit by forking.
+#[cfg(target_os = "linux")]
+fn group_memory(pgid: libc::pid_t) -> Option<u64> {
+    // SAFETY: `sysconf` has no preconditions.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
+
+    let mut pages = 0;
+
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|x| x.parse().ok()) else {
+            continue;
+        };
+
+        if process_group(pid) == Some(pgid) {
+            pages += resident_pages(pid).unwrap_or(0);
+        }
+    }
+
+    Some(pages * page_size)
+}
+
+/// The process group of a process.
+#[cfg(target_os = "linux")]
+fn process_group(pid: libc::pid_t) -> Option<libc::pid_t> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+
+    // The name in parentheses may contain spaces, the state, parent and
+    // process group follow it.
+    stat.rsplit_once(')')?
+        .1
+        .split_whitespace()
+        .nth(2)?
+        .parse()
+        .ok()
+}
+
+/// The number of resident pages of a process.
+#[cfg(target_os = "linux")]
+fn resident_pages(pid: libc::pid_t) -> Option<u64> {
+    let statm = std::fs::read_to_string(format!("/proc/{pid}/statm")).ok()?;
+
+    statm.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// The resident set size in bytes of all processes in a process group.
+///
+/// Without `/proc` the memory is limited by `RLIMIT_AS` instead.
+#[cfg(not(target_os = "linux"))]
+fn group_memory(_: libc::pid_t) -> Option<u64> {
+    None
+}
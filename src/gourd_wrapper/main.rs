@@ -8,12 +8,22 @@
 //!
 //! as arguments, the wrapper will then perform the experiment.
 
+/// Measurements with cgroup v2 on Linux.
+mod cgroup_linux;
+/// Information about the machine that the run is executed on.
+mod host_info;
+/// Resource limit enforcement for unix-like systems.
+mod limits_unix;
 /// Measurements for unix-like systems.
 mod measurement_unix;
+/// Sampling of resource usage over time on Linux.
+mod sampler_linux;
 
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::fs::File;
+use std::path::Path;
 use std::path::PathBuf;
 use std::process::exit;
 use std::process::Command;
@@ -25,13 +35,23 @@ use anstyle::Style;
 use anyhow::bail;
 use anyhow::Context;
 use anyhow::Result;
+use gourd_lib::config::limits::EnforcedLimits;
+use gourd_lib::config::slurm::ResourceLimits;
+use gourd_lib::config::termination::GracefulTermination;
+use gourd_lib::config::MeasurementOptions;
+use gourd_lib::constants::CUSTOM_METRICS_ENV;
+use gourd_lib::constants::SEED_ENV;
+use gourd_lib::constants::WRAPPER_FAILED_EXIT_CODE;
 use gourd_lib::ctx;
 use gourd_lib::experiment::Experiment;
 use gourd_lib::file_system::FileOperations;
 use gourd_lib::file_system::FileSystemInteractor;
+use gourd_lib::measurement::parse_custom_metrics;
 use gourd_lib::measurement::Measurement;
+use gourd_lib::measurement::MetricValue;
 use gourd_lib::measurement::Metrics;
 use gourd_lib::measurement::RUsage;
+use gourd_lib::measurement::Termination;
 
 /// How to style the errors.
 const ERROR_STYLE: Style = anstyle::Style::new()
@@ -56,32 +76,60 @@ struct RunConf {
     output_path: PathBuf,
     /// The path to the result file.
     result_path: PathBuf,
+    /// The path to the samples file.
+    samples_path: PathBuf,
+    /// The path where the program can write its own metrics.
+    custom_metrics_path: PathBuf,
     /// The path to the stderr file.
     err_path: PathBuf,
     /// Additional arguments.
     additional_args: Vec<String>,
+    /// The seed of the run.
+    seed: u64,
+    /// The resource limits of the run.
+    limits: ResourceLimits,
+    /// The limits that the user set, only these are enforced.
+    enforced: EnforcedLimits,
+    /// How the program is asked to stop before its time limit.
+    termination: Option<GracefulTermination>,
+    /// How to measure the run.
+    measurement: MeasurementOptions,
+    /// The environment variables to set.
+    env: BTreeMap<String, String>,
+    /// Whether the run gets only the variables in `env`.
+    clean_env: bool,
+    /// How many times to run the program before the measured run.
+    warmup: usize,
 }
 
 fn main() {
-    if let Err(err) = process() {
+    let args: Vec<String> = env::args().collect();
+
+    if let Err(err) = process(&args) {
+        // If the failure is in the metrics file, gourd shows it as the status of
+        // the run instead of leaving it pending.
+        let recorded = record_failure(&args, &err).is_ok();
+
         eprintln!("{ERROR_STYLE}error:{ERROR_STYLE:#} {err}");
         eprintln!(
             "{ERROR_STYLE}caused by:{ERROR_STYLE:#} {}",
             err.root_cause()
         );
         eprintln!("{HELP_STYLE}help:{HELP_STYLE:#} The gourd_wrapper program is internal. You should not be invoking it manually" );
-        exit(1);
+        exit(if recorded {
+            WRAPPER_FAILED_EXIT_CODE
+        } else {
+            1
+        });
     }
 }
 
 /// Internal part of the wrapper.
-fn process() -> Result<()> {
-    let args: Vec<String> = env::args().collect();
-
+fn process(args: &[String]) -> Result<()> {
     let fs = FileSystemInteractor { dry_run: false };
 
     let rc = match args.len() {
-        4 => process_args(&args, &fs)?,
+        4 => process_args(args, &fs)?,
         _ => bail!("gourd_wrapper needs an experiment file path, a chunk index and a task index"),
     };
 
@@ -95,22 +143,61 @@ fn process() -> Result<()> {
         rc.result_path
     ))?;
 
+    let host = host_info::collect();
+
+    // The warmups run in this job, so that they warm up the machine of the
+    // measured run and are done before it starts.
+    for warmup in 0..rc.warmup {
+        eprintln!("WARMUP {} OF {}", warmup + 1, rc.warmup);
+        warm_up(&rc)?;
+    }
+
+    // A file left over from an earlier attempt or a warmup would be mistaken
+    // for metrics.
+    let _ = fs::remove_file(&rc.custom_metrics_path);
+
     let clock = start_measuring();
 
     eprintln!("RUNNING {:?}", &rc.binary_path);
     eprintln!("ARGS {:?}", &rc.additional_args);
-    #[allow(unused_mut)]
-    let mut child = Command::new(&rc.binary_path)
-        .current_dir(&rc.work_dir)
-        .args(&rc.additional_args)
-        .stdin(if let Some(actual_input) = rc.input_path.clone() {
-            Stdio::from(
-                File::open(actual_input.clone())
-                    .context(format!("Could not open the input {actual_input:?}"))?,
+    let mut cmd = program_command(&rc);
+
+    #[cfg(unix)]
+    let termination_signal = rc
+        .termination
+        .as_ref()
+        .map(|t| {
+            crate::limits_unix::TerminationSignal::install(
+                t,
+                rc.enforced.time_limit.then_some(rc.limits.time_limit),
             )
-        } else {
-            Stdio::null()
         })
+        .transpose()?;
+
+    #[cfg(not(unix))]
+    if rc.termination.is_some() {
+        eprintln!("TERMINATION SIGNALS ARE ONLY AVAILABLE ON UNIX");
+    }
+
+    #[cfg(target_os = "linux")]
+    let cgroup = if rc.measurement.cgroup {
+        match crate::cgroup_linux::Cgroup::create() {
+            Ok(cgroup) => {
+                cgroup.attach(&mut cmd)?;
+                Some(cgroup)
+            }
+            Err(e) => {
+                eprintln!("CGROUP UNAVAILABLE, FALLING BACK TO RUSAGE: {e:#}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    #[allow(unused_mut)]
+    let mut child = cmd
+        .stdin(input(&rc)?)
         .stdout(Stdio::from(File::create(rc.output_path.clone()).context(
             format!("Could not truncate the output {:?}", rc.output_path),
         )?))
@@ -120,23 +207,91 @@ fn process() -> Result<()> {
         .spawn()
         .context(format!("Could not start the binary {:?}", &rc.binary_path))?;
 
+    #[cfg(target_os = "linux")]
+    let sampler = rc.measurement.sample_interval.and_then(|interval| {
+        crate::sampler_linux::Sampler::start(child.id(), interval, &rc.samples_path)
+            .inspect_err(|e| eprintln!("COULD NOT START SAMPLING: {e:#}"))
+            .ok()
+    });
+
+    #[cfg(not(target_os = "linux"))]
+    if rc.measurement.sample_interval.is_some() {
+        eprintln!("SAMPLING IS ONLY AVAILABLE ON LINUX");
+    }
+
     #[cfg(not(unix))]
-    let (rusage_output, exit_code) = (
+    let (rusage_output, exit_code, termination) = (
         None,
         child
             .wait()?
             .code()
             .context("Failed to retrieve the exit code")?,
+        Termination::Exited,
     );
     #[cfg(unix)]
-    let (rusage_output, exit_code) = {
+    let (rusage_output, exit_code, termination) = {
+        use gourd_lib::measurement::LimitKind;
+
+        use crate::limits_unix::*;
         use crate::measurement_unix::GetRUsage;
-        child
+
+        let watchdog = Watchdog::start(child.id(), rc.limits, rc.enforced, termination_signal);
+
+        wait_for_exit(&child)?;
+
+        let intervention = watchdog.stop();
+
+        #[cfg(target_os = "linux")]
+        if let Some(Err(e)) = sampler.map(|sampler| sampler.stop()) {
+            eprintln!("COULD NOT RECORD THE SAMPLES: {e:#}");
+        }
+
+        let exit = child
             .wait_for_rusage()
-            .context("Could not rusage the child")?
+            .context("Could not rusage the child")?;
+
+        let termination = match (intervention, exit.signal) {
+            (
+                Intervention {
+                    exceeded: Some(limit),
+                    ..
+                },
+                _,
+            ) => Termination::Killed { limit },
+            (
+                Intervention {
+                    signalled: Some(signal),
+                    ..
+                },
+                _,
+            ) => Termination::Interrupted { signal },
+            (_, Some((signal, _))) if cpu_limit_exceeded(signal) => Termination::Killed {
+                limit: LimitKind::CpuTime,
+            },
+            (_, Some((signal, core_dumped))) => Termination::Signalled {
+                signal,
+                core_dumped,
+            },
+            (_, None) => Termination::Exited,
+        };
+
+        (Some(exit.rusage), exit.exit_code, termination)
     };
 
-    let meas = stop_measuring(clock, exit_code, rusage_output);
+    let meas = stop_measuring(clock, exit_code, rusage_output, termination);
+
+    // Stopping the leftover processes is not part of the wall clock time.
+    #[cfg(target_os = "linux")]
+    let meas = Measurement {
+        cgroup: cgroup.map(|cgroup| cgroup.finish()),
+        ..meas
+    };
+
+    let meas = Measurement {
+        custom: read_custom_metrics(&rc.custom_metrics_path),
+        host: Some(host),
+        ..meas
+    };
 
     fs::write(
         &rc.result_path,
@@ -150,6 +305,71 @@ fn process() -> Result<()> {
     Ok(())
 }
 
+/// The command that runs the program of `rc`, without its input and output.
+fn program_command(rc: &RunConf) -> Command {
+    let mut cmd = Command::new(&rc.binary_path);
+
+    if rc.clean_env {
+        cmd.env_clear();
+    }
+
+    cmd.envs(&rc.env);
+    cmd.env(CUSTOM_METRICS_ENV, &rc.custom_metrics_path);
+    cmd.env(SEED_ENV, rc.seed.to_string());
+
+    #[cfg(unix)]
+    crate::limits_unix::apply_limits(&mut cmd, &rc.limits, rc.enforced);
+
+    cmd.current_dir(&rc.work_dir).args(&rc.additional_args);
+
+    cmd
+}
+
+/// The input of the program of `rc` on its stdin.
+fn input(rc: &RunConf) -> Result<Stdio> {
+    Ok(if let Some(actual_input) = rc.input_path.clone() {
+        Stdio::from(
+            File::open(actual_input.clone())
+                .context(format!("Could not open the input {actual_input:?}"))?,
+        )
+    } else {
+        Stdio::null()
+    })
+}
+
+/// Run the program of `rc` once without measuring it, and discard its output.
+///
+/// The limits of the run apply to the warmup too. A warmup that fails does not
+/// stop the measured run.
+fn warm_up(rc: &RunConf) -> Result<()> {
+    #[allow(unused_mut)]
+    let mut child = program_command(rc)
+        .stdin(input(rc)?)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context(format!("Could not start the binary {:?}", &rc.binary_path))?;
+
+    #[cfg(unix)]
+    {
+        use crate::limits_unix::*;
+
+        let watchdog = Watchdog::start(child.id(), rc.limits, rc.enforced, None);
+
+        wait_for_exit(&child)?;
+
+        watchdog.stop();
+    }
+
+    let status = child.wait().context("Could not wait for the warmup")?;
+
+    if !status.success() {
+        eprintln!("WARMUP FAILED: {status}");
+    }
+
+    Ok(())
+}
+
 /// Process the command line arguments passed to the wrapper.
 fn process_args(args: &[String], fs: &impl FileOperations) -> Result<RunConf> {
     let exp_path: PathBuf = args[1]
@@ -172,24 +392,99 @@ fn process_args(args: &[String], fs: &impl FileOperations) -> Result<RunConf> {
         "Ensure that Slurm is configured correctly",
     ))?;
 
-    let run = exp.runs[exp.chunks[chunk_id][task_id]].clone();
+    let run_id = exp.chunks[chunk_id][task_id];
+    let run = exp.runs[run_id].clone();
 
     let program = &exp.get_program(&run)?;
 
-    let mut additional_args = program.arguments.clone();
-    additional_args.append(&mut run.input.args.clone());
+    let additional_args = exp.run_arguments(run_id)?;
+
+    // An input that is passed as an argument is not also passed on stdin.
+    let input_path = if exp.input_in_arguments(run_id)? {
+        None
+    } else {
+        run.input.file.clone()
+    };
+
+    let samples_path = run.samples_path();
+    let custom_metrics_path = run.custom_metrics_path();
 
     Ok(RunConf {
         binary_path: program.binary.clone().to_path_buf(),
-        input_path: run.input.file,
+        input_path,
         output_path: run.output_path.clone(),
         result_path: run.metrics_path.clone(),
+        samples_path,
+        custom_metrics_path,
         work_dir: run.work_dir.clone(),
         err_path: run.err_path.clone(),
         additional_args,
+        seed: run.seed_or_replicate(),
+        limits: run.limits,
+        enforced: run.enforced_limits,
+        termination: program.termination.clone(),
+        measurement: exp.measurement,
+        env: run.env,
+        clean_env: exp.clean_env,
+        // Postprocessing is not warmed up.
+        warmup: if run.parent.is_none() {
+            program.warmup
+        } else {
+            0
+        },
     })
 }
 
+/// Write the failure of the wrapper to the metrics file of the run.
+///
+/// The experiment is read without its schema, so that the run can be found
+/// even if the experiment file could not be parsed as a whole.
+fn record_failure(args: &[String], err: &anyhow::Error) -> Result<()> {
+    let [_, exp_path, chunk_id, task_id] = args else {
+        bail!("The run is unknown");
+    };
+
+    let exp: toml::Value = toml::from_str(&fs::read_to_string(exp_path)?)?;
+
+    let run_id = exp
+        .get("chunks")
+        .and_then(|chunks| chunks.get(chunk_id.parse::<usize>().ok()?))
+        .and_then(|chunk| chunk.get(task_id.parse::<usize>().ok()?))
+        .and_then(toml::Value::as_integer)
+        .context("The run is not in the experiment")?;
+
+    let metrics_path = exp
+        .get("runs")
+        .and_then(|runs| runs.get(usize::try_from(run_id).ok()?))
+        .and_then(|run| run.get("metrics_path"))
+        .and_then(toml::Value::as_str)
+        .context("The run has no metrics path")?;
+
+    fs::write(
+        metrics_path,
+        toml::to_string(&Metrics::WrapperFailed {
+            reason: format!("{err:#}"),
+        })?,
+    )?;
+
+    Ok(())
+}
+
+/// Read the metrics that the program wrote to its metrics file, if any.
+fn read_custom_metrics(path: &Path) -> BTreeMap<String, MetricValue> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+
+    let (metrics, invalid) = parse_custom_metrics(&text);
+
+    for line in invalid {
+        eprintln!("IGNORED METRIC LINE {line:?}, EXPECTED key = value");
+    }
+
+    metrics
+}
+
 /// This is an extensible structure for measuring monotonic metrics.
 struct Clock {
     /// The real-world time this program took to execute.
@@ -204,10 +499,21 @@ fn start_measuring() -> Clock {
 }
 
 /// Stop a measurement, returns a new instance of a [Measurement]
-fn stop_measuring(clk: Clock, exit_code: i32, rusage: Option<RUsage>) -> Measurement {
+fn stop_measuring(
+    clk: Clock,
+    exit_code: i32,
+    rusage: Option<RUsage>,
+    termination: Termination,
+) -> Measurement {
     Measurement {
         wall_micros: clk.wall_time.elapsed(),
         exit_code,
         rusage,
+        termination,
+        cgroup: None,
+        custom: BTreeMap::new(),
+        host: None,
+        extracted: None,
+        labels: None,
     }
 }
@@ -9,7 +9,10 @@ use std::time::Duration;
 
 use anyhow::Error;
 use gourd_lib::measurement::RUsage;
-use libc::WIFEXITED;
+use libc::WCOREDUMP;
+use libc::WEXITSTATUS;
+use libc::WIFSIGNALED;
+use libc::WTERMSIG;
 
 /// Returns an empty `libc::rusage` struct.
 unsafe fn empty_raw_rusage() -> libc::rusage {
@@ -17,7 +20,7 @@ unsafe fn empty_raw_rusage() -> libc::rusage {
 }
 
 impl GetRUsage for Child {
-    fn wait_for_rusage(&self) -> Result<(Option<RUsage>, i32), Error> {
+    fn wait_for_rusage(&self) -> Result<ExitInfo, Error> {
         let pid = self.id() as i32;
         let mut status: i32 = 0;
 
@@ -29,42 +32,59 @@ impl GetRUsage for Child {
         // This should be safe as long as libc is correctly loaded.
         unsafe {
             rusage = empty_raw_rusage();
-            libc::wait4(pid, addr_of_mut!(status), 0i32, addr_of_mut!(rusage));
+            if libc::wait4(pid, addr_of_mut!(status), 0i32, addr_of_mut!(rusage)) < 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
         }
 
-        if WIFEXITED(status) {
-            Ok((
-                Some(RUsage {
-                    utime: duration_from_timeval(rusage.ru_utime),
-                    stime: duration_from_timeval(rusage.ru_stime),
-                    maxrss: rusage.ru_maxrss as usize,
-                    ixrss: rusage.ru_ixrss as usize,
-                    idrss: rusage.ru_idrss as usize,
-                    isrss: rusage.ru_isrss as usize,
-                    minflt: rusage.ru_minflt as usize,
-                    majflt: rusage.ru_majflt as usize,
-                    nswap: rusage.ru_nswap as usize,
-                    inblock: rusage.ru_inblock as usize,
-                    oublock: rusage.ru_oublock as usize,
-                    msgsnd: rusage.ru_msgsnd as usize,
-                    msgrcv: rusage.ru_msgrcv as usize,
-                    nsignals: rusage.ru_nsignals as usize,
-                    nvcsw: rusage.ru_nvcsw as usize,
-                    nivcsw: rusage.ru_nivcsw as usize,
-                }),
-                status,
-            ))
+        let (exit_code, signal) = if WIFSIGNALED(status) {
+            let signal = WTERMSIG(status);
+            (128 + signal, Some((signal, WCOREDUMP(status))))
         } else {
-            Ok((None, -33))
-        }
+            (WEXITSTATUS(status), None)
+        };
+
+        Ok(ExitInfo {
+            rusage: RUsage {
+                utime: duration_from_timeval(rusage.ru_utime),
+                stime: duration_from_timeval(rusage.ru_stime),
+                maxrss: rusage.ru_maxrss as usize,
+                ixrss: rusage.ru_ixrss as usize,
+                idrss: rusage.ru_idrss as usize,
+                isrss: rusage.ru_isrss as usize,
+                minflt: rusage.ru_minflt as usize,
+                majflt: rusage.ru_majflt as usize,
+                nswap: rusage.ru_nswap as usize,
+                inblock: rusage.ru_inblock as usize,
+                oublock: rusage.ru_oublock as usize,
+                msgsnd: rusage.ru_msgsnd as usize,
+                msgrcv: rusage.ru_msgrcv as usize,
+                nsignals: rusage.ru_nsignals as usize,
+                nvcsw: rusage.ru_nvcsw as usize,
+                nivcsw: rusage.ru_nivcsw as usize,
+            },
+            exit_code,
+            signal,
+        })
     }
 }
 
+/// How a process ended, and the resources it used.
+#[derive(Debug, Clone, Copy)]
+pub struct ExitInfo {
+    /// The resource usage of the process.
+    pub rusage: RUsage,
+    /// The exit code, or `128` plus the signal number if it was signalled.
+    pub exit_code: i32,
+    /// The signal that terminated the process and whether it dumped core.
+    pub signal: Option<(i32, bool)>,
+}
+
 /// A trait for getting resource usage statistics for a process.
 pub trait GetRUsage {
     /// Waits for the process to exit and returns its resource usage statistics.
     /// Works only on linux with wait4 syscall available.
-    fn wait_for_rusage(&self) -> Result<(Option<RUsage>, i32), Error>;
+    fn wait_for_rusage(&self) -> Result<ExitInfo, Error>;
 }
 
 /// Converts a `libc::timeval` to a `std::time::Duration`.
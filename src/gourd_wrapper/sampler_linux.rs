@@ -0,0 +1,207 @@
+#![cfg(target_os = "linux")]
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Context;
+use anyhow::Result;
+use gourd_lib::measurement::Sample;
+
+/// A thread that periodically records the resource usage of a running
+/// program and all of its descendants.
+#[derive(Debug)]
+pub struct Sampler {
+    /// Dropping or sending on this tells the thread that the program exited.
+    stop: Sender<()>,
+
+    /// The thread, returns whether the samples could be written.
+    handle: JoinHandle<Result<()>>,
+}
+
+impl Sampler {
+    /// Start sampling the process with this `pid` every `interval`, writing
+    /// the samples to `path`.
+    pub fn start(pid: u32, interval: Duration, path: &Path) -> Result<Sampler> {
+        let writer = csv::Writer::from_writer(
+            File::create(path).with_context(|| format!("Could not create {path:?}"))?,
+        );
+
+        let (stop, receiver) = channel();
+
+        let handle = thread::spawn(move || sample(pid, interval, writer, receiver));
+
+        Ok(Sampler { stop, handle })
+    }
+
+    /// Stop sampling, this has to be called before the program is reaped so
+    /// that its process id cannot be reused while it is being sampled.
+    pub fn stop(self) -> Result<()> {
+        let _ = self.stop.send(());
+
+        self.handle
+            .join()
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("The sampler thread panicked")))
+    }
+}
+
+/// A process as seen in `/proc/<pid>/stat`.
+#[derive(Debug, Clone, Copy)]
+struct ProcStat {
+    /// The parent process id.
+    ppid: i32,
+    /// The process group id.
+    pgrp: i32,
+    /// User and system CPU time in clock ticks.
+    cpu_ticks: u64,
+}
+
+/// The body of the [`Sampler`] thread.
+fn sample(
+    pid: u32,
+    interval: Duration,
+    mut writer: csv::Writer<File>,
+    stop: Receiver<()>,
+) -> Result<()> {
+    let root = pid as i32;
+    let start = Instant::now();
+
+    // SAFETY: `sysconf` has no preconditions.
+    let ticks_per_sec = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as f64;
+
+    let mut previous_ticks = BTreeMap::new();
+    let mut previous_time = start;
+
+    loop {
+        let now = Instant::now();
+        let tree = process_tree(root);
+
+        // Only count the CPU time used since the previous sample. Processes
+        // that exited in between are not accounted for.
+        let ticks: u64 = tree
+            .iter()
+            .map(|(pid, stat)| {
+                stat.cpu_ticks
+                    .saturating_sub(previous_ticks.get(pid).copied().unwrap_or(0))
+            })
+            .sum();
+
+        let elapsed = now.duration_since(previous_time).as_secs_f64();
+
+        let (rss_bytes, threads) = tree
+            .keys()
+            .filter_map(|pid| status(*pid))
+            .fold((0, 0), |(rss, threads), (r, t)| (rss + r, threads + t));
+
+        writer.serialize(Sample {
+            time_ms: now.duration_since(start).as_millis() as u64,
+            rss_bytes,
+            cpu: if elapsed > 0.0 {
+                ticks as f64 / ticks_per_sec / elapsed
+            } else {
+                0.0
+            },
+            threads,
+            processes: tree.len() as u64,
+        })?;
+
+        previous_ticks = tree.iter().map(|(pid, s)| (*pid, s.cpu_ticks)).collect();
+        previous_time = now;
+
+        match stop.recv_timeout(interval) {
+            Err(RecvTimeoutError::Timeout) => {}
+            _ => break,
+        }
+    }
+
+    writer.flush().context("Could not write the samples")?;
+
+    Ok(())
+}
+
+/// Find the program and all processes it has spawned.
+///
+/// This includes the descendants of the program and the processes in its
+/// process group, which keeps processes whose parent has already exited.
+fn process_tree(root: i32) -> BTreeMap<i32, ProcStat> {
+    let all: BTreeMap<i32, ProcStat> = fs::read_dir("/proc")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+        .filter_map(|pid| Some((pid, stat(pid)?)))
+        .collect();
+
+    let mut tree: BTreeSet<i32> = all
+        .iter()
+        .filter(|(pid, stat)| **pid == root || stat.pgrp == root)
+        .map(|(pid, _)| *pid)
+        .collect();
+
+    // Add children until nothing changes, the process table is small enough.
+    loop {
+        let before = tree.len();
+
+        for (pid, stat) in &all {
+            if tree.contains(&stat.ppid) {
+                tree.insert(*pid);
+            }
+        }
+
+        if tree.len() == before {
+            break;
+        }
+    }
+
+    all.into_iter()
+        .filter(|(pid, _)| tree.contains(pid))
+        .collect()
+}
+
+/// Read the parent, group and CPU time of a process from `/proc/<pid>/stat`.
+fn stat(pid: i32) -> Option<ProcStat> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+
+    // The name of the program can contain spaces and parentheses, the other
+    // fields follow the last parenthesis starting with the state.
+    let (_, fields) = stat.rsplit_once(')')?;
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+
+    Some(ProcStat {
+        ppid: fields.get(1)?.parse().ok()?,
+        pgrp: fields.get(2)?.parse().ok()?,
+        cpu_ticks: fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?,
+    })
+}
+
+/// Read the resident memory in bytes and the thread count of a process from
+/// `/proc/<pid>/status`.
+fn status(pid: i32) -> Option<(u64, u64)> {
+    let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+
+    let field = |name: &str| {
+        status.lines().find_map(|line| {
+            line.strip_prefix(name)?
+                .split_whitespace()
+                .next()?
+                .parse::<u64>()
+                .ok()
+        })
+    };
+
+    // Zombies have no memory left.
+    Some((
+        field("VmRSS:").unwrap_or(0) * 1024,
+        field("Threads:").unwrap_or(0),
+    ))
+}
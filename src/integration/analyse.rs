@@ -1,6 +1,9 @@
+use gourd_lib::measurement::Sample;
+
 use crate::config;
 use crate::gourd;
 use crate::init;
+use crate::read_experiment_from_stdout;
 
 #[test]
 fn test_analyse_csv() {
@@ -39,3 +42,58 @@ fn test_analyse_csv_file() {
 
     assert!(out_path.exists());
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_analyse_samples() {
+    let env = init();
+
+    let (conf, conf_path) = config(&env, "./src/integration/configurations/samples.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(),
+        "run", "local", "-s"; "run local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+    let samples: Vec<Sample> = csv::Reader::from_path(exp.runs[0].samples_path())
+        .unwrap()
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    // the program is busy until it is killed after a second
+    assert!(samples.len() > 5);
+    assert!(samples.iter().any(|s| s.rss_bytes > 0 && s.processes == 1));
+
+    let out_path = conf.experiments_folder.join("samples.svg");
+    let _ = gourd!(env; "-c", conf_path.to_str().unwrap(),
+        "analyse", "samples", "--per=program", "-f", "svg", "-o", out_path.to_str().unwrap();
+        "analyse samples");
+
+    assert!(out_path.exists());
+}
+
+#[test]
+fn test_analyse_custom_metrics() {
+    let env = init();
+
+    let (conf, conf_path) =
+        config(&env, "./src/integration/configurations/custom_metrics.toml").unwrap();
+
+    let _ = gourd!(env; "-c", conf_path.to_str().unwrap(),
+        "run", "local", "-s"; "run local");
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(),
+        "analyse", "table", "-s", "--format=program", "--metrics=nodes,solver"; "analyse csv");
+
+    let table = std::str::from_utf8(&output.stdout).unwrap();
+    assert!(table.contains("10"));
+    assert!(table.contains("30"));
+    assert!(table.contains("dfs"));
+
+    let out_path = conf.experiments_folder.join("objective.png");
+    let _ = gourd!(env; "-c", conf_path.to_str().unwrap(),
+        "analyse", "plot", "--metric=objective", "-o", out_path.to_str().unwrap();
+        "analyse plot");
+
+    assert!(out_path.exists());
+}
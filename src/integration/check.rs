@@ -0,0 +1,37 @@
+use crate::config;
+use crate::gourd;
+use crate::init;
+
+#[test]
+fn test_check_summarises_valid_config() {
+    let env = init();
+
+    let (_conf, conf_path) = config(&env, "./src/integration/configurations/seeds.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "check"; "check");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("The experiment would have 2 runs"));
+    assert!(stdout.contains("print_args | 2"));
+
+    // Checking does not create the experiment.
+    assert!(!env.temp_dir.path().join("experiments").exists());
+}
+
+#[test]
+fn test_check_reports_every_problem() {
+    let env = init();
+
+    let (_conf, conf_path) =
+        config(&env, "./src/integration/configurations/mistakes.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "check");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("Incorrect program dependency: missing"));
+    assert!(stderr.contains("fast_fib -> fibonacci -> fast_fib"));
+    assert!(stderr.contains("Program hello has no repetitions"));
+    assert!(stderr.contains("Parameter specified incorrectly"));
+    assert!(stderr.contains("Found 4 problems"));
+}
@@ -8,6 +8,7 @@
 //!
 //! + [x] Test the `gourd --version` command.
 //! + [x] Test the `gourd run` command.
+//! + [x] Test the `gourd check` command.
 //! + [x] Test the `gourd init` command.
 //! + [x] Test the `gourd status` command.
 //! + [x] Test the `gourd rerun` command.
@@ -24,6 +25,7 @@
 
 mod afterscript;
 mod analyse;
+mod check;
 mod example;
 mod init_example;
 mod init_interactive;
@@ -141,6 +143,16 @@ fn new_program(
             afterscript: None,
             next: post.map(|p| vec![p.to_string()]).unwrap_or_default(),
             resource_limits: None,
+            repetitions: None,
+            seeds: None,
+            termination_signal: None,
+            grace_period: None,
+            warmup: None,
+            env: BTreeMap::new(),
+            inputs: None,
+            exclude_inputs: vec![],
+            groups: None,
+            exclude_groups: vec![],
         },
     );
 }
@@ -218,6 +230,42 @@ fn init() -> TestEnv {
         None,
     );
 
+    new_program(
+        &mut programs,
+        "print_env",
+        &p,
+        include_str!("programs/print_env.rs"),
+        vec![],
+        None,
+    );
+
+    new_program(
+        &mut programs,
+        "print_args",
+        &p,
+        include_str!("programs/print_args.rs"),
+        vec![],
+        None,
+    );
+
+    new_program(
+        &mut programs,
+        "report_metrics",
+        &p,
+        include_str!("programs/report_metrics.rs"),
+        vec![],
+        None,
+    );
+
+    new_program(
+        &mut programs,
+        "count_executions",
+        &p,
+        include_str!("programs/count_executions.rs"),
+        vec![],
+        None,
+    );
+
     // finally, construct the test environment
     TestEnv {
         gourd_path,
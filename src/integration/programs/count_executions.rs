@@ -0,0 +1,16 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+
+/// Counts how many times it was executed in the working directory.
+fn main() {
+    let mut executions = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open("executions")
+        .unwrap();
+
+    writeln!(executions, "run").unwrap();
+
+    let count = std::fs::read_to_string("executions").unwrap().lines().count();
+    println!("{count}");
+}
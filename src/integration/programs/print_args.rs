@@ -0,0 +1,16 @@
+// This file does NOT belong in a module.
+// It is a resource compiled independently for the integration tests.
+#![allow(unused)]
+
+use std::io::Read;
+
+/// Print the arguments, one per line, followed by what was passed on stdin.
+fn main() {
+    for arg in std::env::args().skip(1) {
+        println!("{arg}");
+    }
+
+    let mut stdin = String::new();
+    std::io::stdin().read_to_string(&mut stdin).unwrap();
+    println!("stdin: {}", stdin.trim());
+}
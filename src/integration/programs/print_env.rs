@@ -0,0 +1,10 @@
+// This file does NOT belong in a module.
+// It is a resource compiled independently for the integration tests.
+#![allow(unused)]
+
+/// Print the environment variables, one per line.
+fn main() {
+    for (key, value) in std::env::vars() {
+        println!("{key}={value}");
+    }
+}
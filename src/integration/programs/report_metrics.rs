@@ -0,0 +1,15 @@
+// This file does NOT belong in a module.
+// It is a resource compiled independently for the integration tests.
+#![allow(unused)]
+
+/// Report some metrics through the file that the wrapper provides.
+fn main() {
+    let path = std::env::var("GOURD_METRICS_FILE").expect("No metrics file provided");
+    let nodes: u64 = std::env::args().nth(1).unwrap().parse().unwrap();
+
+    std::fs::write(
+        path,
+        format!("nodes = {nodes}\nobjective = {}\nsolver = \"dfs\"\n", nodes as f64 / 4.0),
+    )
+    .unwrap();
+}
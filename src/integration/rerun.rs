@@ -1,7 +1,9 @@
 // use std::io::Read;
 // use std::io::Write;
 // use std::process::Stdio;
+use gourd_lib::config::limits::EnforcedLimits;
 use std::string::String;
+use std::time::Duration;
 
 use crate::config;
 use crate::gourd;
@@ -44,6 +46,35 @@ fn test_two_one_run() {
     assert_eq!(exp.runs.len(), 2);
 }
 
+#[test]
+fn test_rerun_keeps_input_limits() {
+    let env = init();
+    let (_conf, conf_path) =
+        config(&env, "./src/integration/configurations/input_limits.toml").unwrap();
+
+    let output = gourd!(&env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let _ =
+        gourd!(&env; "-c", conf_path.to_str().unwrap(), "rerun", "-s", "-r", "0"; "rerun local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+    assert_eq!(exp.runs.len(), 2);
+
+    // the limits of the input are not replaced by those of the program
+    assert_eq!(Duration::from_secs(120), exp.runs[1].limits.time_limit);
+    assert_eq!(exp.runs[0].limits, exp.runs[1].limits);
+
+    // only the time limit is set for the input, so the default memory limit
+    // is not enforced
+    assert_eq!(
+        EnforcedLimits {
+            time_limit: true,
+            mem_per_cpu: false,
+        },
+        exp.runs[1].enforced_limits
+    );
+}
+
 // Not necessary for what we're currently working on (12/10/2024),
 // and the issue is with the test (specifically the faketty), not gourd.
 // Uncomment and fix in due time.
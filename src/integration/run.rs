@@ -1,3 +1,17 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use gourd_lib::constants::WRAPPER_FAILED_EXIT_CODE;
+use gourd_lib::experiment::Experiment;
+use gourd_lib::file_system::FileOperations;
+use gourd_lib::measurement::LimitKind;
+use gourd_lib::measurement::Metrics;
+use gourd_lib::measurement::Termination;
+
 use crate::config;
 use crate::gourd;
 use crate::init;
@@ -26,3 +40,287 @@ fn test_dry_one_run() {
     // check that the output file does not exist
     assert!(read_experiment_from_stdout(&output).is_err());
 }
+
+#[test]
+fn test_time_limit_enforced_locally() {
+    let env = init();
+
+    let (_conf, conf_path) =
+        config(&env, "./src/integration/configurations/time_limit.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+    let metrics: Metrics = env.fs.try_read_toml(&exp.runs[0].metrics_path).unwrap();
+
+    match metrics {
+        Metrics::Done(m) => assert_eq!(
+            Termination::Killed {
+                limit: LimitKind::WallTime
+            },
+            m.termination
+        ),
+        m => panic!("the run did not complete: {m:?}"),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_termination_signal_before_time_limit() {
+    let env = init();
+
+    let (_conf, conf_path) =
+        config(&env, "./src/integration/configurations/termination.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+    let metrics: Metrics = env.fs.try_read_toml(&exp.runs[0].metrics_path).unwrap();
+
+    match metrics {
+        // the program does not handle the signal, so it stops right away
+        Metrics::Done(m) => {
+            // SIGTERM is 15
+            assert_eq!(Termination::Interrupted { signal: 15 }, m.termination);
+            assert_eq!(128 + 15, m.exit_code);
+            assert!(m.wall_micros < Duration::from_secs(3));
+        }
+        m => panic!("the run did not complete: {m:?}"),
+    }
+}
+
+/// The cgroup v2 of this process, if it may be written to, which the wrapper
+/// needs to create cgroups for the runs.
+fn delegated_cgroup() -> Option<PathBuf> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo").ok()?;
+    let mount = mountinfo.lines().find_map(|line| {
+        let (fields, fs_type) = line.split_once(" - ")?;
+        fs_type
+            .starts_with("cgroup2 ")
+            .then(|| fields.split_whitespace().nth(4))
+            .flatten()
+    })?;
+
+    let cgroups = fs::read_to_string("/proc/self/cgroup").unwrap_or_default();
+    let own = cgroups.lines().find_map(|line| line.strip_prefix("0::"))?;
+
+    let cgroup = Path::new(mount).join(own.trim_start_matches('/'));
+
+    fs::OpenOptions::new()
+        .write(true)
+        .open(cgroup.join("cgroup.procs"))
+        .is_ok()
+        .then_some(cgroup)
+}
+
+#[test]
+fn test_cgroup_measurement() {
+    let Some(own) = delegated_cgroup() else {
+        eprintln!("Skipping the cgroup measurement, cgroup v2 is not delegated to this user");
+        return;
+    };
+
+    // the cgroups left behind by a wrapper that could not remove them
+    let stale = own.join(format!("gourd-{}", u32::MAX));
+    fs::create_dir_all(stale.join("wrapper")).unwrap();
+
+    let env = init();
+
+    let (_conf, conf_path) = config(&env, "./src/integration/configurations/cgroup.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+    let metrics: Metrics = env.fs.try_read_toml(&exp.runs[0].metrics_path).unwrap();
+
+    match metrics {
+        Metrics::Done(m) => {
+            let cgroup = m.cgroup.expect("the run was not measured in a cgroup");
+
+            // the cpu time is accounted for every cgroup, whatever its controllers
+            assert!(cgroup.cpu_usage.is_some_and(|t| t > Duration::ZERO));
+            assert!(cgroup.cpu_user.is_some());
+            assert!(cgroup.cpu_system.is_some());
+        }
+        m => panic!("the run did not complete: {m:?}"),
+    }
+
+    // the wrapper of the stale cgroups has exited, so they are removed
+    assert!(!stale.exists());
+}
+
+#[test]
+fn test_run_environment() {
+    let env = init();
+
+    let (_conf, conf_path) = config(&env, "./src/integration/configurations/env.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+    let run = &exp.runs[0];
+
+    // the most specific value is used, and recorded in the lockfile together
+    // with the whitelisted variables
+    assert_eq!(
+        BTreeMap::from([
+            ("A".to_string(), "global".to_string()),
+            ("B".to_string(), "program".to_string()),
+            ("C".to_string(), "input".to_string()),
+            ("PATH".to_string(), std::env::var("PATH").unwrap()),
+        ]),
+        run.env
+    );
+
+    let stdout = fs::read_to_string(&run.output_path).unwrap();
+    let mut keys: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.split_once('=').map(|(k, _)| k))
+        .collect();
+    keys.sort();
+
+    assert_eq!(
+        vec!["A", "B", "C", "GOURD_METRICS_FILE", "GOURD_SEED", "PATH"],
+        keys
+    );
+    assert!(stdout.contains("C=input"));
+    assert!(stdout.contains("GOURD_SEED=0"));
+}
+
+#[test]
+fn test_seeds() {
+    let env = init();
+
+    let (_conf, conf_path) = config(&env, "./src/integration/configurations/seeds.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+
+    assert_eq!(
+        vec![Some(11), Some(22)],
+        exp.runs.iter().map(|r| r.seed).collect::<Vec<_>>()
+    );
+
+    for run in &exp.runs {
+        let stdout = fs::read_to_string(&run.output_path).unwrap();
+        assert!(stdout.starts_with(&format!("--seed={}\n", run.seed.unwrap())));
+    }
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "status", "-i", "1"; "status");
+    let status = String::from_utf8(output.stdout).unwrap();
+    assert!(status.contains("seed") && status.contains("22"));
+}
+
+#[test]
+fn test_warmups_run_before_every_run() {
+    let env = init();
+
+    let (_conf, conf_path) = config(&env, "./src/integration/configurations/warmup.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+
+    // the warmups are not runs, the wrapper executes them in the working
+    // directory of every run before it is measured
+    assert_eq!(2, exp.runs.len());
+
+    for run in &exp.runs {
+        let stdout = fs::read_to_string(&run.output_path).unwrap();
+        assert_eq!("3", stdout.trim());
+    }
+}
+
+#[test]
+fn test_host_information_recorded() {
+    let env = init();
+
+    let (_conf, conf_path) =
+        config(&env, "./src/integration/configurations/single_run.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+    let metrics: Metrics = env.fs.try_read_toml(&exp.runs[0].metrics_path).unwrap();
+
+    match metrics {
+        Metrics::Done(m) => {
+            let host = m.host.expect("the host information is recorded");
+            assert!(host.cores.is_some_and(|cores| cores > 0));
+            #[cfg(unix)]
+            assert!(host.hostname.is_some() && host.kernel.is_some());
+        }
+        m => panic!("the run did not complete: {m:?}"),
+    }
+}
+
+#[test]
+fn test_wrapper_failure_recorded() {
+    let env = init();
+
+    let (_conf, conf_path) =
+        config(&env, "./src/integration/configurations/single_run.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let mut exp = read_experiment_from_stdout(&output).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let exp_path = stdout.lines().last().unwrap();
+
+    // the input disappears before the wrapper runs again
+    exp.runs[0].input.file = Some(env.temp_dir.path().join("missing"));
+    fs::write(exp_path, toml::to_string(&exp).unwrap()).unwrap();
+
+    let wrapper = Command::new(&env.wrapper_path)
+        .args([exp_path, "0", "0"])
+        .output()
+        .unwrap();
+
+    assert_eq!(Some(WRAPPER_FAILED_EXIT_CODE), wrapper.status.code());
+
+    let metrics: Metrics = env.fs.try_read_toml(&exp.runs[0].metrics_path).unwrap();
+
+    match metrics {
+        Metrics::WrapperFailed { reason } => assert!(reason.contains("missing")),
+        m => panic!("the failure was not recorded: {m:?}"),
+    }
+
+    // the run is failed, so it is rerun
+    let _ = gourd!(env; "-c", conf_path.to_str().unwrap(), "rerun", "-s"; "rerun");
+
+    let exp: Experiment = env.fs.try_read_toml(Path::new(exp_path)).unwrap();
+    assert_eq!(2, exp.runs.len());
+    assert_eq!(Some(1), exp.runs[0].rerun);
+}
+
+#[test]
+fn test_argument_placeholders() {
+    let env = init();
+
+    let (_conf, conf_path) =
+        config(&env, "./src/integration/configurations/placeholders.toml").unwrap();
+
+    let output = gourd!(env; "-c", conf_path.to_str().unwrap(), "run", "local", "-s"; "run local");
+
+    let exp = read_experiment_from_stdout(&output).unwrap();
+    let run = &exp.runs[0];
+    let input = run.input.file.as_ref().unwrap();
+
+    let stdout = fs::read_to_string(&run.output_path).unwrap();
+
+    assert_eq!(
+        vec![
+            "--run=0".to_string(),
+            "--seed=0".to_string(),
+            format!("--instance={}", input.display()),
+            format!("--name={}", run.generated_from_input.as_ref().unwrap()),
+            format!("--out={}/sol.txt", run.work_dir.display()),
+            "--x=7".to_string(),
+            "{literal}".to_string(),
+            // the input is passed as an argument instead
+            "stdin: ".to_string(),
+        ],
+        stdout.lines().collect::<Vec<_>>()
+    );
+}